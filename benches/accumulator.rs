@@ -0,0 +1,52 @@
+use std::hint::black_box;
+use std::time::Instant;
+
+use modicum::{AddMod, ModAccumulator, MulMod, SplitMix64};
+
+const LEN: usize = 1 << 20;
+const ROUNDS: usize = 20;
+
+fn main() {
+    let modulus = 1_000_000_007u32;
+    let mut rng = SplitMix64::new(0);
+    let values: Vec<u32> = (0..LEN).map(|_| rng.next_u64() as u32).collect();
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        let sum = black_box(&values)
+            .iter()
+            .fold(0u32, |acc, &x| acc.add_mod(x % modulus, modulus));
+        black_box(sum);
+    }
+    println!("add_mod fold:                {:?}", start.elapsed());
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        let mut accumulator = ModAccumulator::<u32>::new(modulus);
+        for &x in black_box(&values) {
+            accumulator.add(x);
+        }
+        black_box(accumulator.finish());
+    }
+    println!("ModAccumulator::add:         {:?}", start.elapsed());
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        let sum = black_box(&values).chunks(2).fold(0u64, |acc, pair| {
+            let product = (pair[0] as u64).mul_mod(pair[1] as u64, modulus as u64);
+            acc.add_mod(product, modulus as u64)
+        });
+        black_box(sum);
+    }
+    println!("mul_mod + add_mod fold:      {:?}", start.elapsed());
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        let mut accumulator = ModAccumulator::<u32>::new(modulus);
+        for pair in black_box(&values).chunks(2) {
+            accumulator.add_product(pair[0], pair[1]);
+        }
+        black_box(accumulator.finish());
+    }
+    println!("ModAccumulator::add_product: {:?}", start.elapsed());
+}
@@ -0,0 +1,83 @@
+use std::hint::black_box;
+use std::time::Instant;
+
+use modicum::{add_mod_slice, constrain_slice, mul_mod_slice, Barrett, SplitMix64};
+
+const LEN: usize = 1 << 20;
+const ROUNDS: usize = 20;
+
+fn time(name: &str, mut f: impl FnMut()) {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        f();
+    }
+    println!("{name:<32} {:?}", start.elapsed());
+}
+
+fn main() {
+    let mut rng = SplitMix64::new(0);
+    let raw32: Vec<u32> = (0..LEN).map(|_| rng.next_u64() as u32).collect();
+    let raw64: Vec<u64> = (0..LEN).map(|_| rng.next_u64()).collect();
+
+    let m32 = black_box(998_244_353u32);
+    let barrett32 = Barrett::<u32>::new(m32).unwrap();
+    let mut a32 = raw32.clone();
+    constrain_slice(&mut a32, m32);
+    let b32: Vec<u32> = a32.iter().rev().copied().collect();
+    let m64 = black_box(1_000_000_007u64);
+    let barrett64 = Barrett::<u64>::new(m64).unwrap();
+    let mut a64 = raw64.clone();
+    constrain_slice(&mut a64, m64);
+    let b64: Vec<u64> = a64.iter().rev().copied().collect();
+
+    let mut values = raw32.clone();
+    time("u32 constrain_slice", || {
+        values.copy_from_slice(&raw32);
+        constrain_slice(black_box(&mut values), m32);
+    });
+    time("u32 Barrett::constrain_slice", || {
+        values.copy_from_slice(&raw32);
+        barrett32.constrain_slice(black_box(&mut values));
+    });
+    let mut values = a32.clone();
+    time("u32 add_mod_slice", || {
+        add_mod_slice(black_box(&mut values), &b32, m32)
+    });
+    time("u32 Barrett::add_mod_slice", || {
+        barrett32.add_mod_slice(black_box(&mut values), &b32)
+    });
+    // The generic `mul_mod` on `u32` would overflow, so it multiplies in `u64`.
+    let mut wide: Vec<u64> = a32.iter().map(|&x| x as u64).collect();
+    let wide_b: Vec<u64> = b32.iter().map(|&x| x as u64).collect();
+    time("u32 mul_mod_slice (as u64)", || {
+        mul_mod_slice(black_box(&mut wide), &wide_b, m32 as u64)
+    });
+    time("u32 Barrett::mul_mod_slice", || {
+        barrett32.mul_mod_slice(black_box(&mut values), &b32)
+    });
+
+    let mut values = raw64.clone();
+    time("u64 constrain_slice", || {
+        values.copy_from_slice(&raw64);
+        constrain_slice(black_box(&mut values), m64);
+    });
+    time("u64 Barrett::constrain_slice", || {
+        values.copy_from_slice(&raw64);
+        barrett64.constrain_slice(black_box(&mut values));
+    });
+    let mut values = a64.clone();
+    time("u64 add_mod_slice", || {
+        add_mod_slice(black_box(&mut values), &b64, m64)
+    });
+    time("u64 Barrett::add_mod_slice", || {
+        barrett64.add_mod_slice(black_box(&mut values), &b64)
+    });
+    let mut wide: Vec<u128> = a64.iter().map(|&x| x as u128).collect();
+    let wide_b: Vec<u128> = b64.iter().map(|&x| x as u128).collect();
+    time("u64 mul_mod_slice (as u128)", || {
+        mul_mod_slice(black_box(&mut wide), &wide_b, m64 as u128)
+    });
+    time("u64 Barrett::mul_mod_slice", || {
+        barrett64.mul_mod_slice(black_box(&mut values), &b64)
+    });
+}
@@ -0,0 +1,32 @@
+use std::hint::black_box;
+use std::time::Instant;
+
+use modicum::{dot_mod, AddMod, MulMod, SplitMix64};
+
+const LEN: usize = 1 << 16;
+const ROUNDS: usize = 100;
+
+fn main() {
+    let modulus = 1_000_000_007u64;
+    let mut rng = SplitMix64::new(0);
+    let a: Vec<u64> = (0..LEN).map(|_| rng.next_u64() % modulus).collect();
+    let b: Vec<u64> = (0..LEN).map(|_| rng.next_u64() % modulus).collect();
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        let sum = black_box(&a)
+            .iter()
+            .zip(black_box(&b))
+            .fold(0u64, |acc, (&x, &y)| {
+                acc.add_mod(x.mul_mod(y, modulus), modulus)
+            });
+        black_box(sum);
+    }
+    println!("mul_mod + add_mod fold: {:?}", start.elapsed());
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(dot_mod(black_box(&a), black_box(&b), modulus));
+    }
+    println!("dot_mod:                {:?}", start.elapsed());
+}
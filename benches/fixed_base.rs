@@ -0,0 +1,28 @@
+use std::hint::black_box;
+use std::time::Instant;
+
+use modicum::{FixedBasePow, PowMod, SplitMix64};
+
+const ITERATIONS: usize = 10_000;
+
+fn main() {
+    let modulus = 18_446_744_073_709_551_557u64;
+    let base = 5u128;
+    let mut rng = SplitMix64::new(0);
+    let exponents: Vec<u128> = (0..ITERATIONS).map(|_| rng.next_u64() as u128).collect();
+
+    let start = Instant::now();
+    for &exponent in &exponents {
+        black_box(black_box(base).pow_mod(black_box(exponent), modulus));
+    }
+    println!("pow_mod:       {:?}", start.elapsed());
+
+    for window_bits in [4, 6, 8] {
+        let start = Instant::now();
+        let pow = FixedBasePow::new(base, modulus, window_bits);
+        for &exponent in &exponents {
+            black_box(pow.pow(black_box(exponent)));
+        }
+        println!("FixedBasePow ({window_bits} bits): {:?}", start.elapsed());
+    }
+}
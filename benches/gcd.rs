@@ -0,0 +1,49 @@
+use std::hint::black_box;
+use std::time::Instant;
+
+use modicum::{binary_gcd, SplitMix64};
+
+const ITERATIONS: usize = 1_000_000;
+
+/// The Euclidean algorithm, as used for integer types without a binary implementation.
+fn euclid<T: Copy + PartialEq + Default + std::ops::Rem<Output = T>>(mut a: T, mut b: T) -> T {
+    while b != T::default() {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn main() {
+    let mut rng = SplitMix64::new(356);
+    let pairs: Vec<(u64, u64)> = (0..ITERATIONS)
+        .map(|_| (rng.next_u64(), rng.next_u64()))
+        .collect();
+    let wide: Vec<(u128, u128)> = pairs
+        .iter()
+        .map(|&(a, b)| ((a as u128) << 64 | b as u128, (b as u128) << 64 | a as u128))
+        .collect();
+
+    let start = Instant::now();
+    for &(a, b) in &pairs {
+        black_box(euclid(black_box(a), black_box(b)));
+    }
+    println!("euclid u64:       {:?}", start.elapsed());
+
+    let start = Instant::now();
+    for &(a, b) in &pairs {
+        black_box(binary_gcd(black_box(a), black_box(b)));
+    }
+    println!("binary_gcd u64:   {:?}", start.elapsed());
+
+    let start = Instant::now();
+    for &(a, b) in &wide {
+        black_box(euclid(black_box(a), black_box(b)));
+    }
+    println!("euclid u128:      {:?}", start.elapsed());
+
+    let start = Instant::now();
+    for &(a, b) in &wide {
+        black_box(binary_gcd(black_box(a), black_box(b)));
+    }
+    println!("binary_gcd u128:  {:?}", start.elapsed());
+}
@@ -0,0 +1,42 @@
+use std::hint::black_box;
+use std::time::Instant;
+
+use modicum::{Constrain, Egcd, Invert, SplitMix64};
+
+const ITERATIONS: usize = 1_000_000;
+
+fn main() {
+    let modulus = 1_000_000_007u64;
+
+    let start = Instant::now();
+    for a in 1..=ITERATIONS as i64 {
+        black_box(black_box(a).invert(modulus));
+    }
+    println!("invert:         {:?}", start.elapsed());
+
+    let start = Instant::now();
+    for a in 1..=ITERATIONS as i64 {
+        black_box(black_box(a).invert_prime(modulus));
+    }
+    println!("invert_prime:   {:?}", start.elapsed());
+
+    // Modulo a 61-bit prime, the binary extended gcd behind `invert` against the extended Euclidean algorithm.
+    let modulus = (1u64 << 61) - 1;
+    let mut rng = SplitMix64::new(357);
+    let values: Vec<i64> = (0..ITERATIONS)
+        .map(|_| (rng.next_u64() % modulus) as i64)
+        .collect();
+
+    let start = Instant::now();
+    for &a in &values {
+        black_box(black_box(a).invert(modulus));
+    }
+    println!("invert 61-bit:  {:?}", start.elapsed());
+
+    let start = Instant::now();
+    for &a in &values {
+        let (d, x, _) = black_box(a).egcd(modulus as i64);
+        black_box((d == 1).then(|| x.constrain(modulus)));
+    }
+    println!("egcd 61-bit:    {:?}", start.elapsed());
+}
@@ -0,0 +1,55 @@
+use std::hint::black_box;
+use std::time::Instant;
+
+use modicum::{ModInt, SplitMix64};
+
+const LEN: usize = 1 << 20;
+const ROUNDS: usize = 20;
+
+fn time(name: &str, mut f: impl FnMut()) {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        f();
+    }
+    println!("{name:<32} {:?}", start.elapsed());
+}
+
+/// Multiplication with a `u128` remainder, as `ModInt` computes it for moduli of `2^32` and above.
+fn mul_u128<const M: u64>(a: u64, b: u64) -> u64 {
+    (a as u128 * b as u128 % M as u128) as u64
+}
+
+fn bench<const M: u64>(name: &str, raw: &[u64]) {
+    let a: Vec<ModInt<M>> = raw.iter().map(|&x| ModInt::new(x)).collect();
+    let b: Vec<ModInt<M>> = a.iter().rev().copied().collect();
+    let mut out = a.clone();
+    time(&format!("{name} u128 remainder"), || {
+        for ((o, x), y) in out.iter_mut().zip(&a).zip(&b) {
+            *o = ModInt::new(mul_u128::<M>(x.value(), y.value()));
+        }
+        black_box(&mut out);
+    });
+    time(&format!("{name} ModInt mul"), || {
+        for ((o, &x), &y) in out.iter_mut().zip(&a).zip(&b) {
+            *o = x * y;
+        }
+        black_box(&mut out);
+    });
+    time(&format!("{name} u128 remainder chain"), || {
+        let mut acc = 1;
+        for x in &a {
+            acc = mul_u128::<M>(acc, x.value());
+        }
+        black_box(acc);
+    });
+    time(&format!("{name} ModInt product"), || {
+        black_box(a.iter().product::<ModInt<M>>());
+    });
+}
+
+fn main() {
+    let mut rng = SplitMix64::new(369);
+    let raw: Vec<u64> = (0..LEN).map(|_| rng.next_u64()).collect();
+    bench::<998_244_353>("Mint998", &raw);
+    bench::<1_000_000_007>("Mint1e9", &raw);
+}
@@ -0,0 +1,33 @@
+use std::hint::black_box;
+use std::time::Instant;
+
+use modicum::{multi_pow_mod, MulMod, PowMod, SplitMix64};
+
+const ITERATIONS: usize = 100_000;
+
+fn main() {
+    let modulus = 18_446_744_073_709_551_557u64;
+    let mut rng = SplitMix64::new(0);
+    let inputs: Vec<[(u128, u128); 2]> = (0..ITERATIONS)
+        .map(|_| {
+            let mut next = || rng.next_u64() as u128 % modulus as u128;
+            [(next(), next()), (next(), next())]
+        })
+        .collect();
+
+    let start = Instant::now();
+    for &[(a, x), (b, y)] in &inputs {
+        let (a, x, b, y) = black_box((a, x, b, y));
+        black_box(
+            a.pow_mod(x, modulus)
+                .mul_mod(b.pow_mod(y, modulus), modulus),
+        );
+    }
+    println!("pow_mod twice: {:?}", start.elapsed());
+
+    let start = Instant::now();
+    for terms in &inputs {
+        black_box(multi_pow_mod(black_box(terms), modulus));
+    }
+    println!("multi_pow_mod: {:?}", start.elapsed());
+}
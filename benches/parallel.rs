@@ -0,0 +1,39 @@
+//! Run with and without `--features parallel` to compare the serial and threaded batch operations.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use modicum::ntt::convolve_mod;
+use modicum::{batch_invert, constrain_slice, ModMatrix, SplitMix64};
+
+const LEN: usize = 1 << 20;
+
+fn time<R>(name: &str, f: impl FnOnce() -> R) {
+    let start = Instant::now();
+    black_box(f());
+    println!("{name:<24} {:?}", start.elapsed());
+}
+
+fn main() {
+    println!(
+        "parallel feature: {}, threads: {}",
+        cfg!(feature = "parallel"),
+        std::thread::available_parallelism().map_or(1, |n| n.get())
+    );
+    let modulus = black_box(1_000_000_007u64);
+    let mut rng = SplitMix64::new(0);
+    let raw: Vec<u64> = (0..LEN).map(|_| rng.next_u64()).collect();
+    let mut values = raw.clone();
+    time("constrain_slice", || constrain_slice(&mut values, modulus));
+    let values: Vec<i64> = values.iter().map(|&x| x as i64 | 1).collect();
+    time("batch_invert", || batch_invert(&values, modulus));
+
+    let a: Vec<u64> = raw[..LEN / 8].iter().map(|x| x % modulus).collect();
+    let b: Vec<u64> = raw[LEN / 8..LEN / 4].iter().map(|x| x % modulus).collect();
+    time("convolve_mod", || convolve_mod(&a, &b, modulus));
+
+    let n = 200;
+    let data: Vec<u64> = raw[..n * n].to_vec();
+    let matrix = ModMatrix::new(n, n, data, modulus);
+    time("matrix multiplication", || &matrix * &matrix);
+}
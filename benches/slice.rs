@@ -0,0 +1,50 @@
+use std::hint::black_box;
+use std::time::Instant;
+
+use modicum::{add_mod_slice, scale_mod_slice, AddMod, MulMod, SplitMix64};
+
+const LEN: usize = 1 << 16;
+const ROUNDS: usize = 100;
+
+fn main() {
+    let modulus = 1_000_000_007u64;
+    let mut rng = SplitMix64::new(0);
+    let a: Vec<u64> = (0..LEN).map(|_| rng.next_u64() % modulus).collect();
+    let b: Vec<u64> = (0..LEN).map(|_| rng.next_u64() % modulus).collect();
+
+    let mut values = a.clone();
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for (x, &y) in values.iter_mut().zip(&b) {
+            *x = x.add_mod(y, modulus);
+        }
+        black_box(&mut values);
+    }
+    println!("add_mod loop:    {:?}", start.elapsed());
+
+    let mut values = a.clone();
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        add_mod_slice(&mut values, &b, modulus);
+        black_box(&mut values);
+    }
+    println!("add_mod_slice:   {:?}", start.elapsed());
+
+    let mut values = a.clone();
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for x in values.iter_mut() {
+            *x = x.mul_mod(black_box(12_345), modulus);
+        }
+        black_box(&mut values);
+    }
+    println!("mul_mod loop:    {:?}", start.elapsed());
+
+    let mut values = a;
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        scale_mod_slice(&mut values, black_box(12_345), modulus);
+        black_box(&mut values);
+    }
+    println!("scale_mod_slice: {:?}", start.elapsed());
+}
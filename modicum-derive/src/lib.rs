@@ -0,0 +1,298 @@
+//! # Derive macros for modicum
+//!
+//! This crate provides `#[derive(ModicumInteger)]`, which is re-exported by `modicum` with the `derive` feature.
+//! Depend on `modicum` with that feature rather than on this crate: the generated code refers to `::modicum`.
+
+use proc_macro::{Delimiter, TokenStream, TokenTree};
+
+/// The primitive integers, which moduli are converted from and, for unsigned newtypes, to.
+const PRIMITIVES: [&str; 12] = [
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+];
+
+/// Implement `Integer` for a single-field tuple struct over a primitive integer by forwarding to the field.
+///
+/// The derive implements the arithmetic operators and their checked versions, `Zero`, `One`, `Num`,
+/// `FromPrimitive` and `ToPrimitive`, and `TryFrom` every primitive integer so that moduli can be cast to the newtype.
+/// `Clone`, `Copy`, `PartialEq`, `Eq` and `PartialOrd` must be derived alongside it.
+///
+/// With `#[modicum(signed)]` it also implements `Neg` and `Signed`, which `Egcd` and `Invert` require.
+/// With `#[modicum(unsigned)]` it implements `Unsigned` and `TryFrom<Self>` for every primitive integer,
+/// so that the newtype can be used as a modulus.
+#[proc_macro_derive(ModicumInteger, attributes(modicum))]
+pub fn derive_modicum_integer(input: TokenStream) -> TokenStream {
+    match parse(input) {
+        Ok(newtype) => newtype
+            .expand()
+            .parse()
+            .expect("the generated code is valid Rust"),
+        Err(message) => format!("::core::compile_error!({message:?});")
+            .parse()
+            .expect("the error is valid Rust"),
+    }
+}
+
+/// The sign of a newtype, chosen with the `modicum` attribute.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Sign {
+    Unspecified,
+    Signed,
+    Unsigned,
+}
+
+/// A single-field tuple struct.
+struct Newtype {
+    name: String,
+    inner: String,
+    sign: Sign,
+}
+
+/// Parse `#[attributes] visibility struct Name(visibility Inner);`.
+fn parse(input: TokenStream) -> Result<Newtype, String> {
+    const SHAPE: &str = "ModicumInteger can only be derived for tuple structs with a single field";
+    let mut tokens = input.into_iter().peekable();
+    let mut sign = Sign::Unspecified;
+    while let Some(TokenTree::Punct(punct)) = tokens.peek() {
+        if punct.as_char() != '#' {
+            break;
+        }
+        tokens.next();
+        match tokens.next() {
+            Some(TokenTree::Group(group)) => {
+                if let Some(attribute) = parse_attribute(group.stream())? {
+                    if sign != Sign::Unspecified && sign != attribute {
+                        return Err("a newtype cannot be both signed and unsigned".to_string());
+                    }
+                    sign = attribute;
+                }
+            }
+            _ => return Err(SHAPE.to_string()),
+        }
+    }
+    skip_visibility(&mut tokens);
+    match tokens.next() {
+        Some(TokenTree::Ident(ident)) if ident.to_string() == "struct" => {}
+        _ => return Err(SHAPE.to_string()),
+    }
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(ident)) => ident.to_string(),
+        _ => return Err(SHAPE.to_string()),
+    };
+    let fields = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            group.stream()
+        }
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {
+            return Err("ModicumInteger cannot be derived for generic structs".to_string())
+        }
+        _ => return Err(SHAPE.to_string()),
+    };
+    let mut fields = fields.into_iter().peekable();
+    while let Some(TokenTree::Punct(punct)) = fields.peek() {
+        if punct.as_char() != '#' {
+            break;
+        }
+        fields.next();
+        fields.next();
+    }
+    skip_visibility(&mut fields);
+    // Collect the type up to a comma outside of angle brackets, which may only be a trailing one.
+    let mut inner = Vec::new();
+    let mut depth = 0usize;
+    for token in fields.by_ref() {
+        if let TokenTree::Punct(punct) = &token {
+            match punct.as_char() {
+                '<' => depth += 1,
+                '>' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => break,
+                _ => {}
+            }
+        }
+        inner.push(token.to_string());
+    }
+    if inner.is_empty() || fields.next().is_some() {
+        return Err(SHAPE.to_string());
+    }
+    Ok(Newtype {
+        name,
+        inner: inner.join(" "),
+        sign,
+    })
+}
+
+/// Read the sign from `modicum(signed)` or `modicum(unsigned)`, and ignore other attributes.
+fn parse_attribute(attribute: TokenStream) -> Result<Option<Sign>, String> {
+    let mut tokens = attribute.into_iter();
+    match tokens.next() {
+        Some(TokenTree::Ident(ident)) if ident.to_string() == "modicum" => {}
+        _ => return Ok(None),
+    }
+    let arguments = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            group.stream()
+        }
+        _ => return Err("expected `#[modicum(signed)]` or `#[modicum(unsigned)]`".to_string()),
+    };
+    let arguments: Vec<String> = arguments.into_iter().map(|t| t.to_string()).collect();
+    match arguments.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["signed"] => Ok(Some(Sign::Signed)),
+        ["unsigned"] => Ok(Some(Sign::Unsigned)),
+        _ => Err("expected `#[modicum(signed)]` or `#[modicum(unsigned)]`".to_string()),
+    }
+}
+
+/// Skip `pub` and its optional restriction, such as `pub(crate)`.
+fn skip_visibility(tokens: &mut std::iter::Peekable<proc_macro::token_stream::IntoIter>) {
+    if let Some(TokenTree::Ident(ident)) = tokens.peek() {
+        if ident.to_string() == "pub" {
+            tokens.next();
+            if let Some(TokenTree::Group(group)) = tokens.peek() {
+                if group.delimiter() == Delimiter::Parenthesis {
+                    tokens.next();
+                }
+            }
+        }
+    }
+}
+
+impl Newtype {
+    /// Generate the implementations as source code.
+    fn expand(&self) -> String {
+        let Self { name, inner, .. } = self;
+        let num = "::modicum::__num_traits";
+        let mut code = String::new();
+        for (op, method) in [
+            ("Add", "add"),
+            ("Sub", "sub"),
+            ("Mul", "mul"),
+            ("Div", "div"),
+            ("Rem", "rem"),
+        ] {
+            code += &format!(
+                "#[automatically_derived]
+                impl ::core::ops::{op} for {name} {{
+                    type Output = Self;
+                    fn {method}(self, rhs: Self) -> Self {{
+                        {name}(::core::ops::{op}::{method}(self.0, rhs.0))
+                    }}
+                }}"
+            );
+        }
+        code += &format!(
+            "#[automatically_derived]
+            impl {num}::Zero for {name} {{
+                fn zero() -> Self {{
+                    {name}(<{inner} as {num}::Zero>::zero())
+                }}
+                fn is_zero(&self) -> bool {{
+                    {num}::Zero::is_zero(&self.0)
+                }}
+            }}
+            #[automatically_derived]
+            impl {num}::One for {name} {{
+                fn one() -> Self {{
+                    {name}(<{inner} as {num}::One>::one())
+                }}
+            }}
+            #[automatically_derived]
+            impl {num}::Num for {name} {{
+                type FromStrRadixErr = <{inner} as {num}::Num>::FromStrRadixErr;
+                fn from_str_radix(s: &str, radix: u32) -> ::core::result::Result<Self, Self::FromStrRadixErr> {{
+                    <{inner} as {num}::Num>::from_str_radix(s, radix).map({name})
+                }}
+            }}"
+        );
+        code += &format!("#[automatically_derived] impl {num}::FromPrimitive for {name} {{");
+        for t in ["i64", "u64", "i128", "u128"] {
+            code += &format!(
+                "fn from_{t}(n: {t}) -> ::core::option::Option<Self> {{
+                    <{inner} as {num}::FromPrimitive>::from_{t}(n).map({name})
+                }}"
+            );
+        }
+        code += "}";
+        code += &format!("#[automatically_derived] impl {num}::ToPrimitive for {name} {{");
+        for t in ["i64", "u64", "i128", "u128"] {
+            code += &format!(
+                "fn to_{t}(&self) -> ::core::option::Option<{t}> {{
+                    {num}::ToPrimitive::to_{t}(&self.0)
+                }}"
+            );
+        }
+        code += "}";
+        for (op, method) in [
+            ("CheckedAdd", "checked_add"),
+            ("CheckedSub", "checked_sub"),
+            ("CheckedMul", "checked_mul"),
+        ] {
+            code += &format!(
+                "#[automatically_derived]
+                impl {num}::{op} for {name} {{
+                    fn {method}(&self, rhs: &Self) -> ::core::option::Option<Self> {{
+                        {num}::{op}::{method}(&self.0, &rhs.0).map({name})
+                    }}
+                }}"
+            );
+        }
+        for t in PRIMITIVES {
+            code += &format!(
+                "#[automatically_derived]
+                #[allow(clippy::infallible_try_from)]
+                impl ::core::convert::TryFrom<{t}> for {name} {{
+                    type Error = <{inner} as ::core::convert::TryFrom<{t}>>::Error;
+                    fn try_from(n: {t}) -> ::core::result::Result<Self, Self::Error> {{
+                        <{inner} as ::core::convert::TryFrom<{t}>>::try_from(n).map({name})
+                    }}
+                }}"
+            );
+        }
+        match self.sign {
+            Sign::Unspecified => {}
+            Sign::Signed => {
+                code += &format!(
+                    "#[automatically_derived]
+                    impl ::core::ops::Neg for {name} {{
+                        type Output = Self;
+                        fn neg(self) -> Self {{
+                            {name}(-self.0)
+                        }}
+                    }}
+                    #[automatically_derived]
+                    impl {num}::Signed for {name} {{
+                        fn abs(&self) -> Self {{
+                            {name}({num}::Signed::abs(&self.0))
+                        }}
+                        fn abs_sub(&self, other: &Self) -> Self {{
+                            {name}({num}::Signed::abs_sub(&self.0, &other.0))
+                        }}
+                        fn signum(&self) -> Self {{
+                            {name}({num}::Signed::signum(&self.0))
+                        }}
+                        fn is_positive(&self) -> bool {{
+                            {num}::Signed::is_positive(&self.0)
+                        }}
+                        fn is_negative(&self) -> bool {{
+                            {num}::Signed::is_negative(&self.0)
+                        }}
+                    }}"
+                );
+            }
+            Sign::Unsigned => {
+                code += &format!("#[automatically_derived] impl {num}::Unsigned for {name} {{}}");
+                for t in PRIMITIVES {
+                    code += &format!(
+                        "#[automatically_derived]
+                        #[allow(clippy::infallible_try_from)]
+                        impl ::core::convert::TryFrom<{name}> for {t} {{
+                            type Error = <{t} as ::core::convert::TryFrom<{inner}>>::Error;
+                            fn try_from(n: {name}) -> ::core::result::Result<Self, Self::Error> {{
+                                <{t} as ::core::convert::TryFrom<{inner}>>::try_from(n.0)
+                            }}
+                        }}"
+                    );
+                }
+            }
+        }
+        code
+    }
+}
@@ -0,0 +1,206 @@
+use num_traits::{CheckedAdd, CheckedMul, One, Zero};
+
+use crate::{Modulus, Widen};
+
+/// A running sum or product modulo a modulus, kept in the double-width type and reduced only when the next step would overflow.
+/// Summing values or products of two values then costs one addition per step instead of a reduction,
+/// which is what dot products and hash folding need. The operands may be any values of `T`, reduced or not, and negative.
+///
+/// `add` and `add_product` add to the accumulated value, `mul` multiplies it, and `finish` reduces it to `[0, m)`.
+#[derive(Clone, Copy)]
+pub struct ModAccumulator<T: Widen> {
+    value: T::Wide,
+    modulus: T::Wide,
+}
+
+impl<T> ModAccumulator<T>
+where
+    T: Widen,
+    T::Wide: CheckedAdd + CheckedMul,
+{
+    /// Start from zero, to accumulate a sum.
+    /// Panics if the modulus is zero or does not fit in `T`.
+    pub fn new<M>(modulus: M) -> Self
+    where
+        T: TryFrom<M>,
+        T::Wide: TryFrom<M>,
+        M: Modulus<T> + Modulus<T::Wide>,
+    {
+        assert!(modulus != M::zero(), "the modulus must be nonzero");
+        // The result is returned in `T`, so the modulus must fit there too.
+        let _: T = modulus.cast();
+        Self {
+            value: T::Wide::zero(),
+            modulus: modulus.cast(),
+        }
+    }
+
+    /// Start from one, to accumulate a product.
+    /// Panics if the modulus is zero or does not fit in `T`.
+    pub fn one<M>(modulus: M) -> Self
+    where
+        T: TryFrom<M>,
+        T::Wide: TryFrom<M>,
+        M: Modulus<T> + Modulus<T::Wide>,
+    {
+        Self {
+            value: T::Wide::one(),
+            ..Self::new(modulus)
+        }
+    }
+
+    /// Add a value.
+    pub fn add(&mut self, value: T) {
+        self.add_wide(value.widen());
+    }
+
+    /// Add the product of two values, which always fits in the double-width type.
+    pub fn add_product(&mut self, a: T, b: T) {
+        self.add_wide(a.widen() * b.widen());
+    }
+
+    /// Multiply the accumulated value by a value.
+    pub fn mul(&mut self, value: T) {
+        let value = value.widen();
+        // Two reduced factors are below `m` in absolute value, so their product fits.
+        self.value = self
+            .value
+            .checked_mul(&value)
+            .unwrap_or_else(|| (self.value % self.modulus) * (value % self.modulus));
+    }
+
+    /// Reduce the accumulated value to `[0, m)`.
+    pub fn finish(self) -> T {
+        let m = self.modulus;
+        T::narrow((self.value % m + m) % m)
+    }
+
+    fn add_wide(&mut self, x: T::Wide) {
+        // A reduced value is below `m ≤ T::MAX` in absolute value, and a term is at most `T::MAX²` in absolute value,
+        // so their sum fits in the double-width type.
+        self.value = self
+            .value
+            .checked_add(&x)
+            .unwrap_or_else(|| self.value % self.modulus + x);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AddMod, Constrain, MulMod, SplitMix64};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_sum() {
+        let mut rng = SplitMix64::new(370);
+        for m in [1u32, 2, 1_000_000_007, u32::MAX, u32::MAX - 4] {
+            let values: Vec<u32> = (0..100_000)
+                .map(|i| match i % 3 {
+                    0 => u32::MAX - (rng.next_u64() % 16) as u32,
+                    _ => rng.next_u64() as u32,
+                })
+                .collect();
+            let mut accumulator = ModAccumulator::<u32>::new(m);
+            let mut products = ModAccumulator::<u32>::new(m);
+            for pair in values.chunks(2) {
+                accumulator.add(pair[0]);
+                accumulator.add(pair[1]);
+                products.add_product(pair[0], pair[1]);
+            }
+            let m = m as u64;
+            let sum = values
+                .iter()
+                .fold(0u64, |sum, &x| sum.add_mod(x as u64 % m, m));
+            let dot = values.chunks(2).fold(0u64, |sum, pair| {
+                let product = (pair[0] as u64).mul_mod(pair[1] as u64, m);
+                sum.add_mod(product, m)
+            });
+            assert_eq!(accumulator.finish() as u64, sum, "m = {m}");
+            assert_eq!(products.finish() as u64, dot, "m = {m}");
+        }
+    }
+
+    #[test]
+    fn test_sum_u64() {
+        let mut rng = SplitMix64::new(3700);
+        for m in [998_244_353u64, (1 << 61) - 1, u64::MAX] {
+            let values: Vec<u64> = (0..20_000)
+                .map(|i| match i % 2 {
+                    0 => u64::MAX - rng.next_u64() % 16,
+                    _ => rng.next_u64(),
+                })
+                .collect();
+            let mut accumulator = ModAccumulator::<u64>::new(m);
+            for pair in values.chunks(2) {
+                accumulator.add_product(pair[0], pair[1]);
+                accumulator.add(pair[1]);
+            }
+            let m = m as u128;
+            let expected = values.chunks(2).fold(0u128, |sum, pair| {
+                let product = (pair[0] as u128 % m).mul_mod(pair[1] as u128 % m, m);
+                sum.add_mod(product, m).add_mod(pair[1] as u128 % m, m)
+            });
+            assert_eq!(accumulator.finish() as u128, expected, "m = {m}");
+        }
+    }
+
+    #[test]
+    fn test_signed() {
+        let mut rng = SplitMix64::new(37);
+        let m = 1_000_000_007u64;
+        let values: Vec<i64> = (0..50_000)
+            .map(|i| match i % 4 {
+                0 => i64::MIN + (rng.next_u64() % 8) as i64,
+                1 => i64::MAX - (rng.next_u64() % 8) as i64,
+                _ => rng.next_u64() as i64,
+            })
+            .collect();
+        let mut accumulator = ModAccumulator::<i64>::new(m);
+        for pair in values.chunks(2) {
+            accumulator.add(pair[0]);
+            accumulator.add_product(pair[0], pair[1]);
+        }
+        let expected = values.chunks(2).fold(0i128, |sum, pair| {
+            let (a, b) = (pair[0] as i128, pair[1] as i128);
+            sum.add_mod(a, m)
+                .add_mod(a.constrain(m) * b.constrain(m), m)
+        });
+        assert_eq!(accumulator.finish() as i128, expected);
+    }
+
+    #[test]
+    fn test_product() {
+        let mut rng = SplitMix64::new(3701);
+        for m in [7u32, 998_244_353, u32::MAX] {
+            let values: Vec<u32> = (0..10_000).map(|_| rng.next_u64() as u32).collect();
+            let mut accumulator = ModAccumulator::<u32>::one(m);
+            for &x in &values {
+                accumulator.mul(x);
+            }
+            let m = m as u64;
+            let expected = values
+                .iter()
+                .fold(1 % m, |product, &x| product.mul_mod(x as u64, m));
+            assert_eq!(accumulator.finish() as u64, expected, "m = {m}");
+        }
+        // A polynomial hash by Horner's rule mixes products and sums.
+        let m = 1_000_000_007u64;
+        let mut hash = ModAccumulator::<u64>::new(m);
+        for byte in b"modicum" {
+            hash.mul(131);
+            hash.add(*byte as u64);
+        }
+        let expected = b"modicum"
+            .iter()
+            .fold(0u64, |h, &byte| h.mul_mod(131, m).add_mod(byte as u64, m));
+        assert_eq!(hash.finish(), expected);
+        assert_eq!(ModAccumulator::<u8>::one(1u8).finish(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot convert modulus")]
+    fn test_modulus_too_large() {
+        ModAccumulator::<u8>::new(300u16);
+    }
+}
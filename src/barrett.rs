@@ -0,0 +1,237 @@
+/// Reduction modulo a fixed modulus `m` with Barrett's method, for `u32` and `u64`.
+/// Reciprocals `μ = ⌊(2^W - 1) / m⌋` are computed once for the width `W` of the type and for the double width,
+/// after which every reduction is a high multiplication, a low multiplication and one conditional subtraction
+/// instead of a division.
+///
+/// The slice methods give the same results as `constrain_slice`, `add_mod_slice` and `mul_mod_slice`.
+/// Their loops are free of branches and divisions so that the compiler can unroll and vectorize them for the target,
+/// and sums and products never leave the double-width type, so unlike the generic functions they cannot overflow.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Barrett<T> {
+    modulus: T,
+    /// `⌊(2^W - 1) / m⌋` for the width of `T`.
+    reciprocal: T,
+    /// `⌊(2^{2W} - 1) / m⌋` for the double width, which fits in `u128` for both supported types.
+    wide_reciprocal: u128,
+}
+
+macro_rules! barrett {
+    ($t:ty, $wide:ty, $high:ident) => {
+        impl Barrett<$t> {
+            /// Prepare reduction modulo `modulus`, or `None` if the modulus is zero.
+            pub fn new(modulus: $t) -> Option<Self> {
+                (modulus != 0).then(|| Self {
+                    modulus,
+                    reciprocal: <$t>::MAX / modulus,
+                    wide_reciprocal: (<$wide>::MAX / modulus as $wide) as u128,
+                })
+            }
+
+            /// The modulus.
+            pub fn modulus(&self) -> $t {
+                self.modulus
+            }
+
+            /// Constrain an integer to the modulus.
+            pub fn constrain(&self, x: $t) -> $t {
+                // As in `reduce`, the remainder `x - qm` is below `2m`, and it does not exceed `x`.
+                let q = ((x as $wide * self.reciprocal as $wide) >> <$t>::BITS) as $t;
+                let r = x - q * self.modulus;
+                if r >= self.modulus {
+                    r - self.modulus
+                } else {
+                    r
+                }
+            }
+
+            /// Add two integers modulo the modulus.
+            pub fn add(&self, a: $t, b: $t) -> $t {
+                self.reduce(a as $wide + b as $wide)
+            }
+
+            /// Multiply two integers modulo the modulus.
+            pub fn mul(&self, a: $t, b: $t) -> $t {
+                self.reduce(a as $wide * b as $wide)
+            }
+
+            /// Constrain every integer of a slice to the modulus in place, as `constrain_slice`.
+            pub fn constrain_slice(&self, values: &mut [$t]) {
+                for x in values.iter_mut() {
+                    *x = self.constrain(*x);
+                }
+            }
+
+            /// Add `rhs` to `values` element-wise modulo the modulus, in place, as `add_mod_slice`.
+            /// Panics if the slices differ in length.
+            pub fn add_mod_slice(&self, values: &mut [$t], rhs: &[$t]) {
+                assert_eq!(values.len(), rhs.len(), "the slices differ in length");
+                for (x, &y) in values.iter_mut().zip(rhs) {
+                    *x = self.add(*x, y);
+                }
+            }
+
+            /// Multiply `values` by `rhs` element-wise modulo the modulus, in place, as `mul_mod_slice`.
+            /// Panics if the slices differ in length.
+            pub fn mul_mod_slice(&self, values: &mut [$t], rhs: &[$t]) {
+                assert_eq!(values.len(), rhs.len(), "the slices differ in length");
+                for (x, &y) in values.iter_mut().zip(rhs) {
+                    *x = self.mul(*x, y);
+                }
+            }
+
+            /// Reduce a double-width integer.
+            fn reduce(&self, x: $wide) -> $t {
+                // Since `μ > 2^W / m - 1`, the quotient estimate `q = ⌊xμ / 2^W⌋` is the true quotient or one less,
+                // so the remainder `x - qm` is below `2m` and one subtraction completes it.
+                let m = self.modulus as $wide;
+                let q = $high(x, self.wide_reciprocal as $wide);
+                let r = x - q * m;
+                (if r >= m { r - m } else { r }) as $t
+            }
+        }
+    };
+}
+
+barrett!(u32, u64, high_u64);
+barrett!(u64, u128, high_u128);
+
+/// The high half of the product of two `u64`.
+fn high_u64(a: u64, b: u64) -> u64 {
+    ((a as u128 * b as u128) >> 64) as u64
+}
+
+/// The high half of the product of two `u128`, from four products of their 64-bit halves.
+fn high_u128(a: u128, b: u128) -> u128 {
+    const LOW: u128 = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & LOW);
+    let (b_high, b_low) = (b >> 64, b & LOW);
+    // Neither partial sum overflows: each product is at most `(2^64 - 1)^2` and each addend below `2^64`.
+    let middle = a_high * b_low + ((a_low * b_low) >> 64);
+    let carry = a_low * b_high + (middle & LOW);
+    a_high * b_high + (middle >> 64) + (carry >> 64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{add_mod_slice, constrain_slice, mul_mod_slice, SplitMix64};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_high_u128() {
+        let mut rng = SplitMix64::new(336);
+        for _ in 0..1000 {
+            let a = (rng.next_u64() as u128) << 64 | rng.next_u64() as u128;
+            let b = (rng.next_u64() as u128) << 64 | rng.next_u64() as u128;
+            // `⌊ab / 2^128⌋` through 32-bit limbs, which cannot overflow.
+            let limbs = |x: u128| [0, 32, 64, 96].map(|s| (x >> s) as u32 as u128);
+            let (x, y) = (limbs(a), limbs(b));
+            let mut product = [0u128; 9];
+            for i in 0..4 {
+                for j in 0..4 {
+                    product[i + j] += x[i] * y[j];
+                }
+            }
+            for k in 0..8 {
+                product[k + 1] += product[k] >> 32;
+                product[k] &= u32::MAX as u128;
+            }
+            let expected = product[4] | product[5] << 32 | product[6] << 64 | product[7] << 96;
+            assert_eq!(high_u128(a, b), expected);
+        }
+        assert_eq!(high_u128(u128::MAX, u128::MAX), u128::MAX - 1);
+    }
+
+    #[test]
+    fn test_barrett_u32() {
+        let mut rng = SplitMix64::new(3360);
+        for modulus in [
+            1u32,
+            2,
+            7,
+            1_000_000_007,
+            u32::MAX / 2 + 1,
+            u32::MAX - 4,
+            u32::MAX,
+        ] {
+            let barrett = Barrett::<u32>::new(modulus).unwrap();
+            let a: Vec<u32> = (0..500).map(|_| rng.next_u64() as u32).collect();
+            let b: Vec<u32> = (0..500).map(|_| rng.next_u64() as u32).collect();
+            let m = modulus as u64;
+            let mut values = a.clone();
+            barrett.constrain_slice(&mut values);
+            let mut expected: Vec<u64> = a.iter().map(|&x| x as u64).collect();
+            constrain_slice(&mut expected, m);
+            assert_eq!(
+                values.iter().map(|&x| x as u64).collect::<Vec<_>>(),
+                expected
+            );
+            let wide_b: Vec<u64> = b.iter().map(|&x| x as u64).collect();
+            let mut values = a.clone();
+            barrett.add_mod_slice(&mut values, &b);
+            let mut sums: Vec<u64> = a.iter().map(|&x| x as u64).collect();
+            add_mod_slice(&mut sums, &wide_b, m);
+            assert_eq!(values.iter().map(|&x| x as u64).collect::<Vec<_>>(), sums);
+            let mut values = a.clone();
+            barrett.mul_mod_slice(&mut values, &b);
+            let mut products: Vec<u64> = a.iter().map(|&x| x as u64).collect();
+            mul_mod_slice(&mut products, &wide_b, m);
+            assert_eq!(
+                values.iter().map(|&x| x as u64).collect::<Vec<_>>(),
+                products
+            );
+            assert_eq!(barrett.constrain(u32::MAX), u32::MAX % modulus);
+            assert_eq!(
+                barrett.mul(u32::MAX, u32::MAX),
+                ((u32::MAX as u64).pow(2) % m) as u32
+            );
+        }
+        assert_eq!(Barrett::<u32>::new(0), None);
+    }
+
+    #[test]
+    fn test_barrett_u64() {
+        let mut rng = SplitMix64::new(3361);
+        for modulus in [1u64, 2, 998_244_353, 1 << 63, u64::MAX - 58, u64::MAX] {
+            let barrett = Barrett::<u64>::new(modulus).unwrap();
+            assert_eq!(barrett.modulus(), modulus);
+            let a: Vec<u64> = (0..500).map(|_| rng.next_u64()).collect();
+            let b: Vec<u64> = (0..500).map(|_| rng.next_u64()).collect();
+            let m = modulus as u128;
+            let mut values = a.clone();
+            barrett.constrain_slice(&mut values);
+            let mut expected: Vec<u128> = a.iter().map(|&x| x as u128).collect();
+            constrain_slice(&mut expected, m);
+            assert_eq!(
+                values.iter().map(|&x| x as u128).collect::<Vec<_>>(),
+                expected
+            );
+            let wide_b: Vec<u128> = b.iter().map(|&x| x as u128).collect();
+            let mut values = a.clone();
+            barrett.add_mod_slice(&mut values, &b);
+            let mut sums: Vec<u128> = a.iter().map(|&x| x as u128).collect();
+            add_mod_slice(&mut sums, &wide_b, m);
+            assert_eq!(values.iter().map(|&x| x as u128).collect::<Vec<_>>(), sums);
+            // The generic `mul_mod` would overflow `u128` for the largest moduli, so reduce the factors first.
+            let mut values = a.clone();
+            barrett.mul_mod_slice(&mut values, &b);
+            let products: Vec<u128> = a
+                .iter()
+                .zip(&b)
+                .map(|(&x, &y)| (x as u128 % m) * (y as u128 % m) % m)
+                .collect();
+            assert_eq!(
+                values.iter().map(|&x| x as u128).collect::<Vec<_>>(),
+                products
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the slices differ in length")]
+    fn test_barrett_mismatch() {
+        Barrett::<u64>::new(7)
+            .unwrap()
+            .add_mod_slice(&mut [1, 2], &[3]);
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{parallel, Constrain, Invert, Modulus, MulMod};
+
+/// Invert every integer of a slice modulo a modulus using Montgomery's trick.
+/// This costs a single inversion and `3(n-1)` multiplications instead of `n` inversions.
+/// Returns `None` if any of the integers is not invertible.
+pub fn batch_invert<T, M>(values: &[T], modulus: M) -> Option<Vec<T>>
+where
+    T: Invert + TryFrom<M>,
+    M: Modulus<T>,
+{
+    let mut result = values.to_vec();
+    batch_invert_in_place(&mut result, modulus).then_some(result)
+}
+
+/// Invert every integer of a slice modulo a modulus in place using Montgomery's trick.
+/// Returns `false` and leaves the slice unchanged if any of the integers is not invertible.
+/// With the `parallel` feature, large slices are split into one chunk per thread.
+pub fn batch_invert_in_place<T, M>(values: &mut [T], modulus: M) -> bool
+where
+    T: Invert + TryFrom<M>,
+    M: Modulus<T>,
+{
+    let chunk = parallel::chunk_len(values.len(), 3);
+    invert_chunked(values, modulus, chunk)
+}
+
+/// Montgomery's trick in two passes over chunks of length `chunk`, each of which can run on its own thread.
+/// The first pass computes the prefix products within each chunk and the product of the chunk,
+/// the chunk products are inverted together, and the second pass unwinds each chunk from the inverse of its product.
+fn invert_chunked<T, M>(values: &mut [T], modulus: M, chunk: usize) -> bool
+where
+    T: Invert + TryFrom<M>,
+    M: Modulus<T>,
+{
+    if values.is_empty() {
+        return true;
+    }
+    let one = T::one().constrain(modulus);
+    // prefix[i] holds the product of the values of the chunk before index i.
+    let mut prefix = vec![T::zero(); values.len()];
+    let mut products = parallel::map_chunks_mut(&mut prefix, chunk, |i, prefix| {
+        let mut product = one;
+        for (p, &value) in prefix.iter_mut().zip(&values[i * chunk..]) {
+            *p = product;
+            product = product.mul_mod(value.constrain(modulus), modulus);
+        }
+        product
+    });
+    let inverted = match products.as_mut_slice() {
+        [product] => product
+            .invert(modulus)
+            .map(|inverse| *product = inverse)
+            .is_some(),
+        products => invert_chunked(products, modulus, products.len()),
+    };
+    if inverted {
+        parallel::map_chunks_mut(values, chunk, |i, values| {
+            let mut inverse = products[i];
+            for (value, &prefix) in values.iter_mut().zip(&prefix[i * chunk..]).rev() {
+                let original = value.constrain(modulus);
+                *value = inverse.mul_mod(prefix, modulus);
+                inverse = inverse.mul_mod(original, modulus);
+            }
+        });
+    }
+    // The prefix products reveal the inputs, which may be secret.
+    #[cfg(feature = "zeroize")]
+    {
+        crate::wipe::wipe_vec(&mut prefix);
+        crate::wipe::wipe_vec(&mut products);
+    }
+    inverted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_batch_invert() {
+        let modulus = 97u32;
+        let values: Vec<i64> = (1..97).chain(-96..0).collect();
+        let expected: Vec<i64> = values.iter().map(|v| v.invert(modulus).unwrap()).collect();
+        assert_eq!(batch_invert(&values, modulus), Some(expected));
+    }
+
+    #[test]
+    fn test_batch_invert_composite() {
+        let modulus = 12u8;
+        let values = [1, 5, 7, 11, -1, 13];
+        let expected: Vec<i32> = values.iter().map(|v| v.invert(modulus).unwrap()).collect();
+        assert_eq!(batch_invert(&values, modulus), Some(expected));
+        assert_eq!(batch_invert(&[1, 5, 4, 7], modulus), None);
+    }
+
+    #[test]
+    fn test_batch_invert_zero() {
+        assert_eq!(batch_invert(&[3, 0, 5], 11u8), None);
+        assert_eq!(batch_invert(&[3, 22, 5], 11u8), None);
+    }
+
+    #[test]
+    fn test_batch_invert_empty() {
+        assert_eq!(batch_invert::<i32, u8>(&[], 11), Some(vec![]));
+    }
+
+    #[test]
+    fn test_batch_invert_in_place() {
+        let mut values = [2, 3, 4, 5];
+        assert!(batch_invert_in_place(&mut values, 11u8));
+        assert_eq!(values, [6, 4, 3, 9]);
+
+        let mut values = [2, 3, 0, 5];
+        assert!(!batch_invert_in_place(&mut values, 11u8));
+        assert_eq!(values, [2, 3, 0, 5]);
+    }
+
+    #[test]
+    fn test_invert_chunked() {
+        let modulus = 1_000_000_007u64;
+        let values: Vec<i64> = (1..=100).map(|x| x * x * x - 500).collect();
+        let expected: Vec<i64> = values.iter().map(|v| v.invert(modulus).unwrap()).collect();
+        for chunk in [1, 3, 7, 50, 99, 100, 1000] {
+            let mut inverted = values.clone();
+            assert!(invert_chunked(&mut inverted, modulus, chunk));
+            assert_eq!(inverted, expected, "chunk = {chunk}");
+        }
+        let mut values = [2, 3, 4, 5, 6, 0, 7];
+        for chunk in [1, 2, 5, 7] {
+            assert!(!invert_chunked(&mut values, 11u8, chunk));
+            assert_eq!(values, [2, 3, 4, 5, 6, 0, 7]);
+        }
+    }
+
+    #[test]
+    fn test_batch_invert_large() {
+        let modulus = 998_244_353u32;
+        let values: Vec<i64> = (1..=100_000).collect();
+        let inverses = batch_invert(&values, modulus).unwrap();
+        for (&x, &y) in values.iter().zip(&inverses) {
+            assert_eq!(x.mul_mod(y, modulus), 1);
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use std::fmt;
+
+use num_traits::{CheckedAdd, CheckedMul};
+
+use crate::{Integer, Modulus};
+
+/// An error of the checked modular operations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModError {
+    /// The modulus cannot be converted to the integer type.
+    ModulusTooLarge,
+    /// The modulus is zero.
+    ZeroModulus,
+    /// An intermediate result does not fit in the integer type.
+    Overflow,
+    /// An operand of another type cannot be converted to the integer type.
+    OperandOutOfRange,
+    /// A signed modulus is negative.
+    NegativeModulus,
+    /// An exponent is negative.
+    NegativeExponent,
+}
+
+impl fmt::Display for ModError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModError::ModulusTooLarge => write!(f, "modulus does not fit in the integer type"),
+            ModError::ZeroModulus => write!(f, "modulus is zero"),
+            ModError::Overflow => write!(f, "intermediate result overflows"),
+            ModError::OperandOutOfRange => write!(f, "operand does not fit in the integer type"),
+            ModError::NegativeModulus => write!(f, "modulus is negative"),
+            ModError::NegativeExponent => write!(f, "exponent is negative"),
+        }
+    }
+}
+
+impl std::error::Error for ModError {}
+
+/// A trait for modular operations that report failures as a `ModError` instead of panicking or wrapping.
+/// The right-hand side may be any type that converts to the integer type, such as a narrower or differently signed integer;
+/// a value that does not fit is reported as `ModError::OperandOutOfRange`.
+pub trait CheckedMod<M>: Sized {
+    /// Constrain an integer to a modulus.
+    /// Fails only if the modulus is zero or does not fit in the integer type.
+    fn checked_constrain(self, modulus: M) -> Result<Self, ModError>;
+
+    /// Add two integers and constrain the result to a modulus.
+    /// The operands are constrained first and the sum is never formed beyond the modulus, so this cannot overflow.
+    fn checked_add_mod<R: TryInto<Self>>(self, rhs: R, modulus: M) -> Result<Self, ModError>;
+
+    /// Subtract two integers and constrain the result to a modulus.
+    /// The operands are constrained first, so this cannot overflow or underflow.
+    fn checked_sub_mod<R: TryInto<Self>>(self, rhs: R, modulus: M) -> Result<Self, ModError>;
+
+    /// Multiply two integers and constrain the result to a modulus.
+    /// Fails with `ModError::Overflow` if the product of the constrained operands does not fit in the integer type.
+    fn checked_mul_mod<R: TryInto<Self>>(self, rhs: R, modulus: M) -> Result<Self, ModError>;
+
+    /// Raise an integer to a non-negative power and constrain the result to a modulus.
+    /// Fails with `ModError::NegativeExponent` if the exponent is negative,
+    /// and with `ModError::Overflow` if a square or product of residues does not fit in the integer type.
+    fn checked_pow_mod(self, rhs: Self, modulus: M) -> Result<Self, ModError>;
+}
+
+impl<T, M> CheckedMod<M> for T
+where
+    T: Integer + PartialOrd + TryFrom<M> + CheckedAdd + CheckedMul,
+    M: Modulus<T>,
+{
+    fn checked_constrain(self, modulus: M) -> Result<T, ModError> {
+        Ok(constrain_canonical(self, cast(modulus)?))
+    }
+
+    fn checked_add_mod<R: TryInto<T>>(self, rhs: R, modulus: M) -> Result<T, ModError> {
+        let a = self.checked_constrain(modulus)?;
+        let b = operand(rhs)?.checked_constrain(modulus)?;
+        Ok(add_canonical(a, b, cast(modulus)?))
+    }
+
+    fn checked_sub_mod<R: TryInto<T>>(self, rhs: R, modulus: M) -> Result<T, ModError> {
+        let a = self.checked_constrain(modulus)?;
+        let b = operand(rhs)?.checked_constrain(modulus)?;
+        Ok(sub_canonical(a, b, cast(modulus)?))
+    }
+
+    fn checked_mul_mod<R: TryInto<T>>(self, rhs: R, modulus: M) -> Result<T, ModError> {
+        let a = self.checked_constrain(modulus)?;
+        let b = operand(rhs)?.checked_constrain(modulus)?;
+        a.checked_mul(&b)
+            .ok_or(ModError::Overflow)?
+            .checked_constrain(modulus)
+    }
+
+    fn checked_pow_mod(self, mut rhs: T, modulus: M) -> Result<T, ModError> {
+        if rhs < T::zero() {
+            return Err(ModError::NegativeExponent);
+        }
+        let two = T::one() + T::one();
+        let mut result = T::one().checked_constrain(modulus)?;
+        let mut base = self.checked_constrain(modulus)?;
+        while rhs != T::zero() {
+            if rhs % two == T::one() {
+                result = result.checked_mul_mod(base, modulus)?;
+            }
+            rhs = rhs / two;
+            // The last square is never used, so skip it rather than report its overflow.
+            if rhs != T::zero() {
+                base = base.checked_mul_mod(base, modulus)?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Convert a modulus to the integer type, reporting a zero modulus or one that does not fit.
+fn cast<T, M: Modulus<T>>(modulus: M) -> Result<T, ModError> {
+    if modulus.is_zero() {
+        return Err(ModError::ZeroModulus);
+    }
+    modulus.try_cast()
+}
+
+/// Convert a right-hand side to the integer type, reporting a value that does not fit.
+fn operand<T, R: TryInto<T>>(rhs: R) -> Result<T, ModError> {
+    rhs.try_into().map_err(|_| ModError::OperandOutOfRange)
+}
+
+/// Constrain an integer to a positive modulus of the same type without overflowing.
+pub(crate) fn constrain_canonical<T: Integer + CheckedAdd>(x: T, m: T) -> T {
+    let r = x % m;
+    // A negative remainder plus the modulus always fits, so an overflow means that `r` is positive and already canonical.
+    match r.checked_add(&m) {
+        Some(shifted) => shifted % m,
+        None => r,
+    }
+}
+
+/// Add two canonical residues; when their sum overflows it is at least the modulus, so subtract instead.
+pub(crate) fn add_canonical<T: Integer + CheckedAdd>(a: T, b: T, m: T) -> T {
+    match a.checked_add(&b) {
+        Some(sum) => sum % m,
+        None => a - (m - b),
+    }
+}
+
+/// Subtract two canonical residues by adding the negation of the second, which is never negative.
+pub(crate) fn sub_canonical<T: Integer + CheckedAdd>(a: T, b: T, m: T) -> T {
+    if b == T::zero() {
+        a
+    } else {
+        add_canonical(a, m - b, m)
+    }
+}
+
+/// Multiply two canonical residues. When the product overflows, the second factor is halved
+/// and the product is rebuilt by doubling and adding, which never leaves `[0, m)`.
+pub(crate) fn mul_canonical<T: Integer + CheckedAdd + CheckedMul>(a: T, b: T, m: T) -> T {
+    if let Some(product) = a.checked_mul(&b) {
+        return product % m;
+    }
+    let two = T::one() + T::one();
+    let half = mul_canonical(a, b / two, m);
+    let double = add_canonical(half, half, m);
+    if b % two == T::one() {
+        add_canonical(double, a, m)
+    } else {
+        double
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AddMod, Constrain, MulMod, PowMod, SubMod};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_checked_matches_unchecked() {
+        for a in -20i64..20 {
+            for b in -20i64..20 {
+                for m in 1u32..12 {
+                    assert_eq!(a.checked_add_mod(b, m), Ok(a.add_mod(b, m)));
+                    assert_eq!(a.checked_sub_mod(b, m), Ok(a.sub_mod(b, m)));
+                    assert_eq!(a.checked_mul_mod(b, m), Ok(a.mul_mod(b, m)));
+                    if b >= 0 {
+                        // `pow_mod` leaves `a^0 = 1` unreduced modulo one.
+                        let power = a.pow_mod(b, m).constrain(m);
+                        assert_eq!(a.checked_pow_mod(b, m), Ok(power));
+                    }
+                }
+            }
+        }
+        assert_eq!((-7i8).checked_constrain(5u8), Ok(3));
+        assert_eq!(5u8.checked_pow_mod(0, 1u8), Ok(0));
+    }
+
+    #[test]
+    fn test_checked_near_the_limit() {
+        // The unchecked `constrain` and `add_mod` overflow here, since the modulus exceeds half of `u64::MAX`.
+        let m = u64::MAX - 58;
+        assert_eq!(u64::MAX.checked_constrain(m), Ok(58));
+        assert_eq!((m - 1).checked_add_mod(m - 2, m), Ok(m - 3));
+        assert_eq!(1u64.checked_sub_mod(m - 1, m), Ok(2));
+        assert_eq!(i8::MIN.checked_constrain(127u8), Ok(126));
+        assert_eq!(100i8.checked_add_mod(100, 127u8), Ok(73));
+        assert_eq!((2u64 << 31).checked_pow_mod(1, m), Ok(2 << 31));
+    }
+
+    #[test]
+    fn test_checked_mixed_operands() {
+        assert_eq!(5i64.checked_add_mod(-3i32, 7u8), Ok(2));
+        assert_eq!(
+            5i64.checked_sub_mod(u32::MAX, 7u8),
+            Ok(5i64.sub_mod(u32::MAX as i64, 7u8))
+        );
+        assert_eq!(5u64.checked_mul_mod(3u8, 7u8), Ok(1));
+        assert_eq!(5i32.checked_add_mod(6u32, 7u8), Ok(4));
+        assert_eq!((-1i8).checked_mul_mod(-128i64, 7u8), Ok(2));
+    }
+
+    #[test]
+    fn test_checked_errors() {
+        assert_eq!(5i64.checked_constrain(0u32), Err(ModError::ZeroModulus));
+        assert_eq!(5u64.checked_pow_mod(3, 0u64), Err(ModError::ZeroModulus));
+        assert_eq!(
+            5i8.checked_add_mod(1, 200u8),
+            Err(ModError::ModulusTooLarge)
+        );
+        assert_eq!(
+            5u8.checked_mul_mod(1, 300u16),
+            Err(ModError::ModulusTooLarge)
+        );
+        assert_eq!(
+            (1u64 << 40).checked_mul_mod(1u64 << 40, u64::MAX),
+            Err(ModError::Overflow)
+        );
+        assert_eq!(
+            3u64.checked_pow_mod(1 << 20, 1u64 << 62),
+            Err(ModError::Overflow)
+        );
+        assert_eq!(
+            5i8.checked_add_mod(200u8, 7u8),
+            Err(ModError::OperandOutOfRange)
+        );
+        assert_eq!(
+            5u32.checked_mul_mod(-1i32, 7u8),
+            Err(ModError::OperandOutOfRange)
+        );
+        assert_eq!(
+            3i64.checked_pow_mod(-1, 7u64),
+            Err(ModError::NegativeExponent)
+        );
+        assert_eq!(
+            ModError::ModulusTooLarge.to_string(),
+            "modulus does not fit in the integer type"
+        );
+    }
+}
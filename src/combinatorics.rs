@@ -0,0 +1,531 @@
+use std::fmt;
+
+use num_traits::{FromPrimitive, ToPrimitive};
+
+use crate::{crt_slice, factorize, Constrain, Invert, Modulus, MulMod};
+
+/// An error building a table of factorials.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FactorialsError {
+    /// The table size is not below the modulus, so the largest factorials would be zero.
+    TooLarge,
+    /// The largest factorial has no inverse, which happens when the modulus is not prime.
+    NotInvertible,
+}
+
+impl fmt::Display for FactorialsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FactorialsError::TooLarge => write!(f, "table size must be below the modulus"),
+            FactorialsError::NotInvertible => write!(f, "factorials are not invertible"),
+        }
+    }
+}
+
+impl std::error::Error for FactorialsError {}
+
+/// A table of the factorials `0!, …, n!` and their inverses modulo a prime.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Factorials<T, M> {
+    factorials: Vec<T>,
+    inverses: Vec<T>,
+    modulus: M,
+}
+
+#[cfg(feature = "zeroize")]
+impl<T, M> Drop for Factorials<T, M> {
+    fn drop(&mut self) {
+        crate::wipe::wipe_vec(&mut self.factorials);
+        crate::wipe::wipe_vec(&mut self.inverses);
+    }
+}
+
+impl<T, M> Factorials<T, M>
+where
+    T: Invert + TryFrom<M> + FromPrimitive + ToPrimitive,
+    M: Modulus<T>,
+{
+    /// Build the table in `O(n)` multiplications and a single inversion:
+    /// `n!` is inverted once and the other inverses follow from `1/(k-1)! = k · 1/k!`.
+    /// Returns an error if `n` is not below `p`, or if `n!` is not invertible because `p` is not prime.
+    pub fn new(n: usize, p: M) -> Result<Self, FactorialsError> {
+        let modulus: T = p.cast();
+        if modulus.to_usize().is_some_and(|p| n >= p) {
+            return Err(FactorialsError::TooLarge);
+        }
+        let mut factorials = Vec::with_capacity(n + 1);
+        factorials.push(T::one().constrain(p));
+        for k in 1..=n {
+            let k = T::from_usize(k).ok_or(FactorialsError::TooLarge)?;
+            factorials.push(factorials[factorials.len() - 1].mul_mod(k, p));
+        }
+        let mut inverses = vec![T::zero(); n + 1];
+        inverses[n] = factorials[n]
+            .invert(p)
+            .ok_or(FactorialsError::NotInvertible)?;
+        for k in (1..=n).rev() {
+            let value = T::from_usize(k).expect("k fits since n does");
+            inverses[k - 1] = inverses[k].mul_mod(value, p);
+        }
+        Ok(Self {
+            factorials,
+            inverses,
+            modulus: p,
+        })
+    }
+
+    /// The largest `n` in the table.
+    pub fn size(&self) -> usize {
+        self.factorials.len() - 1
+    }
+
+    /// The modulus.
+    pub fn modulus(&self) -> M {
+        self.modulus
+    }
+
+    /// Get `k!`.
+    /// Panics if `k` is greater than the size of the table.
+    pub fn fact(&self, k: usize) -> T {
+        self.factorials[self.index(k)]
+    }
+
+    /// Get the inverse of `k!`.
+    /// Panics if `k` is greater than the size of the table.
+    pub fn inv_fact(&self, k: usize) -> T {
+        self.inverses[self.index(k)]
+    }
+
+    /// Get the inverse of `k` as `(k-1)! / k!`.
+    /// Panics if `k` is zero or greater than the size of the table.
+    pub fn inverse(&self, k: usize) -> T {
+        assert!(k != 0, "zero has no inverse");
+        self.inverses[self.index(k)].mul_mod(self.factorials[k - 1], self.modulus)
+    }
+
+    /// Compute the binomial coefficient `C(n, k)`, which is zero when `k > n`.
+    /// Panics if `n` is greater than the size of the table.
+    pub fn binomial(&self, n: usize, k: usize) -> T {
+        if k > n {
+            self.index(n);
+            return T::zero();
+        }
+        self.fact(n)
+            .mul_mod(self.inv_fact(k), self.modulus)
+            .mul_mod(self.inv_fact(n - k), self.modulus)
+    }
+
+    /// Compute the number of `k`-permutations of `n`, `n! / (n-k)!`, which is zero when `k > n`.
+    /// Panics if `n` is greater than the size of the table.
+    pub fn permutation(&self, n: usize, k: usize) -> T {
+        if k > n {
+            self.index(n);
+            return T::zero();
+        }
+        self.fact(n).mul_mod(self.inv_fact(n - k), self.modulus)
+    }
+
+    /// Compute the multinomial coefficient `(k_1 + … + k_r)! / (k_1! ⋯ k_r!)`; no parts give one.
+    /// Panics if the sum of the parts is greater than the size of the table.
+    pub fn multinomial(&self, parts: &[usize]) -> T {
+        let n = parts
+            .iter()
+            .try_fold(0usize, |n, &k| n.checked_add(k))
+            .expect("the sum of the parts overflows");
+        parts.iter().fold(self.fact(n), |acc, &k| {
+            acc.mul_mod(self.inv_fact(k), self.modulus)
+        })
+    }
+
+    /// Compute the Catalan number `C(2n, n) / (n + 1)`.
+    /// Panics if `2n` or `n + 1` is greater than the size of the table.
+    pub fn catalan(&self, n: usize) -> T {
+        let m = n.checked_mul(2).expect("2n overflows");
+        self.binomial(m, n)
+            .mul_mod(self.inverse(n + 1), self.modulus)
+    }
+
+    /// Compute `C(n, k)` modulo the prime `p` for any `n` and `k` with Lucas' theorem:
+    /// the binomial coefficient is the product of the binomial coefficients of the base-`p` digits of `n` and `k`.
+    /// The table must cover all digits, that is, its size must be `p - 1`.
+    /// Panics if it is not.
+    pub fn binomial_lucas(&self, mut n: u64, mut k: u64) -> T {
+        let p = self
+            .modulus
+            .cast()
+            .to_u64()
+            .filter(|&p| p == self.size() as u64 + 1)
+            .expect("the table must have size p - 1");
+        let mut result = T::one().constrain(self.modulus);
+        while k > 0 {
+            let digit = self.binomial((n % p) as usize, (k % p) as usize);
+            if digit == T::zero() {
+                return digit;
+            }
+            result = result.mul_mod(digit, self.modulus);
+            (n, k) = (n / p, k / p);
+        }
+        result
+    }
+
+    fn index(&self, k: usize) -> usize {
+        assert!(
+            k <= self.size(),
+            "{k} exceeds the table size {}",
+            self.size()
+        );
+        k
+    }
+}
+
+/// Compute the binomial coefficient `C(n, k)` modulo a prime `p` for any `n` and `k` with Lucas' theorem.
+/// This builds a `Factorials` table of size `p - 1`, which takes two residues of memory per integer below `p`,
+/// so `p` should be small; to reuse the table across calls, use `Factorials::binomial_lucas`.
+/// Panics if `p` is not prime or does not fit in memory as a table size.
+pub fn binomial_lucas<T, M>(n: u64, k: u64, p: M) -> T
+where
+    T: Invert + TryFrom<M> + FromPrimitive + ToPrimitive,
+    M: Modulus<T>,
+{
+    let size = p
+        .cast()
+        .to_usize()
+        .and_then(|p| p.checked_sub(1))
+        .expect("the prime must fit a table size");
+    Factorials::new(size, p)
+        .expect("the modulus must be prime")
+        .binomial_lucas(n, k)
+}
+
+/// Compute the binomial coefficient `C(n, k)` modulo any modulus `m`, which is zero when `k > n`.
+/// For each prime power `p^q` dividing `m`, `C(n, k) = p^e · n!_p / (k!_p (n-k)!_p)`, where `e` counts the factors `p`
+/// by Legendre's formula and `n!_p` is `n!` with every factor `p` removed, computed modulo `p^q` from
+/// `n!_p = u^⌊n/p^q⌋ · (n mod p^q)!_p' · ⌊n/p⌋!_p`; here `x!_p'` is the product of the integers up to `x` coprime to `p`
+/// and `u` that product over a full period, which is `-1` except for `p = 2, q ≥ 3` where it is `1`.
+/// The residues are combined with the Chinese Remainder Theorem.
+/// The products up to each `p^q` are tabulated, so time and memory grow with the largest prime power dividing `m`.
+/// Panics if `m` is zero.
+pub fn binomial_mod<T>(n: u64, k: u64, m: T) -> T
+where
+    T: Into<u64> + TryFrom<u64>,
+    <T as TryFrom<u64>>::Error: std::fmt::Debug,
+{
+    let m: u64 = m.into();
+    assert!(m != 0, "the modulus must be nonzero");
+    if k > n {
+        return T::try_from(0).expect("zero fits");
+    }
+    let congruences: Vec<(i128, u64)> = factorize(m)
+        .into_iter()
+        .map(|(p, q)| {
+            let pq = p.pow(q);
+            (binomial_prime_power(n, k, p, q, pq) as i128, pq)
+        })
+        .collect();
+    let (x, _) = crt_slice(&congruences).expect("prime powers are coprime");
+    T::try_from((x as u64) % m).expect("the residue is smaller than the modulus")
+}
+
+/// Compute `C(n, k)` modulo the prime power `pq = p^q` for `k ≤ n`.
+fn binomial_prime_power(n: u64, k: u64, p: u64, q: u32, pq: u64) -> u64 {
+    let legendre = |mut x: u64| {
+        let mut e = 0;
+        while x > 0 {
+            x /= p;
+            e += x;
+        }
+        e
+    };
+    let e = legendre(n) - legendre(k) - legendre(n - k);
+    if e >= q as u64 {
+        return 0;
+    }
+    // units[i] is the product of the integers in 1..=i coprime to p, modulo p^q.
+    let mut units = Vec::with_capacity(pq as usize);
+    units.push(1 % pq);
+    for i in 1..pq {
+        let factor = if i % p == 0 { 1 } else { i };
+        units.push((units[i as usize - 1] as u128).mul_mod(factor as u128, pq) as u64);
+    }
+    // The product over a full period is ±1, so only the parity of the number of periods matters.
+    let period = units[pq as usize - 1] as u128;
+    let stripped = |mut x: u64| {
+        let mut result = 1u128 % pq as u128;
+        while x > 0 {
+            if (x / pq) % 2 == 1 {
+                result = result.mul_mod(period, pq);
+            }
+            result = result.mul_mod(units[(x % pq) as usize] as u128, pq);
+            x /= p;
+        }
+        result as i128
+    };
+    let denominator = (stripped(k) as u128).mul_mod(stripped(n - k) as u128, pq) as i128;
+    let inverse = denominator
+        .invert(pq)
+        .expect("products of units are invertible");
+    let power = (0..e).fold(1u128, |acc, _| acc.mul_mod(p as u128, pq));
+    (stripped(n) as u128)
+        .mul_mod(inverse as u128, pq)
+        .mul_mod(power, pq) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AddMod;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_factorials_small() {
+        let table = Factorials::<i64, u64>::new(20, 1_000_000_007).unwrap();
+        assert_eq!(table.size(), 20);
+        assert_eq!(table.modulus(), 1_000_000_007);
+        let mut factorial = 1i64;
+        for k in 0..=12 {
+            if k > 0 {
+                factorial *= k as i64;
+            }
+            assert_eq!(table.fact(k), factorial);
+            assert_eq!(
+                table.inv_fact(k),
+                factorial.invert(1_000_000_007u64).unwrap()
+            );
+        }
+        for k in 1..=20 {
+            assert_eq!(
+                table.inverse(k),
+                (k as i64).invert(1_000_000_007u64).unwrap()
+            );
+            assert_eq!(
+                table.fact(k).mul_mod(table.inv_fact(k), 1_000_000_007u64),
+                1
+            );
+        }
+    }
+
+    #[test]
+    fn test_factorials_largest() {
+        // Wilson's theorem: (p-1)! ≡ -1 (mod p).
+        let table = Factorials::<i32, u32>::new(12, 13).unwrap();
+        assert_eq!(table.fact(12), 12);
+        assert_eq!(table.inv_fact(12), 12);
+        assert_eq!(table.inverse(12), 12);
+    }
+
+    #[test]
+    fn test_factorials_rejects() {
+        assert_eq!(
+            Factorials::<i32, u32>::new(13, 13),
+            Err(FactorialsError::TooLarge)
+        );
+        assert_eq!(
+            Factorials::<i32, u32>::new(100, 13),
+            Err(FactorialsError::TooLarge)
+        );
+        assert_eq!(
+            Factorials::<i32, u32>::new(4, 15),
+            Err(FactorialsError::NotInvertible)
+        );
+        assert!(Factorials::<i32, u32>::new(2, 15).is_ok());
+        assert!(Factorials::<i32, u32>::new(1, 1).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the table size")]
+    fn test_factorials_out_of_range() {
+        Factorials::<i64, u64>::new(5, 7).unwrap().fact(6);
+    }
+
+    #[test]
+    fn test_binomial_pascal() {
+        let table = Factorials::<i64, u64>::new(200, 1_000_000_007).unwrap();
+        for n in 1..=200 {
+            for k in 1..=n + 2 {
+                let sum = table
+                    .binomial(n - 1, k - 1)
+                    .add_mod(table.binomial(n - 1, k), 1_000_000_007u64);
+                assert_eq!(table.binomial(n, k), sum, "n = {n}, k = {k}");
+            }
+        }
+        assert_eq!(table.binomial(0, 0), 1);
+        assert_eq!(table.binomial(5, 7), 0);
+        assert_eq!(table.binomial(10, 3), 120);
+        assert_eq!(table.binomial(200, 100), 407_336_795);
+    }
+
+    #[test]
+    fn test_permutation_multinomial() {
+        let table = Factorials::<i64, u64>::new(30, 998_244_353).unwrap();
+        assert_eq!(table.permutation(5, 2), 20);
+        assert_eq!(table.permutation(5, 0), 1);
+        assert_eq!(table.permutation(5, 5), 120);
+        assert_eq!(table.permutation(3, 4), 0);
+        // MISSISSIPPI: 11! / (1! 4! 4! 2!).
+        assert_eq!(table.multinomial(&[1, 4, 4, 2]), 34_650);
+        assert_eq!(table.multinomial(&[]), 1);
+        assert_eq!(table.multinomial(&[7]), 1);
+        for n in 0..=30 {
+            for k in 0..=n {
+                assert_eq!(table.multinomial(&[k, n - k]), table.binomial(n, k));
+            }
+        }
+    }
+
+    #[test]
+    fn test_catalan() {
+        let table = Factorials::<i64, u64>::new(2_001, 1_000_000_007).unwrap();
+        let catalan: Vec<i64> = (0..10).map(|n| table.catalan(n)).collect();
+        assert_eq!(catalan, [1, 1, 2, 5, 14, 42, 132, 429, 1430, 4862]);
+        // The recurrence C_{n+1} = Σ C_i C_{n-i}.
+        for n in 0..40 {
+            let sum = (0..=n).fold(0, |acc, i| {
+                acc.add_mod(
+                    table
+                        .catalan(i)
+                        .mul_mod(table.catalan(n - i), 1_000_000_007u64),
+                    1_000_000_007u64,
+                )
+            });
+            assert_eq!(table.catalan(n + 1), sum);
+        }
+        assert_eq!(
+            table.catalan(1_000),
+            table
+                .binomial(2_000, 1_000)
+                .mul_mod(table.inverse(1_001), 1_000_000_007u64)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the table size")]
+    fn test_binomial_out_of_range() {
+        Factorials::<i64, u64>::new(5, 7).unwrap().binomial(6, 7);
+    }
+
+    #[test]
+    fn test_binomial_lucas_small() {
+        for p in [2u64, 3, 7, 13] {
+            let table = Factorials::<i64, u64>::new(p as usize - 1, p).unwrap();
+            let mut row = vec![1i64];
+            for n in 0..200u64 {
+                for (k, &c) in row.iter().enumerate() {
+                    assert_eq!(table.binomial_lucas(n, k as u64), c, "C({n}, {k}) mod {p}");
+                }
+                assert_eq!(table.binomial_lucas(n, n + 1), 0);
+                row = (0..=row.len())
+                    .map(|k| {
+                        let left = if k > 0 { row[k - 1] } else { 0 };
+                        (left + row.get(k).copied().unwrap_or(0)) % p as i64
+                    })
+                    .collect();
+            }
+        }
+        assert_eq!(binomial_lucas::<i64, u64>(10, 4, 7), 0);
+        assert_eq!(binomial_lucas::<i64, u64>(10, 3, 7), 1);
+    }
+
+    #[test]
+    fn test_binomial_lucas_agrees_with_table() {
+        let p = 1_009u64;
+        let table = Factorials::<i64, u64>::new(1_008, p).unwrap();
+        for n in (0..1_009).step_by(7) {
+            for k in (0..=n).step_by(5) {
+                assert_eq!(
+                    table.binomial_lucas(n as u64, k as u64),
+                    table.binomial(n, k)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_binomial_lucas_large() {
+        let p = 1_000_003u64;
+        let table = Factorials::<i64, u64>::new(1_000_002, p).unwrap();
+        assert_eq!(
+            table.binomial_lucas(1_000_000_000_000_000_000, 1_000_000_000),
+            0
+        );
+        assert_eq!(table.binomial_lucas(1 << 63, (1 << 40) + 7), 460_053);
+        assert_eq!(
+            table.binomial_lucas(987_654_321_987_654_321, 123_456_789),
+            96_447
+        );
+        assert_eq!(table.binomial_lucas(u64::MAX, 0), 1);
+        assert_eq!(table.binomial_lucas(u64::MAX, u64::MAX), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "size p - 1")]
+    fn test_binomial_lucas_small_table() {
+        Factorials::<i64, u64>::new(5, 13)
+            .unwrap()
+            .binomial_lucas(20, 3);
+    }
+
+    #[test]
+    fn test_binomial_mod_exhaustive() {
+        let mut rows = vec![vec![1u128]];
+        for n in 1..=50 {
+            let previous: &Vec<u128> = &rows[n - 1];
+            let row = (0..=n)
+                .map(|k| {
+                    let left = if k > 0 { previous[k - 1] } else { 0 };
+                    left + previous.get(k).copied().unwrap_or(0)
+                })
+                .collect();
+            rows.push(row);
+        }
+        for m in 1..=64u64 {
+            for (n, row) in rows.iter().enumerate() {
+                for (k, &c) in row.iter().enumerate() {
+                    let expected = (c % m as u128) as u64;
+                    assert_eq!(
+                        binomial_mod(n as u64, k as u64, m),
+                        expected,
+                        "C({n}, {k}) mod {m}"
+                    );
+                }
+                assert_eq!(binomial_mod(n as u64, n as u64 + 1, m), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_binomial_mod_large() {
+        let cases = [
+            (
+                1_063_369u64,
+                531_684u64,
+                [212_224u64, 22, 86_400, 1_668_352],
+            ),
+            (525_320, 262_152, [208_198, 0, 383_750, 1_439_046]),
+            (777_777, 1_024, [287_933, 0, 624_125, 7_354_557]),
+            (100_000, 19_683, [565_248, 23, 200_000, 1_025_024]),
+        ];
+        for (n, k, expected) in cases {
+            let moduli = [1 << 20, 27, 1_000_000, (1 << 10) * 243 * 49];
+            for (m, e) in moduli.into_iter().zip(expected) {
+                assert_eq!(binomial_mod(n, k, m), e, "C({n}, {k}) mod {m}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_binomial_mod_huge() {
+        let n = 987_654_321_987_654_321u64;
+        let k = 123_456_789u64;
+        assert_eq!(binomial_mod(n, k, 1_000_003u64), 96_447);
+        for m in [8u64, 27, 1 << 20, 1_000_000, 2 * 1_000_003] {
+            let c = binomial_mod(n, k, m);
+            for (p, q) in factorize(m) {
+                let pq = p.pow(q);
+                assert_eq!(binomial_mod(n, k, pq), c % pq);
+            }
+        }
+        assert_eq!(binomial_mod(u64::MAX, 0, 1u64 << 20), 1);
+        assert_eq!(binomial_mod(u64::MAX, 1, 1u64 << 20), (1 << 20) - 1);
+        assert_eq!(binomial_mod(5u32 as u64, 2, 7u32), 3);
+    }
+}
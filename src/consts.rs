@@ -0,0 +1,293 @@
+//! `const fn` versions of the core operations for 64-bit moduli, for building tables at compile time.
+//! Traits cannot be implemented as `const` yet, so these take concrete types.
+//!
+//! ```
+//! use modicum::consts::{invert_u64, pow_mod_u64};
+//! use modicum::PowMod;
+//!
+//! const R: u64 = pow_mod_u64(3, 100, 998_244_353);
+//! const INVERSES: [u64; 8] = {
+//!     let mut table = [0; 8];
+//!     let mut i = 1;
+//!     while i < 8 {
+//!         table[i] = match invert_u64(i as u64, 11) {
+//!             Some(inverse) => inverse,
+//!             None => panic!("11 is prime"),
+//!         };
+//!         i += 1;
+//!     }
+//!     table
+//! };
+//! assert_eq!(R, 3u64.pow_mod(100u64, 998_244_353u64));
+//! assert_eq!(INVERSES, [0, 1, 6, 4, 3, 9, 2, 8]);
+//! ```
+
+/// Constrain an integer to a modulus, as `Constrain`.
+/// Panics if the modulus is zero.
+pub const fn constrain_u64(x: i128, m: u64) -> u64 {
+    assert!(m != 0, "the modulus must be nonzero");
+    x.rem_euclid(m as i128) as u64
+}
+
+/// Add two integers modulo a modulus, as `AddMod`.
+/// Panics if the modulus is zero.
+pub const fn add_mod_u64(a: u64, b: u64, m: u64) -> u64 {
+    assert!(m != 0, "the modulus must be nonzero");
+    ((a as u128 + b as u128) % m as u128) as u64
+}
+
+/// Multiply two integers modulo a modulus, as `MulMod`.
+/// Panics if the modulus is zero.
+pub const fn mul_mod_u64(a: u64, b: u64, m: u64) -> u64 {
+    assert!(m != 0, "the modulus must be nonzero");
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+
+/// Raise an integer to a power modulo a modulus by repeated squaring, as `PowMod`.
+/// Panics if the modulus is zero.
+pub const fn pow_mod_u64(base: u64, mut exponent: u64, m: u64) -> u64 {
+    let mut base = mul_mod_u64(base, 1, m);
+    let mut result = 1 % m;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod_u64(result, base, m);
+        }
+        base = mul_mod_u64(base, base, m);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// Invert an integer modulo a modulus with the iterative extended Euclidean algorithm, as `Invert`.
+/// Returns `None` if the integer and the modulus are not coprime. Panics if the modulus is zero.
+pub const fn invert_u64(a: u64, m: u64) -> Option<u64> {
+    assert!(m != 0, "the modulus must be nonzero");
+    // Invariants: `r0 ≡ s0·a` and `r1 ≡ s1·a (mod m)`; the coefficients stay within `(-m, m)`.
+    let (mut r0, mut r1) = (m as i128, (a % m) as i128);
+    let (mut s0, mut s1) = (0i128, 1i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (s0, s1) = (s1, s0 - q * s1);
+    }
+    if r0 == 1 {
+        Some(constrain_u64(s0, m))
+    } else {
+        None
+    }
+}
+
+/// Check if a `u64` is prime with the deterministic Miller–Rabin test over the primes up to 37, as `is_prime_u64`.
+pub const fn is_prime_u64(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    let mut i = 0;
+    while i < BASES.len() {
+        if n.is_multiple_of(BASES[i]) {
+            return n == BASES[i];
+        }
+        i += 1;
+    }
+    let (mut d, mut s) = (n - 1, 0);
+    while d.is_multiple_of(2) {
+        d /= 2;
+        s += 1;
+    }
+    let mut i = 0;
+    'bases: while i < BASES.len() {
+        let mut x = pow_mod_u64(BASES[i], d, n);
+        i += 1;
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        let mut r = 1;
+        while r < s {
+            x = mul_mod_u64(x, x, n);
+            if x == n - 1 {
+                continue 'bases;
+            }
+            r += 1;
+        }
+        return false;
+    }
+    true
+}
+
+/// Validate a modulus at compile time, so that a typo cannot silently change the arithmetic.
+/// `modulus!(m)` fails to compile unless `m` is positive, and expands to it unchanged.
+/// `modulus!(prime m)` fails to compile unless `m` is a prime of up to 64 bits, checked with the deterministic
+/// Miller–Rabin test in `consts::is_prime_u64`, and expands to `Prime::new_unchecked(m)`, which is a `PrimeModulus`.
+/// Give the literal a suffix when its type cannot be inferred from the use.
+///
+/// ```
+/// use modicum::{modulus, AddMod, PowMod, Prime};
+///
+/// const P: Prime<u64> = modulus!(prime 1_000_000_007);
+/// assert_eq!(5u64.add_mod(P.get() - 1, P), 4);
+/// assert_eq!(P.invert(2i64), Some(500_000_004));
+/// assert_eq!(2u32.pow_mod(10, modulus!(1000u32)), 24);
+/// ```
+///
+/// A composite where a prime was asked for is rejected:
+/// ```compile_fail
+/// let p = modicum::modulus!(prime 1_000_000_009_u64 * 3);
+/// ```
+/// ```compile_fail
+/// let p = modicum::modulus!(prime 561u64);
+/// ```
+/// and so are a zero or negative modulus:
+/// ```compile_fail
+/// let m: u32 = modicum::modulus!(0);
+/// ```
+/// ```compile_fail
+/// let m: i64 = modicum::modulus!(-7);
+/// ```
+/// ```compile_fail
+/// let p = modicum::modulus!(prime -7i64);
+/// ```
+#[macro_export]
+macro_rules! modulus {
+    (prime $m:expr) => {{
+        const _: () = assert!(
+            $m > 1 && ($m as u128) <= u64::MAX as u128 && $crate::consts::is_prime_u64($m as u64),
+            "the modulus must be prime"
+        );
+        $crate::Prime::new_unchecked($m)
+    }};
+    ($m:expr) => {{
+        const _: () = assert!($m > 0, "the modulus must be positive");
+        $m
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AddMod, Constrain, Invert, MulMod, PowMod, Prime, PrimeModulus, SplitMix64};
+    use pretty_assertions::assert_eq;
+
+    const MODULI: [u64; 6] = [1, 2, 12, 998_244_353, 1 << 63, u64::MAX];
+
+    #[test]
+    fn test_constrain_u64() {
+        let mut rng = SplitMix64::new(345);
+        for m in MODULI {
+            for _ in 0..100 {
+                let x = (rng.next_u64() as i128) << 32 | rng.next_u64() as i128;
+                let x = if x % 3 == 0 { -x } else { x };
+                assert_eq!(constrain_u64(x, m) as i128, x.constrain(m as u128));
+            }
+            assert_eq!(
+                constrain_u64(i128::MIN, m) as i128,
+                i128::MIN.constrain(m as u128)
+            );
+        }
+    }
+
+    #[test]
+    fn test_add_mul_pow_mod_u64() {
+        let mut rng = SplitMix64::new(3450);
+        for m in MODULI {
+            let wide = m as u128;
+            for _ in 0..100 {
+                let (a, b, e) = (rng.next_u64(), rng.next_u64(), rng.next_u64() % 1000);
+                assert_eq!(
+                    add_mod_u64(a, b, m) as u128,
+                    (a as u128).add_mod(b as u128, wide)
+                );
+                assert_eq!(
+                    mul_mod_u64(a, b, m) as u128,
+                    (a as u128 % wide).mul_mod(b as u128 % wide, wide)
+                );
+                assert_eq!(
+                    pow_mod_u64(a, e, m) as u128,
+                    (a as u128 % wide).pow_mod(e as u128, wide)
+                );
+            }
+            assert_eq!(pow_mod_u64(5, 0, m), 1 % m);
+        }
+    }
+
+    #[test]
+    fn test_invert_u64() {
+        for m in [1u64, 2, 12, 97, 1_000_000_007] {
+            for a in 0..200 {
+                let expected = (a as i128).invert(m).map(|x| x as u64);
+                assert_eq!(invert_u64(a, m), expected, "a = {a}, m = {m}");
+            }
+        }
+        let mut rng = SplitMix64::new(3451);
+        for m in [u64::MAX, u64::MAX - 58, 1 << 63] {
+            for _ in 0..100 {
+                let a = rng.next_u64();
+                let expected = (a as i128).invert(m).map(|x| x as u64);
+                assert_eq!(invert_u64(a, m), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_const_evaluation() {
+        const R: u64 = pow_mod_u64(3, 100, 998_244_353);
+        const INVERSES: [u64; 100] = {
+            let mut table = [0; 100];
+            let mut i = 1;
+            while i < 100 {
+                table[i] = match invert_u64(i as u64, 1_000_000_007) {
+                    Some(inverse) => inverse,
+                    None => panic!("1_000_000_007 is prime"),
+                };
+                i += 1;
+            }
+            table
+        };
+        assert_eq!(R, 3u64.pow_mod(100u64, 998_244_353u64));
+        for (i, &inverse) in INVERSES.iter().enumerate().skip(1) {
+            assert_eq!(mul_mod_u64(i as u64, inverse, 1_000_000_007), 1);
+        }
+    }
+
+    #[test]
+    fn test_is_prime_u64() {
+        for n in 0..10_000u64 {
+            assert_eq!(is_prime_u64(n), crate::is_prime_u64(n), "n = {n}");
+        }
+        let mut rng = SplitMix64::new(346);
+        for _ in 0..1000 {
+            let n = rng.next_u64() | 1;
+            assert_eq!(is_prime_u64(n), crate::is_prime_u64(n), "n = {n}");
+        }
+        for n in [3_215_031_751u64, 3_825_123_056_546_413_051, u64::MAX - 58] {
+            assert_eq!(is_prime_u64(n), crate::is_prime_u64(n), "n = {n}");
+        }
+    }
+
+    #[test]
+    fn test_modulus_macro() {
+        const P: Prime<u64> = modulus!(prime 998_244_353);
+        assert_eq!(3u64.pow_mod(P.get() - 1, P), 1);
+        assert_eq!(7i32.add_mod(-9, modulus!(5u8)), 3);
+        assert_eq!(
+            modulus!(prime 18_446_744_073_709_551_557u64).get(),
+            u64::MAX - 58
+        );
+        assert_eq!(modulus!(1u32), 1);
+        assert_eq!(requires_prime(3, modulus!(prime 7u64)), 5);
+    }
+
+    fn requires_prime<P: PrimeModulus<i64>>(a: i64, p: P) -> i64
+    where
+        i64: TryFrom<P>,
+    {
+        a.invert(p)
+            .expect("nonzero residues are invertible modulo a prime")
+    }
+
+    #[test]
+    #[should_panic(expected = "the modulus must be nonzero")]
+    fn test_zero_modulus() {
+        mul_mod_u64(3, 4, 0);
+    }
+}
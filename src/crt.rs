@@ -0,0 +1,220 @@
+use std::fmt;
+
+use num_traits::CheckedMul;
+
+use crate::{AddMod, Constrain, Egcd, Invert, Modulus, MulMod};
+
+/// An error combining congruences.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CrtError {
+    /// The congruences have no common solution.
+    Inconsistent,
+    /// The combined modulus or an intermediate product does not fit in the integer types.
+    Overflow,
+}
+
+impl fmt::Display for CrtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CrtError::Inconsistent => write!(f, "congruences are inconsistent"),
+            CrtError::Overflow => write!(f, "combined modulus overflows"),
+        }
+    }
+}
+
+impl std::error::Error for CrtError {}
+
+/// Combine the congruences `x ≡ a1 (mod m1)` and `x ≡ a2 (mod m2)` into `x ≡ a (mod lcm(m1, m2))`.
+/// The moduli need not be coprime.
+/// Returns the canonical residue `a` in `[0, lcm(m1, m2))` together with `lcm(m1, m2)`,
+/// or `None` if the congruences are inconsistent or the computation overflows.
+pub fn crt<T, M>(a1: T, m1: M, a2: T, m2: M) -> Option<(T, M)>
+where
+    T: Egcd + TryFrom<M> + CheckedMul,
+    M: Modulus<T> + TryFrom<T> + CheckedMul,
+{
+    combine(a1, m1, a2, m2).ok()
+}
+
+/// Combine a slice of congruences `x ≡ a_i (mod m_i)` into a single congruence `x ≡ a (mod m)`,
+/// where `m` is the least common multiple of all moduli. An empty slice yields `(0, 1)`.
+/// Stops at the first inconsistency. Rather than overflowing silently, reports `CrtError::Overflow`
+/// when the combined modulus does not fit in `T` or `M`, or when the square of a modulus does not fit in `T`.
+pub fn crt_slice<T, M>(congruences: &[(T, M)]) -> Result<(T, M), CrtError>
+where
+    T: Egcd + TryFrom<M> + CheckedMul,
+    M: Modulus<T> + TryFrom<T> + CheckedMul,
+{
+    congruences
+        .iter()
+        .try_fold((T::zero(), M::one()), |(a1, m1), &(a2, m2)| {
+            combine(a1, m1, a2, m2)
+        })
+}
+
+/// Reconstruct the integer `x` in `[0, m_1 · … · m_k)` with `x ≡ residues[i] (mod moduli[i])`
+/// using Garner's algorithm, and return `x mod target`.
+/// All intermediate values stay below the largest modulus, so the product of the moduli may
+/// exceed every machine integer; only the product of two moduli must fit in `T`.
+/// The moduli must be pairwise coprime; returns `None` if they are not.
+/// Panics if the slices differ in length.
+pub fn garner<T, M>(residues: &[T], moduli: &[M], target: M) -> Option<T>
+where
+    T: Invert + TryFrom<M>,
+    M: Modulus<T>,
+{
+    assert_eq!(
+        residues.len(),
+        moduli.len(),
+        "residues and moduli differ in length"
+    );
+    let all_moduli: Vec<M> = moduli.iter().copied().chain(Some(target)).collect();
+    // The running value of x modulo each modulus, and the product of the processed moduli modulo each modulus.
+    let mut constants = vec![T::zero(); all_moduli.len()];
+    let mut coefficients: Vec<T> = all_moduli.iter().map(|&m| T::one().constrain(m)).collect();
+    for (i, (&residue, &modulus)) in residues.iter().zip(moduli).enumerate() {
+        let inverse = coefficients[i].invert(modulus)?;
+        let digit = (residue.constrain(modulus) - constants[i])
+            .constrain(modulus)
+            .mul_mod(inverse, modulus);
+        for (j, &m) in all_moduli.iter().enumerate().skip(i + 1) {
+            constants[j] = constants[j].add_mod(coefficients[j].mul_mod(digit, m), m);
+            coefficients[j] = coefficients[j].mul_mod(modulus.cast().constrain(m), m);
+        }
+    }
+    constants.pop()
+}
+
+fn combine<T, M>(a1: T, m1: M, a2: T, m2: M) -> Result<(T, M), CrtError>
+where
+    T: Egcd + TryFrom<M> + CheckedMul,
+    M: Modulus<T> + TryFrom<T> + CheckedMul,
+{
+    let n1: T = m1.cast();
+    let n2: T = m2.cast();
+    let (g, x) = n1.half_egcd(n2);
+    let a1 = a1.constrain(m1);
+    let diff = a2.constrain(m2) - a1;
+    if diff % g != T::zero() {
+        return Err(CrtError::Inconsistent);
+    }
+    let step = m2 / M::try_from(g).map_err(|_| CrtError::Overflow)?;
+    let lcm = m1.checked_mul(&step).ok_or(CrtError::Overflow)?;
+    T::try_from(lcm).map_err(|_| CrtError::Overflow)?;
+    let step_t: T = step.cast();
+    step_t.checked_mul(&step_t).ok_or(CrtError::Overflow)?;
+    let k = (diff / g).constrain(step).mul_mod(x.constrain(step), step);
+    Ok((a1 + n1 * k, lcm))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_crt_coprime() {
+        assert_eq!(crt(2, 3u32, 3, 5u32), Some((8, 15)));
+        assert_eq!(crt(1, 7u32, 0, 11u32), Some((22, 77)));
+        assert_eq!(crt(0, 1u32, 5, 9u32), Some((5, 9)));
+    }
+
+    #[test]
+    fn test_crt_non_coprime() {
+        assert_eq!(crt(2, 4u32, 4, 6u32), Some((10, 12)));
+        assert_eq!(crt(3, 6u32, 3, 6u32), Some((3, 6)));
+        assert_eq!(crt(5, 10u32, 1, 4u32), Some((5, 20)));
+    }
+
+    #[test]
+    fn test_crt_inconsistent() {
+        assert_eq!(crt(1, 4u32, 2, 6u32), None);
+        assert_eq!(crt(1, 6u32, 2, 6u32), None);
+    }
+
+    #[test]
+    fn test_crt_negative() {
+        assert_eq!(crt(-1, 3u32, -1, 5u32), Some((14, 15)));
+        assert_eq!(crt(-10, 4u32, -2, 6u32), Some((10, 12)));
+        assert_eq!(
+            crt(-7i64, 1_000_000_007u64, 3, 998_244_353u64),
+            Some((949_089_899_643_629_244, 998_244_359_987_710_471))
+        );
+    }
+
+    #[test]
+    fn test_crt_slice() {
+        let value: i128 = 123_456_789_012_345_678_901_234_567;
+        let moduli = [1_000_000_007u128, 998_244_353, 1_000_000_009];
+        let congruences: Vec<(i128, u128)> =
+            moduli.iter().map(|&m| (value % m as i128, m)).collect();
+        let product = moduli.iter().product();
+        assert_eq!(crt_slice(&congruences), Ok((value, product)));
+    }
+
+    #[test]
+    fn test_crt_slice_edge_cases() {
+        assert_eq!(crt_slice::<i32, u32>(&[]), Ok((0, 1)));
+        assert_eq!(crt_slice(&[(-3, 7u32)]), Ok((4, 7)));
+        assert_eq!(crt_slice(&[(2, 4u32), (4, 6), (1, 9)]), Ok((10, 36)));
+    }
+
+    #[test]
+    fn test_crt_slice_inconsistent() {
+        assert_eq!(
+            crt_slice(&[(1, 3u32), (1, 4), (2, 6)]),
+            Err(CrtError::Inconsistent)
+        );
+    }
+
+    #[test]
+    fn test_crt_slice_overflow() {
+        let congruences = [
+            (1i64, 1_000_000_007u64),
+            (2, 998_244_353),
+            (3, 1_000_000_009),
+        ];
+        assert_eq!(crt_slice(&congruences), Err(CrtError::Overflow));
+        let congruences = [(1i32, 251u8), (2, 241)];
+        assert_eq!(crt_slice(&congruences), Err(CrtError::Overflow));
+        assert_eq!(crt(1i32, 251u8, 2, 241), None);
+    }
+
+    #[test]
+    fn test_garner() {
+        // x = 0xDEADBEEFCAFEBABE0123456789ABCDEFFEDCBA9876543 has 180 bits.
+        let moduli = [
+            4_611_686_018_427_387_847u64,
+            4_611_686_018_427_387_817,
+            4_611_686_018_427_387_787,
+        ];
+        let residues = [
+            4_371_650_166_025_625_374i128,
+            4_280_591_460_745_564_147,
+            1_718_647_029_568_219_905,
+        ];
+        assert_eq!(garner(&residues, &moduli, 1_000_000_007), Some(531_345_132));
+        assert_eq!(garner(&residues, &moduli, 998_244_353), Some(159_779_379));
+        assert_eq!(
+            garner(&residues, &moduli, (1 << 61) - 1),
+            Some(106_738_845_545_412_117)
+        );
+        assert_eq!(
+            garner(&residues, &moduli, u64::MAX - 58),
+            Some(4_099_023_810_945_559_165)
+        );
+    }
+
+    #[test]
+    fn test_garner_small() {
+        assert_eq!(garner(&[2, 3, 2], &[3u32, 5, 7], 1000), Some(23));
+        assert_eq!(garner(&[2, 3, 2], &[3u32, 5, 7], 10), Some(3));
+        assert_eq!(garner(&[-1, -2], &[3u32, 5], 1000), Some(8));
+        assert_eq!(garner::<i32, u32>(&[], &[], 10), Some(0));
+    }
+
+    #[test]
+    fn test_garner_not_coprime() {
+        assert_eq!(garner(&[1, 1], &[4u32, 6], 100), None);
+    }
+}
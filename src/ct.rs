@@ -0,0 +1,192 @@
+//! Constant-time helpers for unsigned machine integers.
+//!
+//! The functions here avoid branches and memory accesses that depend on their arguments, apart from the modulus,
+//! which is treated as public. Conditions are carried in a `Choice` and turned into all-ones or all-zeros masks.
+//! Rust gives no guarantee that the compiler preserves this, so the claim rests on the current code generation.
+
+/// A boolean produced or consumed by constant-time operations, stored as `0` or `1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Choice(u8);
+
+impl Choice {
+    /// The choice as `0` or `1`.
+    pub fn unwrap_u8(self) -> u8 {
+        self.0
+    }
+}
+
+impl From<Choice> for bool {
+    fn from(choice: Choice) -> bool {
+        choice.0 == 1
+    }
+}
+
+impl From<bool> for Choice {
+    fn from(value: bool) -> Choice {
+        Choice(value as u8)
+    }
+}
+
+impl std::ops::Not for Choice {
+    type Output = Choice;
+
+    fn not(self) -> Choice {
+        Choice(self.0 ^ 1)
+    }
+}
+
+impl std::ops::BitAnd for Choice {
+    type Output = Choice;
+
+    fn bitand(self, rhs: Choice) -> Choice {
+        Choice(self.0 & rhs.0)
+    }
+}
+
+impl std::ops::BitOr for Choice {
+    type Output = Choice;
+
+    fn bitor(self, rhs: Choice) -> Choice {
+        Choice(self.0 | rhs.0)
+    }
+}
+
+/// The constant-time primitives, implemented for the unsigned machine integers.
+pub trait CtUnsigned: Copy {
+    /// Return `a` if `choice` is set and `b` otherwise.
+    fn ct_select(choice: Choice, a: Self, b: Self) -> Self;
+
+    /// Check if the integer is zero.
+    fn ct_is_zero(self) -> Choice;
+
+    /// Reduce the integer modulo a nonzero public modulus by shift-and-subtract over every bit,
+    /// avoiding the division instruction, whose latency can depend on its operands.
+    fn ct_reduce(self, modulus: Self) -> Self;
+}
+
+macro_rules! ct_unsigned {
+    ($($t:ty),*) => {$(
+        impl CtUnsigned for $t {
+            fn ct_select(choice: Choice, a: $t, b: $t) -> $t {
+                let mask = (choice.0 as $t).wrapping_neg();
+                b ^ (mask & (a ^ b))
+            }
+
+            fn ct_is_zero(self) -> Choice {
+                Choice((((self | self.wrapping_neg()) >> (<$t>::BITS - 1)) ^ 1) as u8)
+            }
+
+            fn ct_reduce(self, modulus: $t) -> $t {
+                assert!(modulus != 0, "the modulus must be nonzero");
+                let mut remainder: $t = 0;
+                for i in (0..<$t>::BITS).rev() {
+                    // The shifted remainder is below `2m`, with the carry as its top bit.
+                    let carry = remainder >> (<$t>::BITS - 1);
+                    remainder = remainder << 1 | (self >> i) & 1;
+                    let (difference, borrow) = remainder.overflowing_sub(modulus);
+                    let subtract = Choice((carry | !borrow as $t) as u8);
+                    remainder = <$t>::ct_select(subtract, difference, remainder);
+                }
+                remainder
+            }
+        }
+    )*};
+}
+
+ct_unsigned!(u8, u16, u32, u64, u128, usize);
+
+/// Return `a` if `choice` is set and `b` otherwise, without branching on `choice`.
+pub fn ct_select<T: CtUnsigned>(choice: Choice, a: T, b: T) -> T {
+    T::ct_select(choice, a, b)
+}
+
+/// Swap `a` and `b` if `choice` is set, without branching on `choice`.
+pub fn ct_swap<T: CtUnsigned>(choice: Choice, a: &mut T, b: &mut T) {
+    let (x, y) = (*a, *b);
+    *a = T::ct_select(choice, y, x);
+    *b = T::ct_select(choice, x, y);
+}
+
+/// Check if two integers are congruent modulo a public modulus in constant time.
+/// Unlike `EqMod::eq_mod`, neither the reduction nor the comparison depends on `a` or `b`.
+/// Panics if the modulus is zero.
+pub fn ct_eq_mod<T>(a: T, b: T, modulus: T) -> Choice
+where
+    T: CtUnsigned + std::ops::BitXor<Output = T>,
+{
+    (a.ct_reduce(modulus) ^ b.ct_reduce(modulus)).ct_is_zero()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EqMod, SplitMix64};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_choice() {
+        let (yes, no) = (Choice::from(true), Choice::from(false));
+        assert_eq!((yes.unwrap_u8(), no.unwrap_u8()), (1, 0));
+        assert!(bool::from(!no));
+        assert!(!bool::from(yes & no));
+        assert!(bool::from(yes | no));
+    }
+
+    #[test]
+    fn test_ct_select() {
+        assert_eq!(ct_select(Choice::from(true), 3u8, 5), 3);
+        assert_eq!(ct_select(Choice::from(false), 3u128, 5), 5);
+        let (mut a, mut b) = (1u64, 2);
+        ct_swap(Choice::from(false), &mut a, &mut b);
+        assert_eq!((a, b), (1, 2));
+        ct_swap(Choice::from(true), &mut a, &mut b);
+        assert_eq!((a, b), (2, 1));
+    }
+
+    #[test]
+    fn test_ct_is_zero() {
+        assert!(bool::from(0u32.ct_is_zero()));
+        for x in [1u32, 2, 1 << 31, u32::MAX] {
+            assert!(!bool::from(x.ct_is_zero()));
+        }
+    }
+
+    #[test]
+    fn test_ct_reduce() {
+        for m in 1..=255u8 {
+            for x in 0..=255u8 {
+                assert_eq!(x.ct_reduce(m), x % m, "x = {x}, m = {m}");
+            }
+        }
+        let mut rng = SplitMix64::new(312);
+        for _ in 0..1000 {
+            let (x, m) = (rng.next_u64(), rng.next_u64() >> (rng.next_u64() % 64));
+            if m != 0 {
+                assert_eq!(x.ct_reduce(m), x % m);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ct_eq_mod() {
+        for m in 1..40u16 {
+            for a in 0..100u16 {
+                for b in 0..100u16 {
+                    assert_eq!(
+                        bool::from(ct_eq_mod(a, b, m)),
+                        a.eq_mod(b, m),
+                        "{a} = {b} (mod {m})"
+                    );
+                }
+            }
+        }
+        assert!(bool::from(ct_eq_mod(u64::MAX, 0, u64::MAX)));
+        assert!(!bool::from(ct_eq_mod(u128::MAX, 0, u128::MAX - 1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "the modulus must be nonzero")]
+    fn test_ct_eq_mod_zero() {
+        ct_eq_mod(1u32, 1, 0);
+    }
+}
@@ -0,0 +1,278 @@
+use crate::Egcd;
+
+/// The solutions of a linear Diophantine equation `a·x + b·y = c` in integers.
+/// They are `(x0 + t·dx, y0 + t·dy)` for every integer `t`, where `(x0, y0)` is the particular solution
+/// and `(dx, dy)` the step, `(b/g, -a/g)` up to sign with `g = gcd(a, b)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiophantineSolution<T> {
+    x0: T,
+    y0: T,
+    dx: T,
+    dy: T,
+    gcd: T,
+}
+
+impl<T: Egcd> DiophantineSolution<T> {
+    /// The particular solution `(x0, y0)`. If `b ≠ 0`, `x0` is the smallest non-negative `x` of any solution;
+    /// if `b = 0`, `x0 = c/a` is the only possible `x` and `y0 = 0`.
+    pub fn particular(&self) -> (T, T) {
+        (self.x0, self.y0)
+    }
+
+    /// The step `(dx, dy)` between consecutive solutions, with `dx = |b|/g` positive unless `b = 0`.
+    pub fn step(&self) -> (T, T) {
+        (self.dx, self.dy)
+    }
+
+    /// The greatest common divisor `g` of `a` and `b`, which is positive.
+    pub fn gcd(&self) -> T {
+        self.gcd
+    }
+
+    /// The solution `(x0 + t·dx, y0 + t·dy)` for the parameter `t`.
+    pub fn at(&self, t: T) -> (T, T) {
+        (self.x0 + t * self.dx, self.y0 + t * self.dy)
+    }
+
+    /// Iterate over the solutions with `lo ≤ x ≤ hi` in increasing order of `x`.
+    /// Panics if `b = 0` and `x0` is in the range, since `x` is then fixed and every `y` gives a solution.
+    pub fn with_x_in_range(&self, lo: T, hi: T) -> DiophantineIter<T> {
+        let empty = DiophantineIter {
+            x: self.x0,
+            y: self.y0,
+            dx: self.dx,
+            dy: self.dy,
+            remaining: T::zero(),
+        };
+        if self.dx == T::zero() {
+            assert!(
+                self.x0 < lo || self.x0 > hi,
+                "x is fixed when b is zero, so there are infinitely many solutions in the range"
+            );
+            return empty;
+        }
+        let first = T::zero() - floor_div(self.x0 - lo, self.dx);
+        let last = floor_div(hi - self.x0, self.dx);
+        if first > last {
+            return empty;
+        }
+        let (x, y) = self.at(first);
+        DiophantineIter {
+            x,
+            y,
+            remaining: last - first + T::one(),
+            ..empty
+        }
+    }
+}
+
+/// An iterator over the solutions of a linear Diophantine equation with `x` in a range.
+#[derive(Clone, Debug)]
+pub struct DiophantineIter<T> {
+    x: T,
+    y: T,
+    dx: T,
+    dy: T,
+    remaining: T,
+}
+
+impl<T: Egcd> DiophantineIter<T> {
+    /// The number of solutions not yet produced.
+    pub fn remaining(&self) -> T {
+        self.remaining
+    }
+}
+
+impl<T: Egcd> Iterator for DiophantineIter<T> {
+    type Item = (T, T);
+
+    fn next(&mut self) -> Option<(T, T)> {
+        if self.remaining == T::zero() {
+            return None;
+        }
+        let current = (self.x, self.y);
+        self.remaining = self.remaining - T::one();
+        if self.remaining != T::zero() {
+            self.x = self.x + self.dx;
+            self.y = self.y + self.dy;
+        }
+        Some(current)
+    }
+}
+
+/// Solve the linear Diophantine equation `a·x + b·y = c` in integers.
+/// A solution exists if and only if `g = gcd(a, b)` divides `c`; they then form the family described by `DiophantineSolution`.
+/// Returns `None` if there is no solution, and also if `a = b = 0`, when every pair solves `0 = 0` for `c = 0`.
+/// The coefficient of `a` is found with `half_egcd` and reduced modulo `|b|/g` before `y0` is derived,
+/// so only `(|b|/g)²` and `|a|·|b|/g + |c|` must fit in `T`.
+pub fn solve_diophantine<T: Egcd>(a: T, b: T, c: T) -> Option<DiophantineSolution<T>> {
+    let zero = T::zero();
+    if b == zero {
+        if a == zero || c % a != zero {
+            return None;
+        }
+        return Some(DiophantineSolution {
+            x0: c / a,
+            y0: zero,
+            dx: zero,
+            dy: zero - a.signum(),
+            gcd: a.abs(),
+        });
+    }
+    let (g, x) = a.half_egcd(b);
+    // `half_egcd` may return a negative gcd for negative arguments.
+    let (g, x) = if g < zero {
+        (zero - g, zero - x)
+    } else {
+        (g, x)
+    };
+    if c % g != zero {
+        return None;
+    }
+    // `a·x ≡ g (mod b)`, so `x·(c/g)` solves `a·x ≡ c (mod b)`, and the solutions repeat with period `|b|/g`.
+    let dx = b.abs() / g;
+    let x0 = rem_euclid(rem_euclid(x, dx) * rem_euclid(c / g, dx), dx);
+    let y0 = (c - a * x0) / b;
+    let dy = if b > zero { zero - a / g } else { a / g };
+    Some(DiophantineSolution {
+        x0,
+        y0,
+        dx,
+        dy,
+        gcd: g,
+    })
+}
+
+/// The remainder of `n` modulo a positive `m`, in `[0, m)`.
+fn rem_euclid<T: Egcd>(n: T, m: T) -> T {
+    let r = n % m;
+    if r < T::zero() {
+        r + m
+    } else {
+        r
+    }
+}
+
+/// The quotient of `n` by a positive `m`, rounded down.
+fn floor_div<T: Egcd>(n: T, m: T) -> T {
+    let q = n / m;
+    if n % m < T::zero() {
+        q - T::one()
+    } else {
+        q
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_solve_diophantine() {
+        let solution = solve_diophantine(3, 5, 7).unwrap();
+        assert_eq!(solution.particular(), (4, -1));
+        assert_eq!(solution.step(), (5, -3));
+        assert_eq!(solution.gcd(), 1);
+        assert_eq!(solution.at(-1), (-1, 2));
+        let solution = solve_diophantine(12i64, 18, 30).unwrap();
+        assert_eq!(solution.particular(), (1, 1));
+        assert_eq!(solution.step(), (3, -2));
+        assert_eq!(solution.gcd(), 6);
+    }
+
+    #[test]
+    fn test_solve_diophantine_negative() {
+        let solution = solve_diophantine(-4, 6, 10).unwrap();
+        assert_eq!(solution.particular(), (2, 3));
+        assert_eq!(solution.step(), (3, 2));
+        let solution = solve_diophantine(4, -6, 10).unwrap();
+        assert_eq!(solution.particular(), (1, -1));
+        assert_eq!(solution.step(), (3, 2));
+        let solution = solve_diophantine(-4, -6, -10).unwrap();
+        assert_eq!(solution.particular(), (1, 1));
+        assert_eq!(solution.step(), (3, -2));
+        assert_eq!(solution.gcd(), 2);
+    }
+
+    #[test]
+    fn test_solve_diophantine_zero() {
+        let solution = solve_diophantine(4, 6, 0).unwrap();
+        assert_eq!(solution.particular(), (0, 0));
+        assert_eq!(solution.step(), (3, -2));
+        let solution = solve_diophantine(0, -5, 15).unwrap();
+        assert_eq!(solution.particular(), (0, -3));
+        assert_eq!(solution.step(), (1, 0));
+        let solution = solve_diophantine(-5, 0, 15).unwrap();
+        assert_eq!(solution.particular(), (-3, 0));
+        assert_eq!(solution.step(), (0, 1));
+        assert_eq!(solution.gcd(), 5);
+    }
+
+    #[test]
+    fn test_solve_diophantine_none() {
+        assert_eq!(solve_diophantine(2, 4, 5), None);
+        assert_eq!(solve_diophantine(-6, 9, 4), None);
+        assert_eq!(solve_diophantine(0, 4, 2), None);
+        assert_eq!(solve_diophantine(4, 0, 2), None);
+        assert_eq!(solve_diophantine(0, 0, 0), None);
+        assert_eq!(solve_diophantine(0, 0, 1), None);
+    }
+
+    #[test]
+    fn test_solve_diophantine_brute_force() {
+        for a in -12i32..=12 {
+            for b in -12i32..=12 {
+                for c in -30i32..=30 {
+                    let solution = solve_diophantine(a, b, c);
+                    if b == 0 {
+                        let solvable = a != 0 && c % a == 0;
+                        assert_eq!(solution.is_some(), solvable, "{a}x + {b}y = {c}");
+                        continue;
+                    }
+                    // The solutions repeat with a period of at most 12 in `x`, so this range decides existence.
+                    let brute: Vec<(i32, i32)> = (-40..=40)
+                        .filter(|&x| (c - a * x) % b == 0)
+                        .map(|x| (x, (c - a * x) / b))
+                        .collect();
+                    let Some(solution) = solution else {
+                        assert!(brute.is_empty(), "{a}x + {b}y = {c}");
+                        continue;
+                    };
+                    let (x0, y0) = solution.particular();
+                    assert_eq!(a * x0 + b * y0, c, "{a}x + {b}y = {c}");
+                    assert!((0..solution.step().0).contains(&x0));
+                    let found: Vec<(i32, i32)> = solution.with_x_in_range(-40, 40).collect();
+                    assert_eq!(found, brute, "{a}x + {b}y = {c}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_with_x_in_range() {
+        let solution = solve_diophantine(3, 5, 7).unwrap();
+        let solutions = solution.with_x_in_range(-6, 10);
+        assert_eq!(solutions.remaining(), 4);
+        assert_eq!(
+            solutions.collect::<Vec<_>>(),
+            vec![(-6, 5), (-1, 2), (4, -1), (9, -4)]
+        );
+        assert_eq!(solution.with_x_in_range(5, 8).next(), None);
+        assert_eq!(
+            solution.with_x_in_range(4, 4).collect::<Vec<_>>(),
+            vec![(4, -1)]
+        );
+        assert_eq!(solution.with_x_in_range(10, -10).next(), None);
+        let fixed = solve_diophantine(-5, 0, 15).unwrap();
+        assert_eq!(fixed.with_x_in_range(0, 10).next(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "x is fixed when b is zero")]
+    fn test_with_x_in_range_infinite() {
+        solve_diophantine(-5, 0, 15)
+            .unwrap()
+            .with_x_in_range(-10, 10);
+    }
+}
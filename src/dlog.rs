@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use crate::{crt_slice, factorize, Gcd, MulMod, PowMod};
+
+/// Find the smallest `x ≥ 0` with `base^x ≡ target (mod m)` using the baby-step giant-step algorithm,
+/// which takes `O(√m)` time and memory.
+/// `base` need not be coprime to `m`: common factors are peeled off first, which costs `O(log m)` extra steps.
+/// Returns `None` if there is no solution or `m` is zero.
+pub fn discrete_log<T>(base: T, target: T, m: T) -> Option<T>
+where
+    T: Into<u64> + TryFrom<u64>,
+    <T as TryFrom<u64>>::Error: Debug,
+{
+    let x = discrete_log_u64(base.into(), target.into(), m.into())?;
+    Some(T::try_from(x).expect("the logarithm is smaller than the modulus"))
+}
+
+/// Find the smallest `x ≥ 0` with `base^x ≡ target (mod m)` with the Pohlig–Hellman algorithm,
+/// given the factorization `[(q, e), ...]` of some `n` with `base^n ≡ 1 (mod m)`, such as the order of the group of units.
+/// The logarithm is solved one base-`q` digit at a time in the subgroups of prime order,
+/// so the cost is dominated by `O(Σ e·√q)` baby-step giant-step work instead of `O(√m)`.
+/// Returns `None` if there is no solution or `base^n ≢ 1 (mod m)`.
+pub fn discrete_log_ph<T>(base: T, target: T, m: T, order_factors: &[(T, u32)]) -> Option<T>
+where
+    T: Into<u64> + TryFrom<u64> + Copy,
+    <T as TryFrom<u64>>::Error: Debug,
+{
+    let factors: Vec<(u64, u32)> = order_factors.iter().map(|&(q, e)| (q.into(), e)).collect();
+    let x = pohlig_hellman(base.into(), target.into(), m.into(), &factors)?;
+    Some(T::try_from(x).expect("the logarithm is smaller than the modulus"))
+}
+
+/// Find the smallest `x ≥ 0` with `base^x ≡ target (mod p)` for a prime `p`
+/// with the Pohlig–Hellman algorithm, factorizing the group order `p - 1` first.
+/// Returns `None` if there is no solution or `base` is divisible by `p`.
+pub fn discrete_log_ph_prime<T>(base: T, target: T, p: T) -> Option<T>
+where
+    T: Into<u64> + TryFrom<u64> + Copy,
+    <T as TryFrom<u64>>::Error: Debug,
+{
+    let p = p.into();
+    if p < 2 {
+        return None;
+    }
+    let x = pohlig_hellman(base.into(), target.into(), p, &factorize(p - 1))?;
+    Some(T::try_from(x).expect("the logarithm is smaller than the modulus"))
+}
+
+fn pohlig_hellman(base: u64, target: u64, m: u64, factors: &[(u64, u32)]) -> Option<u64> {
+    if m == 0 {
+        return None;
+    }
+    let pow = |a: u64, e: u64| (a as u128).pow_mod(e as u128, m) as u64;
+    let (a, b) = (base % m, target % m);
+    let n = factors
+        .iter()
+        .try_fold(1u64, |n, &(q, e)| n.checked_mul(q.checked_pow(e)?))?;
+    if pow(a, n) != 1 % m {
+        return None;
+    }
+    // Shrink `n` to the exact order of `a`, so that each projected generator has full prime-power order.
+    let mut order = n;
+    let mut congruences = Vec::new();
+    for &(q, e) in factors {
+        let mut e = e;
+        while e > 0 && pow(a, order / q) == 1 % m {
+            order /= q;
+            e -= 1;
+        }
+        congruences.push((q, e));
+    }
+    let mut residues = Vec::with_capacity(congruences.len());
+    for (q, e) in congruences {
+        let qe = q.pow(e);
+        // `g` has order exactly `q^e` and `gamma` order `q`.
+        let g = pow(a, order / qe);
+        let h = pow(b, order / qe);
+        let g_inv = pow(g, qe - 1);
+        let gamma = pow(g, qe / q);
+        let mut x = 0;
+        let mut q_k = 1;
+        for _ in 0..e {
+            // Strip the digits found so far and project onto the subgroup of order `q`.
+            let stripped = (h as u128).mul_mod(pow(g_inv, x) as u128, m) as u64;
+            let h_k = pow(stripped, qe / q_k / q);
+            let d = if h_k == 1 % m {
+                0
+            } else {
+                baby_step_giant_step(gamma, 1, h_k, m, q)?
+            };
+            x += d * q_k;
+            q_k *= q;
+        }
+        residues.push((x as i128, qe));
+    }
+    let (x, _) = crt_slice(&residues).ok()?;
+    let x = x as u64;
+    (pow(a, x) == b).then_some(x)
+}
+
+fn discrete_log_u64(base: u64, target: u64, mut m: u64) -> Option<u64> {
+    if m == 0 {
+        return None;
+    }
+    let (mut a, mut b) = (base % m, target % m);
+    // Solve `k · a^x ≡ b (mod m)` while `a` shares a factor `g` with `m`,
+    // dividing through by `g` and taking one power of `a` into `k` each time.
+    let (mut k, mut offset) = (1 % m, 0);
+    loop {
+        if k == b {
+            return Some(offset);
+        }
+        let g = a.gcd(m);
+        if g == 1 {
+            break;
+        }
+        if b % g != 0 {
+            return None;
+        }
+        (b, m) = (b / g, m / g);
+        k = (k as u128).mul_mod((a / g) as u128, m) as u64;
+        a %= m;
+        offset += 1;
+    }
+    baby_step_giant_step(a, k, b, m, m).map(|x| offset + x)
+}
+
+/// Find the smallest `x` in `1..=n²`, where `n = ⌊√bound⌋ + 1`, with `k · a^x ≡ b (mod m)`
+/// for `a` coprime to `m`, or `None` if there is none.
+/// Any `bound` at least the order of `a` makes the search exhaustive.
+fn baby_step_giant_step(a: u64, k: u64, b: u64, m: u64, bound: u64) -> Option<u64> {
+    let n = bound.isqrt() + 1;
+    // Baby steps `b · a^j` for `0 ≤ j < n`, keeping the largest `j` so the first match is the smallest `x`.
+    let mut baby = HashMap::with_capacity(n as usize);
+    let mut step = b as u128;
+    for j in 0..n {
+        baby.insert(step as u64, j);
+        step = step.mul_mod(a as u128, m);
+    }
+    // Giant steps `k · a^(i·n)` for `1 ≤ i ≤ n`, matching `x = i·n - j`.
+    let giant = (a as u128).pow_mod(n as u128, m);
+    let mut step = k as u128;
+    for i in 1..=n {
+        step = step.mul_mod(giant, m);
+        if let Some(j) = baby.get(&(step as u64)) {
+            return Some(i * n - j);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SplitMix64;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_discrete_log() {
+        assert_eq!(discrete_log(3u32, 13, 17), Some(4));
+        assert_eq!(discrete_log(2u32, 1, 7), Some(0));
+        assert_eq!(discrete_log(2u32, 4, 7), Some(2));
+        assert_eq!(discrete_log(2u32, 3, 7), None);
+        assert_eq!(discrete_log(5u32, 0, 1), Some(0));
+        assert_eq!(discrete_log(5u32, 3, 0), None);
+    }
+
+    #[test]
+    fn test_discrete_log_not_coprime() {
+        assert_eq!(discrete_log(2u32, 0, 8), Some(3));
+        assert_eq!(discrete_log(6u32, 0, 8), Some(3));
+        assert_eq!(discrete_log(2u32, 3, 8), None);
+        assert_eq!(discrete_log(4u32, 6, 10), Some(2));
+        assert_eq!(discrete_log(0u32, 0, 10), Some(1));
+    }
+
+    #[test]
+    fn test_discrete_log_brute_force() {
+        for m in 1..60u64 {
+            for a in 0..m {
+                for b in 0..m {
+                    let mut x = 1 % m;
+                    let expected = (0..2 * m).find(|_| {
+                        let hit = x == b;
+                        x = x * a % m;
+                        hit
+                    });
+                    assert_eq!(discrete_log(a, b, m), expected, "{a}^x = {b} (mod {m})");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_discrete_log_large() {
+        let p = 1_099_511_627_791u64;
+        let mut rng = SplitMix64::new(p);
+        for _ in 0..2 {
+            let e = rng.next_u64() % (p - 1);
+            let target = (3u128).pow_mod(e as u128, p) as u64;
+            assert_eq!(discrete_log(3, target, p), Some(e));
+        }
+    }
+
+    #[test]
+    fn test_discrete_log_ph() {
+        assert_eq!(discrete_log_ph(3u32, 13, 17, &[(2, 4)]), Some(4));
+        assert_eq!(discrete_log_ph_prime(3u32, 13, 17), Some(4));
+        assert_eq!(discrete_log_ph_prime(2u32, 3, 7), None);
+        assert_eq!(discrete_log_ph_prime(7u32, 3, 7), None);
+        // `n` must be a multiple of the order of the base.
+        assert_eq!(discrete_log_ph(3u32, 13, 17, &[(2, 3)]), None);
+        // The order of 2 modulo 7 is 3, and φ(7) = 6 is an acceptable multiple.
+        assert_eq!(discrete_log_ph(2u32, 4, 7, &[(2, 1), (3, 1)]), Some(2));
+    }
+
+    #[test]
+    fn test_discrete_log_ph_matches_bsgs() {
+        for m in 2..80u64 {
+            let factors = factorize(crate::euler_phi(m));
+            for a in (1..m).filter(|&a| a.gcd(m) == 1) {
+                for b in 0..m {
+                    assert_eq!(
+                        discrete_log_ph(a, b, m, &factors),
+                        discrete_log(a, b, m),
+                        "{a}^x = {b} (mod {m})"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_discrete_log_ph_smooth() {
+        // p - 1 = 2 · 3 · 5² · 19 · 53² · 59² · 67 · 73 · 83 · 89
+        let p = 1_006_853_732_411_320_051u64;
+        let mut rng = SplitMix64::new(p);
+        for _ in 0..10 {
+            let e = rng.next_u64() % (p - 1);
+            let target = (3u128).pow_mod(e as u128, p) as u64;
+            assert_eq!(discrete_log_ph_prime(3, target, p), Some(e));
+            let target = (5u128).pow_mod(e as u128, p) as u64;
+            let x = discrete_log_ph_prime(5, target, p).unwrap();
+            assert_eq!((5u128).pow_mod(x as u128, p) as u64, target);
+        }
+    }
+}
@@ -1,29 +1,79 @@
 use num_traits::Signed;
 
-use crate::Integer;
+use crate::{Gcd, Integer};
 
 /// A trait to compute the extended greatest common divisor of two integers.
-pub trait Egcd: Integer + Signed {
+/// It is implemented for every signed integer with a `Gcd`.
+pub trait Egcd: Gcd + Signed {
     /// Compute the extended greatest common divisor of two integers.
     /// Returns `(d, x, y)` such that `d = gcd(self, other) = self * x + other * y`.
     fn egcd(self, other: Self) -> (Self, Self, Self) {
         egcd(self, other)
     }
+
+    /// Compute the greatest common divisor and only the coefficient of `self` in the extended greatest common divisor.
+    /// Returns `(d, x)` such that `d = gcd(self, other)` and `self * x ≡ d (mod other)`,
+    /// with the same `d` and `x` as `egcd`. Skipping the other coefficient saves about a third of the work,
+    /// which is all that inversion and division need.
+    fn half_egcd(self, other: Self) -> (Self, Self) {
+        half_egcd(self, other)
+    }
 }
 
-impl<T: Integer + Signed> Egcd for T {}
+impl<T: Gcd + Signed> Egcd for T {}
 
 fn egcd<T: Integer>(a: T, b: T) -> (T, T, T) {
-    if b == T::zero() {
-        return (a, T::one(), T::zero());
+    let start = ((T::one(), T::zero()), (T::zero(), T::one()));
+    let [(d, (x, y)), _] = euclid(
+        a,
+        b,
+        |r| r != T::zero(),
+        start,
+        |(x0, y0), q, (x1, y1)| (x0 - q * x1, y0 - q * y1),
+    );
+    (d, x, y)
+}
+
+fn half_egcd<T: Integer>(a: T, b: T) -> (T, T) {
+    remainder_sequence(a, b, |r| r != T::zero())[0]
+}
+
+/// Run the extended Euclidean algorithm on `a` and `b` while `proceed` accepts the last remainder,
+/// tracking only the coefficients of `a`. Returns the last two remainders with their coefficients,
+/// each pair `(r, x)` satisfying `a * x ≡ r (mod b)`.
+pub(crate) fn remainder_sequence<T: Integer>(
+    a: T,
+    b: T,
+    proceed: impl FnMut(T) -> bool,
+) -> [(T, T); 2] {
+    euclid(a, b, proceed, (T::one(), T::zero()), |x0, q, x1| {
+        x0 - q * x1
+    })
+}
+
+/// The Euclidean loop shared by `egcd` and `remainder_sequence`, tracking coefficients of any shape.
+/// `start` holds the coefficients of `a` and `b`, and `step(c0, q, c1)` computes `c0 - q * c1`.
+fn euclid<T: Integer, C: Copy>(
+    a: T,
+    b: T,
+    mut proceed: impl FnMut(T) -> bool,
+    start: (C, C),
+    step: impl Fn(C, T, C) -> C,
+) -> [(T, C); 2] {
+    let (mut r0, mut r1) = (a, b);
+    let (mut c0, mut c1) = start;
+    while r1 != T::zero() && proceed(r1) {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 % r1);
+        (c0, c1) = (c1, step(c0, q, c1));
     }
-    let (d, x, y) = egcd(b, a % b);
-    (d, y, x - (a / b) * y)
+    [(r0, c0), (r1, c1)]
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::SplitMix64;
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -33,5 +83,38 @@ mod tests {
         assert_eq!(egcd(1432, 123211), (1, -22973, 267));
         assert_eq!(egcd(14, 28), (14, 1, 0));
         assert_eq!(egcd(28, 14), (14, 0, 1));
+        assert_eq!(egcd(0, 14), (14, 0, 1));
+        assert_eq!(egcd(14, 0), (14, 1, 0));
+        assert_eq!(egcd(-102, 38), (-2, 3, 8));
+        let (a, b) = (i64::MAX, i64::MAX - 1);
+        assert_eq!(egcd(a, b), (1, 1, -1));
+        let (d, x, y) = egcd(i64::MAX - 24, 1 << 62);
+        assert_eq!(
+            (i64::MAX - 24) as i128 * x as i128 + (1i128 << 62) * y as i128,
+            d as i128
+        );
+    }
+
+    #[test]
+    fn test_half_egcd() {
+        assert_eq!(half_egcd(102, 38), (2, 3));
+        assert_eq!(half_egcd(1432, 123211), (1, -22973));
+        assert_eq!(half_egcd(14, 0), (14, 1));
+        assert_eq!(half_egcd(0, 14), (14, 0));
+        let mut rng = SplitMix64::new(358);
+        for _ in 0..10_000 {
+            let a = rng.next_u64() as i64 >> (rng.next_u64() % 63);
+            let b = rng.next_u64() as i64 >> (rng.next_u64() % 63);
+            let (d, x) = a.half_egcd(b);
+            let (e, y, _) = a.egcd(b);
+            assert_eq!((d, x), (e, y), "a = {a}, b = {b}");
+            if b != 0 {
+                assert_eq!(
+                    (a as i128 * x as i128 - d as i128) % b as i128,
+                    0,
+                    "a = {a}, b = {b}"
+                );
+            }
+        }
     }
 }
@@ -0,0 +1,171 @@
+use crate::{binary_gcd, is_prime_u64, AddMod, MulMod, SplitMix64};
+
+/// Factorize an integer into primes, returning `(prime, exponent)` pairs sorted by prime.
+/// Small factors are removed by trial division and the rest is split with Pollard's rho
+/// using Brent's cycle detection, with the deterministic Miller–Rabin test deciding when to stop.
+/// Both `0` and `1` have an empty factorization.
+pub fn factorize(n: u64) -> Vec<(u64, u32)> {
+    let mut primes = Vec::new();
+    let mut n = n;
+    if n == 0 {
+        return Vec::new();
+    }
+    for p in 2..TRIAL_DIVISION_LIMIT {
+        if p * p > n {
+            break;
+        }
+        while n.is_multiple_of(p) {
+            primes.push(p);
+            n /= p;
+        }
+    }
+    let mut stack = vec![n];
+    while let Some(n) = stack.pop() {
+        if n == 1 {
+            continue;
+        }
+        if n < TRIAL_DIVISION_LIMIT * TRIAL_DIVISION_LIMIT || is_prime_u64(n) {
+            primes.push(n);
+            continue;
+        }
+        let d = pollard_brent(n);
+        stack.push(d);
+        stack.push(n / d);
+    }
+    primes.sort_unstable();
+    let mut factors: Vec<(u64, u32)> = Vec::new();
+    for p in primes {
+        match factors.last_mut() {
+            Some((q, e)) if *q == p => *e += 1,
+            _ => factors.push((p, 1)),
+        }
+    }
+    factors
+}
+
+/// Trial division removes all prime factors below this limit.
+const TRIAL_DIVISION_LIMIT: u64 = 1 << 10;
+
+/// Find a nontrivial factor of an odd composite `n` without small factors
+/// with Pollard's rho algorithm and Brent's cycle detection.
+/// Differences are accumulated into a product so that a gcd is taken only every `BATCH` steps.
+fn pollard_brent(n: u64) -> u64 {
+    const BATCH: u64 = 128;
+    let mut rng = SplitMix64::new(n);
+    loop {
+        let c = (rng.next_u64() % (n - 1) + 1) as u128;
+        let f = |x: u128| x.mul_mod(x, n).add_mod(c, n);
+        let mut y = (rng.next_u64() % n) as u128;
+        let (mut x, mut ys) = (y, y);
+        let (mut g, mut r, mut q) = (1, 1, 1u128);
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = f(y);
+            }
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys = y;
+                for _ in 0..BATCH.min(r - k) {
+                    y = f(y);
+                    q = q.mul_mod(x.abs_diff(y), n);
+                }
+                g = binary_gcd(q as u64, n);
+                k += BATCH;
+            }
+            r *= 2;
+        }
+        if g == n {
+            // The batch overshot; retrace it one step at a time.
+            loop {
+                ys = f(ys);
+                g = binary_gcd(x.abs_diff(ys) as u64, n);
+                if g > 1 {
+                    break;
+                }
+            }
+        }
+        if g != n {
+            return g;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn product(factors: &[(u64, u32)]) -> u64 {
+        factors.iter().map(|&(p, e)| p.pow(e)).product()
+    }
+
+    #[test]
+    fn test_factorize_small() {
+        assert_eq!(factorize(0), vec![]);
+        assert_eq!(factorize(1), vec![]);
+        assert_eq!(factorize(2), vec![(2, 1)]);
+        assert_eq!(factorize(360), vec![(2, 3), (3, 2), (5, 1)]);
+        for n in 1..10_000 {
+            let factors = factorize(n);
+            assert_eq!(product(&factors), n);
+            assert!(factors.iter().all(|&(p, _)| is_prime_u64(p)));
+        }
+    }
+
+    #[test]
+    fn test_factorize_semiprimes() {
+        assert_eq!(
+            factorize(1_000_000_007 * 998_244_353),
+            vec![(998_244_353, 1), (1_000_000_007, 1)]
+        );
+        assert_eq!(
+            factorize(2_147_483_647 * 1_073_741_789),
+            vec![(1_073_741_789, 1), (2_147_483_647, 1)]
+        );
+        assert_eq!(
+            factorize(4_294_967_291 * 4_294_967_279),
+            vec![(4_294_967_279, 1), (4_294_967_291, 1)]
+        );
+        assert_eq!(
+            factorize(4_294_967_291 * 4_294_967_291),
+            vec![(4_294_967_291, 2)]
+        );
+    }
+
+    #[test]
+    fn test_factorize_prime_powers() {
+        assert_eq!(factorize(1 << 63), vec![(2, 63)]);
+        assert_eq!(factorize(3u64.pow(40)), vec![(3, 40)]);
+        assert_eq!(factorize(1_000_003u64.pow(3)), vec![(1_000_003, 3)]);
+        let n = (1 << 10) * 3u64.pow(5) * 1_000_003u64.pow(2);
+        assert_eq!(factorize(n), vec![(2, 10), (3, 5), (1_000_003, 2)]);
+    }
+
+    #[test]
+    fn test_factorize_large() {
+        assert_eq!(
+            factorize(9_223_372_036_854_775_783),
+            vec![(9_223_372_036_854_775_783, 1)]
+        );
+        assert_eq!(
+            factorize(u64::MAX),
+            vec![
+                (3, 1),
+                (5, 1),
+                (17, 1),
+                (257, 1),
+                (641, 1),
+                (65_537, 1),
+                (6_700_417, 1)
+            ]
+        );
+        let mut rng = SplitMix64::new(7);
+        for _ in 0..200 {
+            let n = rng.next_u64();
+            let factors = factorize(n);
+            assert_eq!(product(&factors), n);
+            assert!(factors.iter().all(|&(p, _)| is_prime_u64(p)));
+        }
+    }
+}
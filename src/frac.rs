@@ -0,0 +1,198 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use num_traits::{CheckedAdd, CheckedMul};
+
+use crate::{rational_reconstruct, Invert, Modulus, Residue};
+
+/// A fraction `p/q` modulo a modulus chosen at run time, stored as the residue `p · q^-1`.
+/// This carries exact rational quantities, such as probabilities and expected values, through modular computations.
+/// Operations between fractions panic if their moduli differ, as they do for `Residue`.
+///
+/// Equal fractions have equal residues, so `1/2 + 1/3 == 5/6`; distinct fractions may share a residue, since only
+/// the residue is stored. `reconstruct` recovers the fraction when both its numerator and denominator are small.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FracMod<T, M>(Residue<T, M>);
+
+impl<T, M> FracMod<T, M>
+where
+    T: Invert + TryFrom<M> + CheckedAdd + CheckedMul,
+    M: Modulus<T>,
+{
+    /// Create the fraction `p/q` modulo `m`, or return `None` if `q` is not invertible modulo `m`.
+    /// Panics if the modulus is zero.
+    pub fn new(p: T, q: T, modulus: M) -> Option<Self> {
+        Residue::new(p, modulus)
+            .checked_div(Residue::new(q, modulus))
+            .map(Self)
+    }
+
+    /// Create the fraction `n/1` modulo `m`.
+    /// Panics if the modulus is zero.
+    pub fn from_integer(n: T, modulus: M) -> Self {
+        Self(Residue::new(n, modulus))
+    }
+
+    /// The residue `p · q^-1` in `[0, m)`.
+    pub fn value(&self) -> T {
+        self.0.value()
+    }
+
+    /// The modulus.
+    pub fn modulus(&self) -> M {
+        self.0.modulus()
+    }
+
+    /// The residue as a `Residue`.
+    pub fn residue(&self) -> Residue<T, M> {
+        self.0
+    }
+
+    /// The inverse `q/p`, or `None` if the numerator is not invertible.
+    pub fn inverse(self) -> Option<Self> {
+        self.0.inverse().map(Self)
+    }
+
+    /// Divide by another fraction, or return `None` if the divisor is not invertible.
+    /// Panics if the moduli differ.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        self.0.checked_div(rhs.0).map(Self)
+    }
+
+    /// Recover the fraction `(p, q)` with `q > 0` from the residue with `rational_reconstruct`, for debugging,
+    /// or return `None` if no fraction with `|p|, q ≤ √(m/2)` has this residue.
+    pub fn reconstruct(&self) -> Option<(T, T)> {
+        rational_reconstruct(self.value(), self.modulus())
+    }
+}
+
+impl<T, M> From<Residue<T, M>> for FracMod<T, M> {
+    fn from(residue: Residue<T, M>) -> Self {
+        Self(residue)
+    }
+}
+
+impl<T, M> From<FracMod<T, M>> for Residue<T, M> {
+    fn from(fraction: FracMod<T, M>) -> Self {
+        fraction.0
+    }
+}
+
+impl<T: fmt::Display, M: fmt::Display> fmt::Display for FracMod<T, M> {
+    /// Format the residue, or `a (mod m)` with the alternate flag.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+macro_rules! binary {
+    ($($trait:ident $method:ident $assign:ident $assign_method:ident),*) => {$(
+        impl<T, M> $trait for FracMod<T, M>
+        where
+            T: Invert + TryFrom<M> + CheckedAdd + CheckedMul,
+            M: Modulus<T>,
+        {
+            type Output = Self;
+
+            fn $method(self, rhs: Self) -> Self {
+                Self(self.0.$method(rhs.0))
+            }
+        }
+
+        impl<T, M> $assign for FracMod<T, M>
+        where
+            T: Invert + TryFrom<M> + CheckedAdd + CheckedMul,
+            M: Modulus<T>,
+        {
+            fn $assign_method(&mut self, rhs: Self) {
+                *self = self.$method(rhs);
+            }
+        }
+    )*};
+}
+
+binary!(
+    Add add AddAssign add_assign,
+    Sub sub SubAssign sub_assign,
+    Mul mul MulAssign mul_assign,
+    Div div DivAssign div_assign
+);
+
+impl<T, M> Neg for FracMod<T, M>
+where
+    T: Invert + TryFrom<M> + CheckedAdd + CheckedMul,
+    M: Modulus<T>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Gcd;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_frac_mod_arithmetic() {
+        for m in [7u64, 101, 65_537, 1_000_000_007, 998_244_353] {
+            let half = FracMod::new(1i64, 2, m).unwrap();
+            let third = FracMod::new(1i64, 3, m).unwrap();
+            assert_eq!(half + third, FracMod::new(5, 6, m).unwrap());
+            assert_eq!(half - third, FracMod::new(1, 6, m).unwrap());
+            assert_eq!(half * third, FracMod::new(1, 6, m).unwrap());
+            assert_eq!(half / third, FracMod::new(3, 2, m).unwrap());
+            assert_eq!(-half, FracMod::new(-1, 2, m).unwrap());
+            assert_eq!(third.inverse(), Some(FracMod::from_integer(3, m)));
+            let mut sum = FracMod::from_integer(0, m);
+            sum += half;
+            sum += half;
+            assert_eq!(sum, FracMod::from_integer(1, m));
+        }
+        // The expected value of a fair die.
+        let m = 1_000_000_007u64;
+        let sixth = FracMod::new(1i64, 6, m).unwrap();
+        let expected = (1..=6).fold(FracMod::from_integer(0, m), |acc, face| {
+            acc + FracMod::from_integer(face, m) * sixth
+        });
+        assert_eq!(expected.reconstruct(), Some((7, 2)));
+        assert_eq!(expected.value(), 500_000_007);
+    }
+
+    #[test]
+    fn test_frac_mod_non_units() {
+        assert_eq!(FracMod::new(1i64, 3, 12u32), None);
+        assert_eq!(FracMod::new(1i64, 0, 7u32), None);
+        let m = 12u32;
+        let fifth = FracMod::new(1i64, 5, m).unwrap();
+        let two = FracMod::from_integer(2, m);
+        assert_eq!(fifth.checked_div(two), None);
+        assert_eq!(two.inverse(), None);
+        assert_eq!(fifth.checked_div(fifth), Some(FracMod::from_integer(1, m)));
+    }
+
+    #[test]
+    #[should_panic(expected = "the moduli must be equal")]
+    fn test_frac_mod_different_moduli() {
+        let _ = FracMod::new(1i64, 2, 7u32).unwrap() + FracMod::new(1i64, 2, 11u32).unwrap();
+    }
+
+    #[test]
+    fn test_frac_mod_reconstruct() {
+        let m = 1_000_000_007u64;
+        for p in -30i64..=30 {
+            for q in 1i64..=30 {
+                let fraction = FracMod::new(p, q, m).unwrap();
+                let g = p.gcd(q);
+                assert_eq!(fraction.reconstruct(), Some((p / g, q / g)));
+            }
+        }
+        let fraction = FracMod::new(3i64, 4, 101u32).unwrap();
+        assert_eq!(Residue::from(fraction), Residue::new(26, 101));
+        assert_eq!(FracMod::from(Residue::new(26i64, 101u32)), fraction);
+        assert_eq!(format!("{fraction:#}"), "26 (mod 101)");
+    }
+}
@@ -0,0 +1,297 @@
+use std::any::Any;
+
+use num_traits::{CheckedMul, CheckedSub, PrimInt, Unsigned};
+
+use crate::{Constrain, Integer, Modulus};
+
+/// A trait to compute the greatest common divisor and the least common multiple of two integers.
+/// The primitive integers use the binary algorithm of `binary_gcd`, and other integer types the Euclidean algorithm,
+/// which needs only division.
+pub trait Gcd: Integer + PartialOrd + CheckedMul + CheckedSub {
+    /// Compute the greatest common divisor.
+    /// The result is never negative, and `gcd(0, 0) = 0`.
+    /// Panics if the result does not fit, which happens only for `gcd(T::MIN, 0)` and `gcd(T::MIN, T::MIN)`.
+    fn gcd(self, other: Self) -> Self;
+
+    /// Compute the least common multiple as `a / gcd(a, b) * b`, or `None` if it overflows.
+    /// The result is never negative, and it is zero if either integer is.
+    fn lcm(self, other: Self) -> Option<Self>;
+}
+
+impl<T> Gcd for T
+where
+    T: Integer + PartialOrd + CheckedMul + CheckedSub + 'static,
+{
+    fn gcd(self, other: T) -> T {
+        if let Some(d) = primitive_gcd(self, other) {
+            return d;
+        }
+        let (mut a, mut b) = (self, other);
+        while b != T::zero() {
+            (a, b) = (b, a % b);
+        }
+        checked_abs(a).expect("the greatest common divisor overflows")
+    }
+
+    fn lcm(self, other: T) -> Option<T> {
+        if self == T::zero() || other == T::zero() {
+            return Some(T::zero());
+        }
+        // The lcm is at least the absolute value of either integer, so it overflows if one of them does.
+        let (a, b) = (checked_abs(self)?, checked_abs(other)?);
+        (a / a.gcd(b)).checked_mul(&b)
+    }
+}
+
+/// A trait to check whether an integer is invertible modulo a modulus.
+pub trait IsUnit<M>: Copy {
+    /// Check whether the integer is a unit modulo the modulus, that is, whether it is coprime to the modulus.
+    /// The integer is constrained first, so negative integers are accepted.
+    /// This agrees with `invert(...).is_some()` without computing the inverse.
+    fn is_unit(self, modulus: M) -> bool;
+}
+
+impl<T, M> IsUnit<M> for T
+where
+    T: Gcd + TryFrom<M>,
+    M: Modulus<T>,
+{
+    fn is_unit(self, modulus: M) -> bool {
+        self.constrain(modulus).gcd(modulus.cast()) == T::one()
+    }
+}
+
+/// Check whether two integers are coprime, that is, whether their greatest common divisor is one.
+/// Zero is coprime only to `1` and `-1`.
+pub fn are_coprime<T: Gcd>(a: T, b: T) -> bool {
+    a.gcd(b) == T::one()
+}
+
+/// Compute the greatest common divisor of two unsigned integers with Stein's binary algorithm,
+/// which replaces the divisions of the Euclidean algorithm with shifts by the trailing zero count and subtractions.
+pub fn binary_gcd<T: PrimInt + Unsigned>(mut a: T, mut b: T) -> T {
+    if a.is_zero() {
+        return b;
+    }
+    if b.is_zero() {
+        return a;
+    }
+    let shift = (a | b).trailing_zeros() as usize;
+    a = a >> a.trailing_zeros() as usize;
+    loop {
+        // Both are odd here, so their difference is even and at least one factor of two is removed per step.
+        b = b >> b.trailing_zeros() as usize;
+        if a > b {
+            (a, b) = (b, a);
+        }
+        b = b - a;
+        if b.is_zero() {
+            return a << shift;
+        }
+    }
+}
+
+/// Compute the gcd with `binary_gcd` if `T` is a primitive integer, or return `None` for other types.
+fn primitive_gcd<T: Copy + 'static>(a: T, b: T) -> Option<T> {
+    macro_rules! dispatch {
+        ($($u:ty),*; $($i:ty),*) => {
+            let (a, b): (&dyn Any, &dyn Any) = (&a, &b);
+            $(
+                if let (Some(&a), Some(&b)) = (a.downcast_ref::<$u>(), b.downcast_ref::<$u>()) {
+                    return (&binary_gcd(a, b) as &dyn Any).downcast_ref().copied();
+                }
+            )*
+            $(
+                if let (Some(&a), Some(&b)) = (a.downcast_ref::<$i>(), b.downcast_ref::<$i>()) {
+                    let d = <$i>::try_from(binary_gcd(a.unsigned_abs(), b.unsigned_abs()))
+                        .expect("the greatest common divisor overflows");
+                    return (&d as &dyn Any).downcast_ref().copied();
+                }
+            )*
+        };
+    }
+    dispatch!(u8, u16, u32, u64, u128, usize; i8, i16, i32, i64, i128, isize);
+    None
+}
+
+/// The absolute value, or `None` if it overflows.
+fn checked_abs<T: Integer + PartialOrd + CheckedSub>(x: T) -> Option<T> {
+    if x < T::zero() {
+        T::zero().checked_sub(&x)
+    } else {
+        Some(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SplitMix64;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(12u32.gcd(18), 6);
+        assert_eq!(0u64.gcd(7), 7);
+        assert_eq!(7u64.gcd(0), 7);
+        assert_eq!(0i32.gcd(0), 0);
+        assert_eq!(0i32.gcd(-7), 7);
+        assert_eq!((-4i32).gcd(6), 2);
+        assert_eq!(4i32.gcd(-6), 2);
+        assert_eq!((-4i32).gcd(-6), 2);
+        assert_eq!(i64::MIN.gcd(6), 2);
+        assert_eq!(u128::MAX.gcd(u128::MAX - 1), 1);
+    }
+
+    #[test]
+    fn test_is_unit() {
+        use crate::Invert;
+        for m in [1u32, 2, 7, 12, 30, 64, 97, 210] {
+            for a in -2 * m as i64..=2 * m as i64 {
+                assert_eq!(a.is_unit(m), a.invert(m).is_some(), "a = {a}, m = {m}");
+            }
+        }
+        assert!(!0i64.is_unit(7u8));
+        assert!(0i64.is_unit(1u8));
+        assert!((-1i8).is_unit(127u8));
+        assert!(3u64.is_unit(1u64 << 62));
+        assert!(!6u64.is_unit(1u64 << 62));
+    }
+
+    #[test]
+    fn test_are_coprime() {
+        assert!(are_coprime(8u32, 15));
+        assert!(!are_coprime(8u32, 12));
+        assert!(are_coprime(-1i32, 0));
+        assert!(!are_coprime(0i32, 0));
+        assert!(!are_coprime(0i32, 2));
+        assert!(are_coprime(-9i64, 10));
+    }
+
+    #[test]
+    fn test_binary_gcd_exhaustive() {
+        for a in 0..=u8::MAX {
+            for b in 0..=u8::MAX {
+                assert_eq!(binary_gcd(a, b), euclid(a, b));
+            }
+        }
+        for a in (0..=u16::MAX).step_by(997) {
+            for b in 0..=u16::MAX {
+                assert_eq!(binary_gcd(a, b), euclid(a, b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_binary_gcd_random() {
+        let mut rng = SplitMix64::new(356);
+        for _ in 0..10_000 {
+            let (a, b) = (rng.next_u64(), rng.next_u64());
+            // Share a factor so that the results are not almost always one.
+            let c = rng.next_u64() >> 48;
+            let (a, b) = (a >> 16, b >> 16);
+            assert_eq!(binary_gcd(a * c, b * c), euclid(a * c, b * c));
+            let (x, y) = (wide(&mut rng), wide(&mut rng));
+            assert_eq!(binary_gcd(x, y), euclid(x, y));
+            assert_eq!(binary_gcd(x << 7, y << 3), euclid(x << 7, y << 3));
+        }
+        assert_eq!(binary_gcd(u128::MAX, u128::MAX), u128::MAX);
+        assert_eq!(binary_gcd(1u128 << 127, 1 << 100), 1 << 100);
+    }
+
+    fn euclid<T: Integer>(mut a: T, mut b: T) -> T {
+        while b != T::zero() {
+            (a, b) = (b, a % b);
+        }
+        a
+    }
+
+    fn wide(rng: &mut SplitMix64) -> u128 {
+        (rng.next_u64() as u128) << 64 | rng.next_u64() as u128
+    }
+
+    #[test]
+    fn test_gcd_euclid_default() {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd)]
+        struct Euclid(i32);
+        // A custom integer type gets the Euclidean algorithm from the blanket impl.
+        impl std::ops::Add for Euclid {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                Self(self.0 + rhs.0)
+            }
+        }
+        impl std::ops::Sub for Euclid {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self {
+                Self(self.0 - rhs.0)
+            }
+        }
+        impl std::ops::Mul for Euclid {
+            type Output = Self;
+            fn mul(self, rhs: Self) -> Self {
+                Self(self.0 * rhs.0)
+            }
+        }
+        impl std::ops::Div for Euclid {
+            type Output = Self;
+            fn div(self, rhs: Self) -> Self {
+                Self(self.0 / rhs.0)
+            }
+        }
+        impl std::ops::Rem for Euclid {
+            type Output = Self;
+            fn rem(self, rhs: Self) -> Self {
+                Self(self.0 % rhs.0)
+            }
+        }
+        impl num_traits::Zero for Euclid {
+            fn zero() -> Self {
+                Self(0)
+            }
+            fn is_zero(&self) -> bool {
+                self.0 == 0
+            }
+        }
+        impl num_traits::One for Euclid {
+            fn one() -> Self {
+                Self(1)
+            }
+        }
+        impl CheckedMul for Euclid {
+            fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+                self.0.checked_mul(rhs.0).map(Self)
+            }
+        }
+        impl CheckedSub for Euclid {
+            fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+                self.0.checked_sub(rhs.0).map(Self)
+            }
+        }
+        assert_eq!(Euclid(-4).gcd(Euclid(6)), Euclid(2));
+        assert_eq!(Euclid(4).lcm(Euclid(-6)), Some(Euclid(12)));
+        assert_eq!(Euclid(i32::MAX).lcm(Euclid(2)), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "the greatest common divisor overflows")]
+    fn test_gcd_overflow() {
+        i8::MIN.gcd(0);
+    }
+
+    #[test]
+    fn test_lcm() {
+        assert_eq!(4u8.lcm(6), Some(12));
+        assert_eq!(0u8.lcm(6), Some(0));
+        assert_eq!(6u8.lcm(0), Some(0));
+        assert_eq!(15u8.lcm(17), Some(255));
+        assert_eq!(16u8.lcm(17), None);
+        assert_eq!(128u8.lcm(3), None);
+        assert_eq!((-4i32).lcm(6), Some(12));
+        assert_eq!(4i32.lcm(-6), Some(12));
+        assert_eq!((-4i32).lcm(-6), Some(12));
+        assert_eq!(i8::MIN.lcm(1), None);
+        assert_eq!(i8::MIN.lcm(-2), None);
+        assert_eq!((-64i8).lcm(2), Some(64));
+    }
+}
@@ -0,0 +1,370 @@
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use crate::{binary_gcd, euler_phi_factored, factorize, Gcd, Invert, MulMod, PowMod};
+
+/// Find the smallest primitive root modulo `m`, a generator of the group of units.
+/// One exists only for `m = 1, 2, 4, p^k, 2p^k` with `p` an odd prime; `None` is returned otherwise.
+pub fn primitive_root<T>(m: T) -> Option<T>
+where
+    T: Into<u64> + TryFrom<u64>,
+    <T as TryFrom<u64>>::Error: Debug,
+{
+    let m = m.into();
+    let factors = factorize(m);
+    let cyclic = match factors.as_slice() {
+        [] => m == 1,
+        [(2, k)] => *k <= 2,
+        [(_, _)] | [(2, 1), (_, _)] => true,
+        _ => false,
+    };
+    if !cyclic {
+        return None;
+    }
+    if m == 1 {
+        return Some(T::try_from(0).expect("zero"));
+    }
+    let divisors = order_test_divisors(euler_phi_factored(&factors));
+    (1..m)
+        .find(|&g| g.gcd(m) == 1 && has_full_order(g, m, &divisors))
+        .map(|g| T::try_from(g).expect("the root is smaller than the modulus"))
+}
+
+/// Check whether `g` is a primitive root modulo `m`, that is, whether its multiplicative order is `φ(m)`.
+pub fn is_primitive_root<T: Into<u64>>(g: T, m: T) -> bool {
+    let (g, m) = (g.into(), m.into());
+    if m == 0 || (g % m).gcd(m) != 1 {
+        return false;
+    }
+    let divisors = order_test_divisors(euler_phi_factored(&factorize(m)));
+    has_full_order(g % m, m, &divisors)
+}
+
+/// Decompose the group of units modulo `n` into cyclic factors, returning a generator and the order of each.
+/// Every unit is then a unique product `g_1^e_1 · … · g_r^e_r` with `0 ≤ e_i < order_i`, and the orders multiply to `φ(n)`.
+///
+/// There is one factor per odd prime power `p^k` of `n`, generated by a primitive root modulo `p^k`,
+/// and for `2^k` one factor generated by `-1` if `k ≥ 2` and another one generated by `5` if `k ≥ 3`.
+/// Each generator is lifted with the Chinese Remainder Theorem so that it is `1` modulo the other prime powers.
+/// The factors are ordered by prime. The trivial groups modulo `1` and `2` have no factors.
+/// Panics if `n` is zero.
+pub fn unit_group_structure<T>(n: T) -> Vec<(T, T)>
+where
+    T: Into<u64> + TryFrom<u64>,
+    <T as TryFrom<u64>>::Error: Debug,
+{
+    let n = n.into();
+    assert!(n != 0, "the modulus must be nonzero");
+    let mut factors = Vec::new();
+    for (p, k) in factorize(n) {
+        let q = p.pow(k);
+        let mut lift = |g: u64, order: u64| {
+            let g = lift_generator(g, q, n);
+            factors.push((
+                T::try_from(g).expect("the generator is smaller than the modulus"),
+                T::try_from(order).expect("the order is smaller than the modulus"),
+            ));
+        };
+        match (p, k) {
+            (2, 1) => {}
+            (2, k) => {
+                lift(q - 1, 2);
+                if k >= 3 {
+                    lift(5, q / 4);
+                }
+            }
+            _ => {
+                let g = primitive_root(q).expect("odd prime powers have primitive roots");
+                lift(g, q / p * (p - 1));
+            }
+        }
+    }
+    factors
+}
+
+/// Iterate over the units modulo `m`, the integers in `[1, m)` coprime to `m`, in increasing order.
+/// `m` is factorized first to count the units, so the iterator knows its exact length, `φ(m)` for `m ≥ 2`,
+/// and stops after the last unit. The iterator is empty for `m = 0` and `m = 1`.
+pub fn units<T>(m: T) -> Units<T>
+where
+    T: Into<u64> + TryFrom<u64>,
+    <T as TryFrom<u64>>::Error: Debug,
+{
+    let m = m.into();
+    let remaining = if m < 2 {
+        0
+    } else {
+        euler_phi_factored(&factorize(m))
+    };
+    Units {
+        next: 1,
+        m,
+        remaining,
+        marker: PhantomData,
+    }
+}
+
+/// An iterator over the units modulo `m`.
+#[derive(Clone, Debug)]
+pub struct Units<T> {
+    next: u64,
+    m: u64,
+    remaining: u64,
+    marker: PhantomData<T>,
+}
+
+impl<T> Iterator for Units<T>
+where
+    T: TryFrom<u64>,
+    <T as TryFrom<u64>>::Error: Debug,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        while binary_gcd(self.next, self.m) != 1 {
+            self.next += 1;
+        }
+        let current = self.next;
+        self.remaining -= 1;
+        self.next += 1;
+        Some(T::try_from(current).expect("the unit is smaller than the modulus"))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.remaining as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for Units<T>
+where
+    T: TryFrom<u64>,
+    <T as TryFrom<u64>>::Error: Debug,
+{
+}
+
+/// Find the `k`-th unit modulo `m`, counting from zero in increasing order, as `units(m).nth(k)` but without
+/// enumerating the smaller ones. Returns `None` if there are at most `k` units in `[1, m)`.
+///
+/// The units repeat with period `r`, the product of the distinct primes dividing `m`, so the search is confined to `[1, r]`,
+/// where the units up to `x` are counted by inclusion–exclusion over the divisors of `r` and the `k`-th one is found by bisection.
+pub fn nth_unit<T>(m: T, k: u64) -> Option<T>
+where
+    T: Into<u64> + TryFrom<u64>,
+    <T as TryFrom<u64>>::Error: Debug,
+{
+    let m = m.into();
+    if m < 2 {
+        return None;
+    }
+    let factors = factorize(m);
+    if k >= euler_phi_factored(&factors) {
+        return None;
+    }
+    // The square-free divisors of `r` with the sign of the Möbius function.
+    let mut divisors = vec![(1u64, true)];
+    for &(p, _) in &factors {
+        for i in 0..divisors.len() {
+            let (d, positive) = divisors[i];
+            divisors.push((d * p, !positive));
+        }
+    }
+    let count = |x: u64| {
+        divisors.iter().fold(0i64, |count, &(d, positive)| {
+            let multiples = (x / d) as i64;
+            if positive {
+                count + multiples
+            } else {
+                count - multiples
+            }
+        }) as u64
+    };
+    let r: u64 = factors.iter().map(|&(p, _)| p).product();
+    let phi_r: u64 = factors.iter().map(|&(p, _)| p - 1).product();
+    let (periods, k) = (k / phi_r, k % phi_r);
+    // The smallest `x` with more than `k` units in `[1, x]`.
+    let (mut low, mut high) = (1, r);
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if count(middle) > k {
+            high = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+    Some(T::try_from(periods * r + low).expect("the unit is smaller than the modulus"))
+}
+
+/// The unit `x` modulo `n` with `x ≡ g (mod q)` and `x ≡ 1` modulo `n / q`, for `q` a prime power exactly dividing `n`.
+fn lift_generator(g: u64, q: u64, n: u64) -> u64 {
+    let rest = n / q;
+    if rest == 1 {
+        return g;
+    }
+    let inverse = ((rest % q) as i128)
+        .invert(q)
+        .expect("the cofactor is coprime to the prime power") as u128;
+    let digit = ((g + q - 1) as u128).mul_mod(inverse, q as u128);
+    (1 + rest as u128 * digit) as u64
+}
+
+/// The exponents `φ/q` for every prime `q | φ`.
+fn order_test_divisors(phi: u64) -> Vec<u64> {
+    factorize(phi).iter().map(|&(q, _)| phi / q).collect()
+}
+
+/// Check that a unit `g` is not annihilated by any of the exponents `φ/q`,
+/// which, since `g^φ ≡ 1`, means that its order is exactly `φ`.
+fn has_full_order(g: u64, m: u64, divisors: &[u64]) -> bool {
+    divisors
+        .iter()
+        .all(|&d| (g as u128).pow_mod(d as u128, m) != 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_primitive_root() {
+        assert_eq!(primitive_root(998_244_353u32), Some(3));
+        assert_eq!(primitive_root(1_000_000_007u64), Some(5));
+        assert_eq!(primitive_root(7u8), Some(3));
+        assert_eq!(primitive_root(1u8), Some(0));
+        assert_eq!(primitive_root(2u8), Some(1));
+        assert_eq!(primitive_root(4u8), Some(3));
+        assert_eq!(primitive_root(18u8), Some(5));
+        assert_eq!(primitive_root(25u8), Some(2));
+    }
+
+    #[test]
+    fn test_primitive_root_none() {
+        for m in [0u32, 8, 12, 15, 16, 21, 36, 1 << 20] {
+            assert_eq!(primitive_root(m), None, "m = {m}");
+        }
+    }
+
+    #[test]
+    fn test_primitive_root_brute_force() {
+        for m in 1..300u64 {
+            let units: Vec<u64> = (0..m).filter(|&a| a.gcd(m) == 1).collect();
+            let generates = |g: u64| {
+                let mut seen = vec![false; m as usize];
+                let mut x = 1 % m;
+                for _ in 0..units.len() {
+                    seen[x as usize] = true;
+                    x = x * g % m;
+                }
+                units.iter().all(|&u| seen[u as usize])
+            };
+            let expected = units.iter().copied().find(|&g| generates(g));
+            assert_eq!(primitive_root(m), expected, "m = {m}");
+            for &g in &units {
+                assert_eq!(is_primitive_root(g, m), generates(g), "g = {g}, m = {m}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_unit_group_structure() {
+        assert_eq!(unit_group_structure(8u8), vec![(7, 2), (5, 2)]);
+        assert_eq!(unit_group_structure(15u8), vec![(11, 2), (7, 4)]);
+        assert_eq!(unit_group_structure(7u8), vec![(3, 6)]);
+        assert_eq!(unit_group_structure(4u8), vec![(3, 2)]);
+        assert_eq!(unit_group_structure(1u8), vec![]);
+        assert_eq!(unit_group_structure(2u8), vec![]);
+        let structure = unit_group_structure(1u64 << 40);
+        assert_eq!(structure, vec![((1 << 40) - 1, 2), (5, 1 << 38)]);
+        let n = 1_000_000_007u64 * 998_244_353;
+        let orders: Vec<u64> = unit_group_structure(n)
+            .iter()
+            .map(|&(_, order)| order)
+            .collect();
+        assert_eq!(orders, vec![998_244_352, 1_000_000_006]);
+    }
+
+    #[test]
+    fn test_unit_group_structure_brute_force() {
+        for n in 1..400u64 {
+            let structure = unit_group_structure(n);
+            let product: u64 = structure.iter().map(|&(_, order)| order).product();
+            assert_eq!(product, euler_phi_factored(&factorize(n)), "n = {n}");
+            // Every combination of exponents gives a different unit, so they cover all `φ(n)` units.
+            let mut units = vec![1 % n];
+            for &(g, order) in &structure {
+                assert_eq!(g.pow_mod(order, n), 1, "g = {g}, n = {n}");
+                units = units
+                    .iter()
+                    .flat_map(|&u| (0..order).map(move |e| u * g.pow_mod(e, n) % n))
+                    .collect();
+            }
+            units.sort();
+            units.dedup();
+            assert_eq!(units.len() as u64, product, "n = {n}");
+            assert!(units.iter().all(|&u| u.gcd(n) == 1));
+        }
+    }
+
+    #[test]
+    fn test_units() {
+        assert_eq!(units(12u8).collect::<Vec<_>>(), vec![1, 5, 7, 11]);
+        assert_eq!(units(7u32).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(units(1u32).next(), None);
+        assert_eq!(units(0u32).next(), None);
+        assert_eq!(units(2u32).collect::<Vec<_>>(), vec![1]);
+        let mut iter = units(30u64);
+        assert_eq!(iter.size_hint(), (8, Some(8)));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(
+            iter.clone().collect::<Vec<_>>(),
+            vec![7, 11, 13, 17, 19, 23, 29]
+        );
+        assert_eq!(iter.size_hint(), (7, Some(7)));
+        assert_eq!(units(u8::MAX).last(), Some(254));
+    }
+
+    #[test]
+    fn test_units_brute_force() {
+        for m in 2..500u64 {
+            let expected: Vec<u64> = (1..m).filter(|&a| a.gcd(m) == 1).collect();
+            let iter = units(m);
+            assert_eq!(
+                iter.len(),
+                euler_phi_factored(&factorize(m)) as usize,
+                "m = {m}"
+            );
+            assert_eq!(iter.collect::<Vec<_>>(), expected, "m = {m}");
+            for (k, &unit) in expected.iter().enumerate() {
+                assert_eq!(nth_unit(m, k as u64), Some(unit), "k = {k}, m = {m}");
+            }
+            assert_eq!(nth_unit(m, expected.len() as u64), None);
+        }
+    }
+
+    #[test]
+    fn test_nth_unit() {
+        assert_eq!(nth_unit(1u32, 0), None);
+        assert_eq!(nth_unit(1_000_000_007u64, 999_999_999), Some(1_000_000_000));
+        let m = 2u64.pow(10) * 3u64.pow(12) * 5 * 7 * 11 * 13;
+        let units: Vec<u64> = units(m).take(1000).collect();
+        for (k, &unit) in units.iter().enumerate() {
+            assert_eq!(nth_unit(m, k as u64), Some(unit));
+        }
+        let phi = euler_phi_factored(&factorize(m));
+        assert_eq!(nth_unit(m, phi - 1), Some(m - 1));
+        assert_eq!(nth_unit(m, phi), None);
+    }
+
+    #[test]
+    fn test_is_primitive_root() {
+        assert!(is_primitive_root(3u32, 998_244_353));
+        assert!(!is_primitive_root(2u32, 998_244_353));
+        assert!(is_primitive_root(3u32 + 998_244_353, 998_244_353));
+        assert!(!is_primitive_root(0u32, 7));
+        assert!(!is_primitive_root(3u32, 0));
+    }
+}
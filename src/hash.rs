@@ -0,0 +1,280 @@
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+
+use crate::{AddMod, Constrain, MulMod, PowMod, SubModConstrained};
+
+/// The Mersenne prime `2^61 - 1`, the default modulus of `RollingHash`.
+pub const MERSENNE_61: u64 = (1 << 61) - 1;
+
+/// A polynomial rolling hash of a growing sequence of symbols, such as the bytes or characters of a string.
+/// The hash of `s_0 s_1 … s_{n-1}` is `s_0 b^{n-1} + s_1 b^{n-2} + … + s_{n-1}` modulo the modulus,
+/// and the prefix hashes and powers of the base are kept so that any range hashes in `O(1)`.
+/// Distinct strings collide with probability about `n / m` over the choice of the base.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RollingHash {
+    base: u64,
+    modulus: u64,
+    prefix: Vec<u64>,
+    powers: Vec<u64>,
+}
+
+impl RollingHash {
+    /// Create an empty hash with the given base and modulus, which should be prime.
+    /// Panics if the modulus is not in `2..2^63`.
+    pub fn new(base: u64, modulus: u64) -> Self {
+        assert!(
+            (2..1 << 63).contains(&modulus),
+            "the modulus must be in 2..2^63"
+        );
+        Self {
+            base: base.constrain(modulus),
+            modulus,
+            prefix: vec![0],
+            powers: vec![1],
+        }
+    }
+
+    /// Create an empty hash modulo `2^61 - 1` with a base drawn at random from `[256, 2^61 - 1)`,
+    /// so that collisions cannot be planned in advance.
+    pub fn with_random_base() -> Self {
+        Self::new(256 + random_u64() % (MERSENNE_61 - 256), MERSENNE_61)
+    }
+
+    /// The base.
+    pub fn base(&self) -> u64 {
+        self.base
+    }
+
+    /// The modulus.
+    pub fn modulus(&self) -> u64 {
+        self.modulus
+    }
+
+    /// The number of symbols pushed so far.
+    pub fn len(&self) -> usize {
+        self.prefix.len() - 1
+    }
+
+    /// Check if no symbols have been pushed.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Append a symbol, such as a `u8` or a `char`.
+    pub fn push<S: Into<u64>>(&mut self, symbol: S) {
+        let m = self.modulus;
+        let last = *self
+            .prefix
+            .last()
+            .expect("the prefixes start with the empty one");
+        let hash = self
+            .mul(last, self.base)
+            .add_mod(symbol.into().constrain(m), m);
+        self.prefix.push(hash);
+        let power = *self.powers.last().expect("the powers start with one");
+        self.powers.push(self.mul(power, self.base));
+    }
+
+    /// The hash of all symbols pushed so far.
+    pub fn hash(&self) -> u64 {
+        self.hash_of_range(0, self.len())
+    }
+
+    /// The hash of the symbols at positions `l..r`, equal to the hash of those symbols pushed on their own.
+    /// Panics if `l > r` or `r > len()`.
+    pub fn hash_of_range(&self, l: usize, r: usize) -> u64 {
+        assert!(l <= r && r <= self.len(), "the range is out of bounds");
+        let shifted = self.mul(self.prefix[l], self.powers[r - l]);
+        self.prefix[r].sub_mod_constrained(shifted, self.modulus)
+    }
+
+    /// Combine the hash `h1` of a string with the hash `h2` of a string of length `len2` into the hash of their concatenation.
+    pub fn concat(&self, h1: u64, h2: u64, len2: usize) -> u64 {
+        let power = match self.powers.get(len2) {
+            Some(&power) => power,
+            None => (self.base as u128).pow_mod(len2 as u128, self.modulus) as u64,
+        };
+        self.mul(h1, power).add_mod(h2, self.modulus)
+    }
+
+    fn mul(&self, a: u64, b: u64) -> u64 {
+        (a as u128).mul_mod(b as u128, self.modulus) as u64
+    }
+}
+
+impl<S: Into<u64>> Extend<S> for RollingHash {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, symbols: I) {
+        for symbol in symbols {
+            self.push(symbol);
+        }
+    }
+}
+
+/// Two independent rolling hashes side by side, whose pairs of hashes collide with about the product of the two probabilities.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DoubleRollingHash {
+    first: RollingHash,
+    second: RollingHash,
+}
+
+impl DoubleRollingHash {
+    /// Combine two empty rolling hashes, which should use different moduli or bases.
+    /// Panics if either of them is not empty.
+    pub fn new(first: RollingHash, second: RollingHash) -> Self {
+        assert!(
+            first.is_empty() && second.is_empty(),
+            "the hashes must be empty"
+        );
+        Self { first, second }
+    }
+
+    /// Create a pair of hashes modulo `2^61 - 1` and `10^9 + 7`, both with random bases.
+    pub fn with_random_bases() -> Self {
+        const SECOND_MODULUS: u64 = 1_000_000_007;
+        let second = RollingHash::new(256 + random_u64() % (SECOND_MODULUS - 256), SECOND_MODULUS);
+        Self::new(RollingHash::with_random_base(), second)
+    }
+
+    /// The number of symbols pushed so far.
+    pub fn len(&self) -> usize {
+        self.first.len()
+    }
+
+    /// Check if no symbols have been pushed.
+    pub fn is_empty(&self) -> bool {
+        self.first.is_empty()
+    }
+
+    /// Append a symbol to both hashes.
+    pub fn push<S: Into<u64> + Copy>(&mut self, symbol: S) {
+        self.first.push(symbol);
+        self.second.push(symbol);
+    }
+
+    /// The pair of hashes of all symbols pushed so far.
+    pub fn hash(&self) -> (u64, u64) {
+        (self.first.hash(), self.second.hash())
+    }
+
+    /// The pair of hashes of the symbols at positions `l..r`.
+    /// Panics if `l > r` or `r > len()`.
+    pub fn hash_of_range(&self, l: usize, r: usize) -> (u64, u64) {
+        (
+            self.first.hash_of_range(l, r),
+            self.second.hash_of_range(l, r),
+        )
+    }
+
+    /// Combine pairs of hashes as in `RollingHash::concat`.
+    pub fn concat(&self, h1: (u64, u64), h2: (u64, u64), len2: usize) -> (u64, u64) {
+        (
+            self.first.concat(h1.0, h2.0, len2),
+            self.second.concat(h1.1, h2.1, len2),
+        )
+    }
+}
+
+impl<S: Into<u64> + Copy> Extend<S> for DoubleRollingHash {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, symbols: I) {
+        for symbol in symbols {
+            self.push(symbol);
+        }
+    }
+}
+
+/// A random value from the standard library's randomly keyed hasher, which is enough to pick a base.
+fn random_u64() -> u64 {
+    RandomState::new().hash_one(0u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SplitMix64;
+    use pretty_assertions::assert_eq;
+
+    fn hash_of(symbols: &[u8], base: u64, modulus: u64) -> u64 {
+        let mut hash = RollingHash::new(base, modulus);
+        hash.extend(symbols.iter().copied());
+        hash.hash()
+    }
+
+    #[test]
+    fn test_rolling_hash_small() {
+        let mut hash = RollingHash::new(10, 1_000_000_007);
+        assert!(hash.is_empty());
+        assert_eq!(hash.hash(), 0);
+        hash.extend([1u8, 2, 3, 4]);
+        assert_eq!(hash.len(), 4);
+        assert_eq!(hash.hash(), 1234);
+        assert_eq!(hash.hash_of_range(1, 3), 23);
+        assert_eq!(hash.hash_of_range(2, 2), 0);
+        assert_eq!(hash.concat(12, 34, 2), 1234);
+        assert_eq!(hash.concat(1, 0, 10), 999_999_937);
+        hash.push('a');
+        assert_eq!(hash.hash_of_range(3, 5), 4 * 10 + 97);
+    }
+
+    #[test]
+    fn test_rolling_hash_ranges() {
+        let mut rng = SplitMix64::new(325);
+        for _ in 0..20 {
+            let len = 1 + (rng.next_u64() % 60) as usize;
+            let text: Vec<u8> = (0..len)
+                .map(|_| b'a' + (rng.next_u64() % 3) as u8)
+                .collect();
+            let mut hash = RollingHash::with_random_base();
+            hash.extend(text.iter().copied());
+            let (base, modulus) = (hash.base(), hash.modulus());
+            assert_eq!(modulus, MERSENNE_61);
+            assert!((256..MERSENNE_61).contains(&base));
+            for l in 0..=len {
+                for r in l..=len {
+                    assert_eq!(
+                        hash.hash_of_range(l, r),
+                        hash_of(&text[l..r], base, modulus)
+                    );
+                }
+            }
+            let mid = (rng.next_u64() % len as u64) as usize;
+            let joined = hash.concat(
+                hash.hash_of_range(0, mid),
+                hash.hash_of_range(mid, len),
+                len - mid,
+            );
+            assert_eq!(joined, hash.hash());
+        }
+    }
+
+    #[test]
+    fn test_rolling_hash_substrings() {
+        let text = "abracadabra";
+        let mut hash = RollingHash::with_random_base();
+        hash.extend(text.chars());
+        assert_eq!(hash.hash_of_range(0, 4), hash.hash_of_range(7, 11));
+        assert_ne!(hash.hash_of_range(0, 4), hash.hash_of_range(1, 5));
+    }
+
+    #[test]
+    fn test_double_rolling_hash() {
+        let mut hash = DoubleRollingHash::new(
+            RollingHash::new(131, MERSENNE_61),
+            RollingHash::new(137, 998_244_353),
+        );
+        hash.extend("hello, world".bytes());
+        assert_eq!(hash.len(), 12);
+        assert_eq!(
+            hash.hash_of_range(7, 12),
+            (
+                hash_of(b"world", 131, MERSENNE_61),
+                hash_of(b"world", 137, 998_244_353)
+            )
+        );
+        let whole = hash.concat(hash.hash_of_range(0, 5), hash.hash_of_range(5, 12), 7);
+        assert_eq!(whole, hash.hash());
+        let mut random = DoubleRollingHash::with_random_bases();
+        assert!(random.is_empty());
+        random.push(b'x');
+        assert_eq!(random.hash_of_range(0, 1), (120, 120));
+    }
+}
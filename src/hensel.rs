@@ -0,0 +1,77 @@
+use crate::integer::power;
+use crate::{Constrain, Invert, Modulus, MulMod, SubMod};
+
+/// Lift a root `x0` of a polynomial `f` modulo a prime `p` to a root modulo `p^k` with Hensel's lemma.
+/// `df` is the derivative of `f`, and `df(x0)` must be invertible modulo `p`.
+/// Each Newton step `x ← x - f(x) / df(x)` doubles the number of correct `p`-adic digits.
+/// `f` and `df` are evaluated at values in `[0, p^k)`, and their results must fit in `T`.
+/// Returns the root in `[0, p^k)`, or `None` if `x0` is not a simple root modulo `p`.
+pub fn hensel_lift<T, M>(f: impl Fn(T) -> T, df: impl Fn(T) -> T, x0: T, p: M, k: u32) -> Option<T>
+where
+    T: Invert + TryFrom<M>,
+    M: Modulus<T>,
+{
+    if k == 0 {
+        return Some(T::zero());
+    }
+    let mut x = x0.constrain(p);
+    if f(x).constrain(p) != T::zero() {
+        return None;
+    }
+    df(x).invert(p)?;
+    let mut precision = 1;
+    while precision < k {
+        precision = k.min(2 * precision);
+        let modulus = power(p, precision);
+        let inverse = df(x).invert(modulus)?;
+        x = x.sub_mod(f(x).constrain(modulus).mul_mod(inverse, modulus), modulus);
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_hensel_lift_square_root() {
+        let n = 5i64.pow(10);
+        let root = hensel_lift(|x| x * x + 1, |x| 2 * x, 2, 5u32, 10).unwrap();
+        assert_eq!((root * root + 1) % n, 0);
+        assert_eq!(root % 5, 2);
+
+        let n = 13i128.pow(10);
+        let root = hensel_lift(|x: i128| x * x - 17, |x| 2 * x, 2, 13u64, 10).unwrap();
+        assert_eq!((root * root - 17).rem_euclid(n), 0);
+        let root = hensel_lift(|x: i128| x * x - 17, |x| 2 * x, 11, 13u64, 10).unwrap();
+        assert_eq!((root * root - 17).rem_euclid(n), 0);
+        assert_eq!(root % 13, 11);
+    }
+
+    #[test]
+    fn test_hensel_lift_cubic() {
+        let n = 5i128.pow(10);
+        let root = hensel_lift(|x| x * x * x - 2, |x| 3 * x * x, 3, 5u64, 10).unwrap();
+        assert_eq!((root * root * root - 2) % n, 0);
+    }
+
+    #[test]
+    fn test_hensel_lift_not_a_root() {
+        // 17 is not a quadratic residue modulo 5.
+        for x0 in 0..5 {
+            assert_eq!(hensel_lift(|x| x * x - 17, |x| 2 * x, x0, 5u32, 10), None);
+        }
+        // 1 is a double root of (x - 1)^2 modulo 3.
+        assert_eq!(
+            hensel_lift(|x| (x - 1) * (x - 1), |x| 2 * (x - 1), 1, 3u32, 4),
+            None
+        );
+    }
+
+    #[test]
+    fn test_hensel_lift_trivial_precision() {
+        assert_eq!(hensel_lift(|x| x * x + 1, |x| 2 * x, 7, 5u32, 1), Some(2));
+        assert_eq!(hensel_lift(|x| x * x + 1, |x| 2 * x, 7, 5u32, 0), Some(0));
+    }
+}
@@ -3,12 +3,62 @@ use std::ops::{Div, Rem, Sub};
 use num_traits::{One, Zero};
 
 /// A trait for integers.
+/// With the `parallel` feature, integers must also be `Send` and `Sync` so that batch operations can share them between threads.
 pub trait Integer:
-    Zero + One + Eq + Div<Output = Self> + Sub<Output = Self> + Rem<Output = Self> + Copy
+    Zero + One + Eq + Div<Output = Self> + Sub<Output = Self> + Rem<Output = Self> + Copy + Shareable
 {
 }
 
 impl<T> Integer for T where
-    T: Zero + One + Eq + Div<Output = T> + Sub<Output = T> + Rem<Output = T> + Copy
+    T: Zero + One + Eq + Div<Output = T> + Sub<Output = T> + Rem<Output = T> + Copy + Shareable
 {
 }
+
+/// `Send + Sync` with the `parallel` feature, and implemented for every type without it.
+#[cfg(feature = "parallel")]
+pub trait Shareable: Send + Sync {}
+
+#[cfg(feature = "parallel")]
+impl<T: Send + Sync> Shareable for T {}
+
+/// `Send + Sync` with the `parallel` feature, and implemented for every type without it.
+#[cfg(not(feature = "parallel"))]
+pub trait Shareable {}
+
+#[cfg(not(feature = "parallel"))]
+impl<T> Shareable for T {}
+
+/// A machine integer with a type of twice the width, which holds the product of any two values.
+pub trait Widen: Integer {
+    /// The wider type.
+    type Wide: Integer;
+
+    /// Convert to the wider type without loss.
+    fn widen(self) -> Self::Wide;
+
+    /// Convert back from the wider type, keeping the low bits.
+    fn narrow(wide: Self::Wide) -> Self;
+}
+
+macro_rules! widen {
+    ($($t:ty => $w:ty),*) => {$(
+        impl Widen for $t {
+            type Wide = $w;
+
+            fn widen(self) -> $w {
+                self as $w
+            }
+
+            fn narrow(wide: $w) -> $t {
+                wide as $t
+            }
+        }
+    )*};
+}
+
+widen!(u8 => u16, u16 => u32, u32 => u64, u64 => u128, i8 => i16, i16 => i32, i32 => i64, i64 => i128);
+
+/// Raise an integer to a small power by repeated multiplication.
+pub(crate) fn power<T: Integer>(base: T, exponent: u32) -> T {
+    (0..exponent).fold(T::one(), |acc, _| acc * base)
+}
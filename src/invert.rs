@@ -1,34 +1,257 @@
-use super::{Constrain, Egcd, Modulus};
+use std::any::Any;
+
+use num_traits::{FromPrimitive, PrimInt, Unsigned};
+
+use super::{Constrain, Egcd, ModError, Modulus, PowMod};
 
 /// A trait to invert an integer modulo a modulus.
+/// The primitive integers invert modulo odd moduli with the binary extended gcd, which needs no divisions,
+/// and fall back to the extended Euclidean algorithm modulo even moduli.
+/// Other integer types always use the extended Euclidean algorithm.
 pub trait Invert: Egcd {
-    /// Invert an integer modulo a modulus.
+    /// Invert an integer modulo a modulus, returning the canonical inverse in `[0, m)`,
+    /// or `None` if the integer is not coprime to the modulus.
     fn invert<P: Modulus<Self>>(self, p: P) -> Option<Self>
+    where
+        Self: TryFrom<P>;
+
+    /// Invert an integer modulo a modulus as `invert`, but fail with `ModError::ModulusTooLarge`
+    /// if the modulus does not fit in the integer type and with `ModError::ZeroModulus` if it is zero, instead of panicking.
+    /// `InvertWidening` computes the inverse in a type that holds the modulus instead.
+    fn try_invert<P: Modulus<Self>>(self, p: P) -> Result<Option<Self>, ModError>
     where
         Self: TryFrom<P>,
-        <Self as TryFrom<P>>::Error: std::fmt::Debug,
     {
-        invert(self, p)
+        if p == P::zero() {
+            return Err(ModError::ZeroModulus);
+        }
+        p.try_cast()?;
+        Ok(self.invert(p))
+    }
+
+    /// Invert an integer modulo a prime using Fermat's little theorem, that is, as `self^(p-2) mod p`.
+    /// The modulus must be prime, otherwise the result is meaningless.
+    /// Returns `None` only when the integer is congruent to zero.
+    fn invert_prime<P: Modulus<Self>>(self, p: P) -> Option<Self>
+    where
+        Self: TryFrom<P> + FromPrimitive,
+    {
+        invert_prime(self, p)
+    }
+}
+
+impl<T: Egcd + 'static> Invert for T {
+    fn invert<P: Modulus<T>>(self, p: P) -> Option<T>
+    where
+        T: TryFrom<P>,
+    {
+        let a = self.constrain(p);
+        primitive_invert(a, p.cast()).unwrap_or_else(|| invert(a, p))
+    }
+}
+
+/// Invert a canonical residue with the binary algorithms if `T` is a primitive integer and the modulus is odd,
+/// or return `None` to fall back to the extended Euclidean algorithm.
+fn primitive_invert<T: Copy + 'static>(a: T, m: T) -> Option<Option<T>> {
+    macro_rules! dispatch {
+        ($($t:ty => $u:ty, $inverse:ident);*) => {
+            let (a, m): (&dyn Any, &dyn Any) = (&a, &m);
+            $(
+                if let (Some(&a), Some(&m)) = (a.downcast_ref::<$t>(), m.downcast_ref::<$t>()) {
+                    if m % 2 == 0 {
+                        return None;
+                    }
+                    // The modulus is positive, so both it and the canonical residue fit in the unsigned type.
+                    let x = $inverse(a as $u, m as $u).map(|x| x as $t);
+                    return (&x as &dyn Any).downcast_ref().copied();
+                }
+            )*
+        };
     }
+    dispatch!(
+        i8 => u64, binary_inverse_u64;
+        i16 => u64, binary_inverse_u64;
+        i32 => u64, binary_inverse_u64;
+        i64 => u64, binary_inverse_u64;
+        isize => u64, binary_inverse_u64;
+        i128 => u128, binary_inverse
+    );
+    None
 }
 
-impl<T: Egcd> Invert for T {}
+/// A trait to invert a signed integer modulo a modulus that may not fit in its type.
+/// The inverse lies in `[0, m)`, so it is computed and returned in the narrowest signed type that holds both
+/// the integer type and the modulus type, so that `3_i8.invert_widening(1000u32)` is `Some(667_i64)`.
+pub trait InvertWidening<M> {
+    /// The signed type of the inverse.
+    type Output;
 
-fn invert<T: Egcd, P: Modulus<T>>(a: T, p: P) -> Option<T>
+    /// Invert an integer modulo a modulus in the wider type, returning the canonical inverse in `[0, m)`,
+    /// or `None` if the integer is not coprime to the modulus.
+    fn invert_widening(self, modulus: M) -> Option<Self::Output>;
+}
+
+macro_rules! invert_widening {
+    ($($t:ty: $($m:ty => $w:ty),*);*) => {$($(
+        impl InvertWidening<$m> for $t {
+            type Output = $w;
+
+            fn invert_widening(self, modulus: $m) -> Option<$w> {
+                <$w>::from(self).invert(modulus)
+            }
+        }
+    )*)*};
+}
+
+invert_widening!(
+    i8: u8 => i16, u16 => i32, u32 => i64, u64 => i128;
+    i16: u8 => i16, u16 => i32, u32 => i64, u64 => i128;
+    i32: u8 => i32, u16 => i32, u32 => i64, u64 => i128;
+    i64: u8 => i64, u16 => i64, u32 => i64, u64 => i128;
+    i128: u8 => i128, u16 => i128, u32 => i128, u64 => i128
+);
+
+fn invert<T, P>(a: T, p: P) -> Option<T>
 where
-    T: TryFrom<P>,
-    <T as TryFrom<P>>::Error: std::fmt::Debug,
+    T: Egcd + TryFrom<P>,
+    P: Modulus<T>,
 {
-    let (d, x, _) = a.constrain(p).egcd(p.cast());
+    let (d, x) = a.constrain(p).half_egcd(p.cast());
     if d != T::one() {
         return None;
     }
     Some(x.constrain(p))
 }
 
+/// Invert `a` in `[0, m)` modulo an odd modulus `m` with the binary extended gcd.
+/// Only shifts, subtractions and comparisons are used, and the coefficients stay in `[0, m)`, so nothing overflows.
+fn binary_inverse<U: PrimInt + Unsigned>(a: U, m: U) -> Option<U> {
+    let one = U::one();
+    // Halve a coefficient modulo the odd modulus: `(x + m) / 2` for odd `x`, computed without overflow.
+    let half = |x: U| {
+        if x & one == U::zero() {
+            x >> 1
+        } else {
+            (x >> 1) + (m >> 1) + one
+        }
+    };
+    let sub = |x: U, y: U| if x >= y { x - y } else { x + (m - y) };
+    // Invariants: `x1 · a ≡ u` and `x2 · a ≡ v (mod m)`, and `gcd(u, v) = gcd(a, m)`.
+    let (mut u, mut v) = (a, m);
+    let (mut x1, mut x2) = (one, U::zero());
+    loop {
+        if v == one {
+            return Some(x2);
+        }
+        if u == one {
+            return Some(x1);
+        }
+        if u.is_zero() {
+            // The gcd is `v`, which is not one.
+            return None;
+        }
+        while u & one == U::zero() {
+            u = u >> 1;
+            x1 = half(x1);
+        }
+        while v & one == U::zero() {
+            v = v >> 1;
+            x2 = half(x2);
+        }
+        if u >= v {
+            u = u - v;
+            x1 = sub(x1, x2);
+        } else {
+            v = v - u;
+            x2 = sub(x2, x1);
+        }
+    }
+}
+
+/// Invert `a` in `[0, m)` modulo an odd modulus `m` with Kaliski's almost inverse.
+/// The loop doubles the coefficients instead of halving them modulo `m`, and removes all factors of two at once,
+/// producing `a^-1 · 2^k`; the factor `2^k` is then divided out with Montgomery reductions.
+fn binary_inverse_u64(a: u64, m: u64) -> Option<u64> {
+    if m == 1 {
+        return Some(0);
+    }
+    // Invariants: `m = u·s + v·r`, `a·r ≡ -u·2^k` and `a·s ≡ v·2^k (mod m)`.
+    // The first one bounds the coefficients by `2m`, so they fit in `u128`.
+    let (mut u, mut v) = (m, a);
+    let (mut r, mut s) = (0u128, 1u128);
+    let mut k = 0;
+    if v == 0 {
+        return None;
+    }
+    let zeros = v.trailing_zeros();
+    v >>= zeros;
+    k += zeros;
+    while v != 0 {
+        if u > v {
+            let d = u - v;
+            let zeros = d.trailing_zeros();
+            u = d >> zeros;
+            r += s;
+            s <<= zeros;
+            k += zeros;
+        } else if v > u {
+            let d = v - u;
+            let zeros = d.trailing_zeros();
+            v = d >> zeros;
+            s += r;
+            r <<= zeros;
+            k += zeros;
+        } else {
+            v = 0;
+            s += r;
+            r <<= 1;
+            k += 1;
+        }
+    }
+    if u != 1 {
+        return None;
+    }
+    let m_wide = m as u128;
+    let r = if r >= m_wide { r - m_wide } else { r } as u64;
+    // `m - r ≡ a^-1 · 2^k`.
+    Some(divide_by_power_of_two(m - r, k, m))
+}
+
+/// Compute `x · 2^-k mod m` for an odd modulus `m` and `x` in `[0, m)` with Montgomery reductions of up to 63 bits.
+fn divide_by_power_of_two(mut x: u64, mut k: u32, m: u64) -> u64 {
+    // Newton's iteration doubles the correct low bits of `m^-1 mod 2^64`, starting from three.
+    let mut inverse = m;
+    for _ in 0..5 {
+        inverse = inverse.wrapping_mul(2u64.wrapping_sub(m.wrapping_mul(inverse)));
+    }
+    let negated = inverse.wrapping_neg();
+    while k > 0 {
+        let j = k.min(63);
+        // `x + t·m` is divisible by `2^j`, and the quotient stays below `m`.
+        let t = x.wrapping_mul(negated) & ((1 << j) - 1);
+        x = ((x as u128 + t as u128 * m as u128) >> j) as u64;
+        k -= j;
+    }
+    x
+}
+
+fn invert_prime<T, P>(a: T, p: P) -> Option<T>
+where
+    T: Egcd + TryFrom<P> + FromPrimitive,
+    P: Modulus<T>,
+{
+    let a = a.constrain(p);
+    if a == T::zero() {
+        return None;
+    }
+    let two = T::one() + T::one();
+    Some(a.pow_mod(p.cast() - two, p))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::SplitMix64;
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -48,4 +271,134 @@ mod tests {
         assert_eq!(invert(-10, modulus), Some(1));
         assert_eq!(invert(-11, modulus), None);
     }
+
+    #[test]
+    fn test_binary_inverse() {
+        for m in (1u32..300).step_by(2) {
+            for a in 0..m {
+                assert_eq!(
+                    binary_inverse(a, m).map(|x| x as i64),
+                    invert(a as i64, m),
+                    "a = {a}, m = {m}"
+                );
+            }
+        }
+        let m = u64::MAX;
+        assert_eq!(binary_inverse(2, m), Some(m / 2 + 1));
+        assert_eq!(binary_inverse(3, m), None);
+    }
+
+    #[test]
+    fn test_binary_inverse_u64() {
+        for m in (1u64..300).step_by(2) {
+            for a in 0..m {
+                assert_eq!(
+                    binary_inverse_u64(a, m),
+                    binary_inverse(a, m),
+                    "a = {a}, m = {m}"
+                );
+            }
+        }
+        let mut rng = SplitMix64::new(3570);
+        for m in [u64::MAX, u64::MAX - 58, (1 << 61) - 1, 1_000_000_007] {
+            for _ in 0..1000 {
+                let a = rng.next_u64() % m;
+                assert_eq!(
+                    binary_inverse_u64(a, m),
+                    binary_inverse(a, m),
+                    "a = {a}, m = {m}"
+                );
+            }
+            assert_eq!(binary_inverse_u64(m - 1, m), Some(m - 1));
+            assert_eq!(binary_inverse_u64(1, m), Some(1));
+        }
+    }
+
+    #[test]
+    fn test_invert_primitive() {
+        let mut rng = SplitMix64::new(357);
+        for m in [
+            1u64,
+            2,
+            9,
+            10,
+            1_000_000_007,
+            (1 << 61) - 1,
+            1 << 61,
+            (1 << 62) - 1,
+        ] {
+            for _ in 0..1000 {
+                let a = rng.next_u64() as i64;
+                let expected = invert(a as i128, m).map(|x| x as i64);
+                assert_eq!(a.invert(m), expected, "a = {a}, m = {m}");
+                if let Some(x) = expected {
+                    assert!((0..m as i64).contains(&x));
+                }
+            }
+        }
+        let m = u128::MAX >> 2;
+        for _ in 0..1000 {
+            let a = ((rng.next_u64() as i128) << 64) | rng.next_u64() as i128;
+            assert_eq!(a.invert(m), invert(a, m));
+        }
+        assert_eq!((-3i8).invert(127u8), Some(42));
+        assert_eq!(5i32.invert(1u32), Some(0));
+    }
+
+    #[test]
+    fn test_invert_matches_egcd() {
+        let mut rng = SplitMix64::new(358);
+        for _ in 0..10_000 {
+            let a = rng.next_u64() as i64;
+            let m = (rng.next_u64() >> (2 + rng.next_u64() % 62)).max(1);
+            let (d, x, _) = a.constrain(m).egcd(m as i64);
+            let expected = (d == 1).then(|| x.constrain(m));
+            assert_eq!(invert(a, m), expected, "a = {a}, m = {m}");
+        }
+    }
+
+    #[test]
+    fn test_try_invert() {
+        assert_eq!(3i8.try_invert(1000u32), Err(ModError::ModulusTooLarge));
+        assert_eq!(3i32.try_invert(1u64 << 40), Err(ModError::ModulusTooLarge));
+        assert_eq!(3i8.try_invert(0u32), Err(ModError::ZeroModulus));
+        assert_eq!(3i8.try_invert(100u32), Ok(Some(67)));
+        assert_eq!(4i8.try_invert(100u32), Ok(None));
+        assert_eq!((-3i8).try_invert(127u8), Ok(Some(42)));
+    }
+
+    #[test]
+    fn test_invert_widening() {
+        assert_eq!(3i8.invert_widening(1000u32), Some(667i64));
+        assert_eq!((-3i8).invert_widening(1000u32), Some(333i64));
+        assert_eq!(4i8.invert_widening(1000u32), None);
+        assert_eq!(
+            i8::MIN.invert_widening(u32::MAX),
+            (-128i64).invert(u32::MAX)
+        );
+        assert_eq!(3i8.invert_widening(100u8), Some(67i16));
+        let m = (1u64 << 61) - 1;
+        let mut rng = SplitMix64::new(371);
+        for _ in 0..1000 {
+            let a = rng.next_u64() as i32;
+            let expected = invert(a as i128, m);
+            assert_eq!(a.invert_widening(m), expected, "a = {a}");
+            assert_eq!(a.invert_widening(u64::MAX), invert(a as i128, u64::MAX));
+        }
+        assert_eq!(
+            3i32.invert_widening(1u64 << 40),
+            Some((2 * (1i128 << 40) + 1) / 3)
+        );
+        assert_eq!((i64::MAX - 1).invert_widening(7u8), Some(6i64));
+    }
+
+    #[test]
+    fn test_invert_prime() {
+        for p in [2u32, 3, 5, 7, 11, 13, 97, 101, 7919] {
+            let p_signed = p as i64;
+            for a in -p_signed..=2 * p_signed {
+                assert_eq!(invert_prime(a, p), invert(a, p), "a = {a}, p = {p}");
+            }
+        }
+    }
 }
@@ -2,15 +2,133 @@
 //!
 //! This crate provides a set of traits to perform modular arithmetic on integer types.
 //! The traits are implemented for the standard integer types and can be implemented for custom integer types.
+//! With the `derive` feature, `#[derive(ModicumInteger)]` implements `Integer` for newtypes over the standard integer types.
 //! The traits are:
 //! - `Constrain<M>`: constrain an integer to a modulus.
+//! - `ConstrainInto<M>`: constrain, add or multiply, and return the residue in the modulus type.
+//! - `ConstrainSymmetric<M>`: constrain a signed integer to the balanced range `(-m/2, m/2]`.
 //! - `AddMod<M>`: add two integers and constrain the result to a modulus.
 //! - `SubMod<M>`: subtract two integers and constrain the result to a modulus.
 //! - `MulMod<M>`: multiply two integers and constrain the result to a modulus.
 //! - `DivMod<M>`: divide two integers and constrain the result to a modulus.
 //! - `PowMod<M>`: raise an integer to a power and constrain the result to a modulus.
 //! - `EqMod<M>`: check if two integers are congruent modulo a given modulus.
-//! - `Invert`: invert an integer with respect to a modulus.
+//! - `CheckedMod<M>`: constrain, add, subtract, multiply and raise to a power, reporting a `ModError` instead of panicking or overflowing.
+//! - `Gcd`: the greatest common divisor and the least common multiple, with overflow detection.
+//!   The primitive integers use `binary_gcd`, Stein's division-free algorithm.
+//! - `IsUnit<M>`: check if an integer is invertible modulo a modulus, without computing the inverse.
+//!   The free function `are_coprime` checks two integers.
+//! - `Invert`: invert an integer with respect to a modulus, with a Fermat-based fast path for prime moduli.
+//! - `InvertWidening<M>`: invert a signed integer modulo a modulus that does not fit in its type, in a wider type.
+//! - `SqrtMod<M>`: compute Legendre symbols and square roots modulo a prime.
+//! - `QuadraticResidue<M>`: check if an integer is a quadratic residue modulo a prime or a factored composite.
+//! - `PowModCt`: raise an unsigned integer to a secret power in constant time.
+//! - `InvertCt`: invert an unsigned integer modulo a prime in constant time.
+//!
+//! For `i32`, `i64` and `i128`, `AddMod`, `SubMod`, `MulMod` and `EqMod` also accept any narrower right-hand side,
+//! and `eq_mod` compares a signed integer with the unsigned integer of the same width.
+//!
+//! `Prime` wraps a modulus verified to be prime. It can be passed wherever a modulus is expected,
+//! implements the `PrimeModulus` marker, and provides inversion and division without the extended Euclidean algorithm.
+//! `SignedModulus` similarly wraps a signed modulus verified to be positive.
+//!
+//! Wrapper types:
+//! - `Residue`: an integer modulo a modulus chosen at run time, with arithmetic operators.
+//! - `ModInt`: an integer modulo a modulus fixed at compile time, with arithmetic operators.
+//!   `Mint998` and `Mint1e9` are the residues modulo `998244353` and `10^9 + 7`.
+//! - `FracMod`: a fraction `p/q` stored as the residue `p · q^-1`, with arithmetic operators and rational reconstruction.
+//! - `format_congruence`: format `a ≡ b (mod m)` for diagnostics.
+//!
+//! Exponentiation:
+//! - `multi_pow_mod`: products of several powers with shared squarings.
+//! - `FixedBasePow`: powers of a fixed base from a precomputed table.
+//! - `powers_mod`, `powers_mod_upto`: the successive powers of a base.
+//! - `geometric_sum_mod`: the sum of the first powers of a base, for any modulus.
+//! - `Montgomery`: constant-time Montgomery multiplication and exponentiation modulo an odd modulus.
+//!
+//! The `ct` module provides constant-time congruence checks and selection for unsigned machine integers.
+//! With the `zeroize` feature, the `Wipe` trait overwrites secret residues and Montgomery contexts with zero,
+//! and the precomputed tables and the buffers of `batch_invert` are wiped when they are dropped.
+//!
+//! Batch operations are provided as free functions:
+//! - `batch_invert`: invert a slice of integers with a single inversion.
+//! - `constrain_slice`, `add_mod_slice`, `sub_mod_slice`, `mul_mod_slice`, `scale_mod_slice`: element-wise operations in place.
+//! - `dot_mod`: the dot product of two slices, reduced only when the wide accumulator would overflow.
+//! - `ModAccumulator`: a running sum, dot product or product, reduced only when the wide accumulator would overflow.
+//! - `prefix_sums_mod`, `prefix_products_mod`: prefix sums and products for range queries with `range_sum` and `range_product`.
+//! - `Barrett`: division-free reduction modulo a fixed `u32` or `u64` modulus, with faster element-wise operations.
+//!
+//! With the `parallel` feature, `batch_invert`, `constrain_slice`, `ntt::convolve_mod` and matrix multiplication
+//! split large inputs across scoped threads, one chunk per available core, and the integer types must be `Send + Sync`.
+//!
+//! Congruences and roots:
+//! - `crt`: combine two congruences with the Chinese Remainder Theorem.
+//! - `crt_slice`: combine any number of congruences, reporting overflow as an error.
+//! - `garner`: reconstruct a value modulo a target from residues modulo pairwise coprime moduli.
+//! - `solve_linear_congruence`: find all solutions of `ax ≡ b (mod m)`.
+//! - `solve_diophantine`: find all integer solutions of `ax + by = c`.
+//! - `rational_reconstruct`, `rational_reconstruct_bounded`: recover a small fraction from its residue.
+//! - `sqrt_mod_prime_power`, `sqrt_mod_composite`: find all square roots modulo prime powers and composites.
+//! - `nth_root_mod`, `all_nth_roots`: `k`-th roots modulo a prime.
+//! - `hensel_lift`: lift a simple root of a polynomial modulo a prime to a root modulo a prime power.
+//!
+//! Residue symbols:
+//! - `jacobi`: the Jacobi symbol for odd positive moduli.
+//! - `kronecker`: the Kronecker symbol for all integers.
+//!
+//! Primality and factorization:
+//! - `is_probable_prime`: the Miller–Rabin test with pseudo-random or caller-provided bases.
+//! - `is_prime`: the deterministic Miller–Rabin test for integers of up to 64 bits.
+//! - `is_bpsw_prime`: the Baillie–PSW test.
+//! - `factorize`: factorization with Pollard's rho and Brent's cycle detection.
+//! - `Sieve`: a linear sieve of smallest prime factors, for primality and factorization of every integer up to a limit.
+//!
+//! Sequences:
+//! - `fibonacci_mod`, `lucas_mod`: Fibonacci and Lucas numbers by fast doubling.
+//! - `linear_recurrence_kth`: the `n`-th term of a linear recurrence by Kitamasa's method.
+//! - `berlekamp_massey`: the shortest linear recurrence generating a sequence over a prime field.
+//!
+//! Multiplicative functions:
+//! - `euler_phi`: Euler's totient, from `n` or from its factorization.
+//! - `carmichael_lambda`: the Carmichael function, the exponent of the group of units.
+//! - `mobius`: the Möbius function, from `n` or from its factorization.
+//! - `Sieve::totients`, `Sieve::mobius`: the totient and the Möbius function of every integer up to a limit.
+//!
+//! Multiplicative group:
+//! - `primitive_root`: the smallest generator of the group of units, if the group is cyclic.
+//! - `unit_group_structure`: generators and orders of the cyclic factors of the group of units.
+//! - `units`, `nth_unit`: the units in `[1, m)` in increasing order.
+//! - `discrete_log`: the discrete logarithm with the baby-step giant-step algorithm.
+//! - `discrete_log_ph`: the discrete logarithm with the Pohlig–Hellman algorithm for smooth group orders.
+//!
+//! Linear algebra:
+//! - `ModMatrix`: matrices modulo `m` with multiplication, fast exponentiation, determinants and inverses.
+//! - `solve_mod`: all solutions of a linear system modulo a prime.
+//!
+//! Combinatorics:
+//! - `binomial_lucas`: binomial coefficients of large arguments modulo a small prime with Lucas' theorem.
+//! - `binomial_mod`: binomial coefficients modulo any modulus, through prime powers and the Chinese Remainder Theorem.
+//!
+//! Polynomials:
+//! - `ModPoly`: polynomials modulo `m` with arithmetic, evaluation, division, gcd and powers modulo a polynomial.
+//! - `lagrange_interpolate`, `lagrange_evaluate_at`: Lagrange interpolation modulo a prime.
+//!
+//! The `consts` module provides `const fn` versions of the core operations for 64-bit moduli, for compile-time tables,
+//! and the `modulus!` macro checks at compile time that a modulus is nonzero or prime.
+//!
+//! The `ntt` module provides the number-theoretic transform and fast convolution modulo NTT-friendly primes,
+//! as well as modulo any modulus of up to 31 bits.
+//!
+//! Sampling:
+//! - `random_residue`: a uniformly distributed residue, by rejection sampling.
+//! - `random_unit`, `random_invertible_pair`: a uniformly distributed unit, alone or with its inverse.
+//!
+//! Hashing:
+//! - `RollingHash`, `DoubleRollingHash`: polynomial rolling hashes with `O(1)` range hashes.
+//!
+//! Precomputed tables:
+//! - `InverseTable`: the inverses of `1..=n` modulo a prime.
+//! - `Factorials`: the factorials of `0..=n` and their inverses modulo a prime, with binomial coefficients and related counts.
 //!
 //! # Example
 //! ```
@@ -30,23 +148,111 @@
 //! assert!(!a.eq_mod(6, modulus));
 //! ```
 
+mod accumulator;
+mod barrett;
+mod batch;
+mod checked;
+mod combinatorics;
+pub mod consts;
+mod crt;
+pub mod ct;
+mod diophantine;
+mod dlog;
 mod egcd;
+mod factor;
+mod frac;
+mod gcd;
+mod group;
+mod hash;
+mod hensel;
 mod integer;
 mod invert;
+mod linear;
+mod matrix;
+mod mixed;
+mod modint;
 mod modulus;
-
+mod montgomery;
+mod multiplicative;
+pub mod ntt;
+mod parallel;
+mod poly;
+mod pow;
+mod prime;
+mod rational;
+mod residue;
+mod rng;
+mod root;
+mod sequence;
+mod sieve;
+mod slice;
+mod sqrt;
+mod symbol;
+mod table;
+#[cfg(feature = "zeroize")]
+mod wipe;
+
+/// The code generated by `#[derive(ModicumInteger)]` refers to `num_traits` through this path.
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub use num_traits as __num_traits;
+
+pub use accumulator::ModAccumulator;
+pub use barrett::Barrett;
+pub use batch::{batch_invert, batch_invert_in_place};
+pub use checked::{CheckedMod, ModError};
+pub use combinatorics::{binomial_lucas, binomial_mod, Factorials, FactorialsError};
+pub use crt::{crt, crt_slice, garner, CrtError};
+pub use diophantine::{solve_diophantine, DiophantineIter, DiophantineSolution};
+pub use dlog::{discrete_log, discrete_log_ph, discrete_log_ph_prime};
 pub use egcd::Egcd;
-pub use integer::Integer;
-pub use invert::Invert;
-pub use modulus::Modulus;
-use num_traits::FromPrimitive;
+pub use factor::factorize;
+pub use frac::FracMod;
+pub use gcd::{are_coprime, binary_gcd, Gcd, IsUnit};
+pub use group::{is_primitive_root, nth_unit, primitive_root, unit_group_structure, units, Units};
+pub use hash::{DoubleRollingHash, RollingHash, MERSENNE_61};
+pub use hensel::hensel_lift;
+pub use integer::{Integer, Widen};
+pub use invert::{Invert, InvertWidening};
+pub use linear::{solve_linear_congruence, LinearSolutions, LinearSolutionsIter};
+pub use matrix::{solve_mod, MatrixError, ModMatrix, Solution};
+#[cfg(feature = "derive")]
+pub use modicum_derive::ModicumInteger;
+pub use modint::{Mint1e9, Mint998, ModInt};
+pub use modulus::{Modulus, Prime, PrimeModulus, SignedModulus};
+pub use montgomery::{InvertCt, Montgomery, PowModCt};
+pub use multiplicative::{
+    carmichael_lambda, carmichael_lambda_factored, euler_phi, euler_phi_factored, mobius,
+    mobius_factored,
+};
+use num_traits::{FromPrimitive, Signed};
+pub use poly::{lagrange_evaluate_at, lagrange_interpolate, ModPoly, PolyError};
+pub use pow::{
+    geometric_sum_mod, multi_pow_mod, pow_mod_bytes, pow_mod_limbs, pow_mod_window, powers_mod,
+    powers_mod_upto, FixedBasePow, PowersMod,
+};
+pub use prime::{
+    is_bpsw_prime, is_prime, is_prime_u64, is_probable_prime, is_probable_prime_with,
+    is_strong_probable_prime,
+};
+pub use rational::{rational_reconstruct, rational_reconstruct_bounded};
+pub use residue::{format_congruence, ParseResidueError, Residue, ResidueError};
+pub use rng::{random_invertible_pair, random_residue, random_unit, RandomSource, SplitMix64};
+pub use root::{all_nth_roots, nth_root_mod, NthRoots};
+pub use sequence::{berlekamp_massey, fibonacci_mod, linear_recurrence_kth, lucas_mod};
+pub use sieve::Sieve;
+pub use slice::{
+    add_mod_slice, constrain_slice, dot_mod, mul_mod_slice, prefix_products_mod, prefix_sums_mod,
+    range_product, range_sum, scale_mod_slice, sub_mod_slice,
+};
+pub use sqrt::{sqrt_mod_composite, sqrt_mod_prime_power, QuadraticResidue, SqrtMod};
+pub use symbol::{jacobi, kronecker};
+pub use table::InverseTable;
+#[cfg(feature = "zeroize")]
+pub use wipe::Wipe;
 
 /// A trait to constrain an integer to a modulus.
-pub trait Constrain<M: Modulus<Self>>
-where
-    Self: TryFrom<M>,
-    <Self as TryFrom<M>>::Error: std::fmt::Debug,
-{
+pub trait Constrain<M>: Sized {
     /// Constrain an integer to a modulus.
     fn constrain(self, modulus: M) -> Self;
 }
@@ -54,7 +260,6 @@ where
 impl<T, M> Constrain<M> for T
 where
     T: Integer + TryFrom<M>,
-    <T as TryFrom<M>>::Error: std::fmt::Debug,
     M: Modulus<T>,
 {
     fn constrain(self, modulus: M) -> T {
@@ -63,12 +268,72 @@ where
     }
 }
 
-/// A trait to add two integers and constrain the result to a modulus.
-pub trait AddMod<M: Modulus<Self>, Rhs = Self>
+/// A trait to constrain an integer and return the canonical residue in the modulus type,
+/// which always fits since it is below the modulus.
+pub trait ConstrainInto<M>: Sized {
+    /// Constrain an integer to a modulus and convert the result to the modulus type.
+    fn constrain_into(self, modulus: M) -> M;
+
+    /// Add two integers modulo a modulus, as `add_mod`, and convert the result to the modulus type.
+    fn add_mod_into(self, rhs: Self, modulus: M) -> M;
+
+    /// Multiply two integers modulo a modulus, as `mul_mod`, and convert the result to the modulus type.
+    fn mul_mod_into(self, rhs: Self, modulus: M) -> M;
+}
+
+impl<T, M> ConstrainInto<M> for T
 where
-    Self: TryFrom<M>,
-    <Self as TryFrom<M>>::Error: std::fmt::Debug,
+    T: Integer + TryFrom<M>,
+    M: Modulus<T> + TryFrom<T>,
 {
+    fn constrain_into(self, modulus: M) -> M {
+        M::try_from(self.constrain(modulus))
+            .unwrap_or_else(|_| panic!("a canonical residue fits the modulus type"))
+    }
+
+    fn add_mod_into(self, rhs: T, modulus: M) -> M {
+        self.add_mod(rhs, modulus).constrain_into(modulus)
+    }
+
+    fn mul_mod_into(self, rhs: T, modulus: M) -> M {
+        self.mul_mod(rhs, modulus).constrain_into(modulus)
+    }
+}
+
+/// A trait to constrain a signed integer to the balanced range of residues around zero.
+pub trait ConstrainSymmetric<M>: Sized {
+    /// Constrain an integer to the symmetric range `(-m/2, m/2]`, so that `6` modulo `7` is `-1`.
+    /// For an even modulus the boundary `m/2` is kept positive, so `4` and `-4` modulo `8` are both `4`.
+    /// Unlike `constrain`, this never overflows, even for `T::MIN` or moduli above `T::MAX / 2`.
+    fn constrain_symmetric(self, modulus: M) -> Self;
+}
+
+impl<T, M> ConstrainSymmetric<M> for T
+where
+    T: Integer + Signed + PartialOrd + TryFrom<M>,
+    M: Modulus<T>,
+{
+    fn constrain_symmetric(self, modulus: M) -> T {
+        let modulus = modulus.cast();
+        let half = modulus / (T::one() + T::one());
+        // The remainder has the sign of `self` and lies in `(-m, m)`, so stepping by `m` towards zero cannot overflow.
+        let r = self % modulus;
+        if r.is_negative() {
+            if r + modulus <= half {
+                r + modulus
+            } else {
+                r
+            }
+        } else if r > half {
+            r - modulus
+        } else {
+            r
+        }
+    }
+}
+
+/// A trait to add two integers and constrain the result to a modulus.
+pub trait AddMod<M, Rhs = Self>: Sized {
     /// The output type.
     type Output;
 
@@ -79,7 +344,6 @@ where
 impl<T, M> AddMod<M> for T
 where
     T: Integer + TryFrom<M>,
-    <T as TryFrom<M>>::Error: std::fmt::Debug,
     M: Modulus<T>,
 {
     type Output = T;
@@ -89,11 +353,7 @@ where
 }
 
 /// A trait to subtract two integers and constrain the result to a modulus.
-pub trait SubMod<M: Modulus<Self>, Rhs = Self>
-where
-    Self: TryFrom<M>,
-    <Self as TryFrom<M>>::Error: std::fmt::Debug,
-{
+pub trait SubMod<M, Rhs = Self>: Sized {
     /// The output type.
     type Output;
 
@@ -104,7 +364,6 @@ where
 impl<T, M> SubMod<M> for T
 where
     T: Integer + TryFrom<M>,
-    <T as TryFrom<M>>::Error: std::fmt::Debug,
     M: Modulus<T>,
 {
     /// The output type.
@@ -116,12 +375,26 @@ where
     }
 }
 
-/// A trait to multiply two integers and constrain the result to a modulus.
-pub trait MulMod<M: Modulus<Self>, Rhs = Self>
+/// Subtraction for the crate's own arithmetic on residues, which are often unsigned.
+/// `sub_mod` subtracts first, which underflows for unsigned types whenever `rhs > self`.
+pub(crate) trait SubModConstrained<M>: Sized {
+    /// Subtract two integers modulo a modulus as `sub_mod`, constraining both operands first
+    /// so that the difference is never negative. This costs three reductions instead of one.
+    fn sub_mod_constrained(self, rhs: Self, modulus: M) -> Self;
+}
+
+impl<T, M> SubModConstrained<M> for T
 where
-    Self: TryFrom<M>,
-    <Self as TryFrom<M>>::Error: std::fmt::Debug,
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
 {
+    fn sub_mod_constrained(self, rhs: T, modulus: M) -> T {
+        (self.constrain(modulus) + (modulus.cast() - rhs.constrain(modulus))).constrain(modulus)
+    }
+}
+
+/// A trait to multiply two integers and constrain the result to a modulus.
+pub trait MulMod<M, Rhs = Self>: Sized {
     /// The output type.
     type Output;
 
@@ -132,7 +405,6 @@ where
 impl<T, M> MulMod<M> for T
 where
     T: Integer + TryFrom<M>,
-    <T as TryFrom<M>>::Error: std::fmt::Debug,
     M: Modulus<T>,
 {
     type Output = T;
@@ -142,23 +414,32 @@ where
 }
 
 /// A trait to divide two integers and constrain the result to a modulus.
-pub trait DivMod<M: Modulus<Self>, Rhs = Self>
-where
-    Self: TryFrom<M>,
-    <Self as TryFrom<M>>::Error: std::fmt::Debug,
-{
+pub trait DivMod<M, Rhs = Self>: Sized {
     /// The output type.
     type Output;
 
     /// Divide two integers and constrain the result to a modulus.
     /// If the divisor is not invertible, return `None`.
     fn div_mod(self, rhs: Rhs, modulus: M) -> Option<Self::Output>;
+
+    /// Divide two integers modulo a prime and constrain the result to the modulus.
+    /// The divisor is inverted with Fermat's little theorem, so the modulus must be prime.
+    /// If the divisor is congruent to zero, return `None`.
+    fn div_mod_prime(self, rhs: Rhs, modulus: M) -> Option<Self::Output>
+    where
+        Self: FromPrimitive;
+
+    /// Divide two integers when the divisor shares a factor `g = gcd(rhs, modulus)` with the modulus.
+    /// The division is then performed modulo the reduced modulus `modulus / g`, which is returned
+    /// along with the canonical quotient. If `g` does not divide `self`, there is no solution and `None` is returned.
+    fn div_mod_exact(self, rhs: Rhs, modulus: M) -> Option<(Self::Output, M)>
+    where
+        M: TryFrom<Self>;
 }
 
 impl<T, M> DivMod<M> for T
 where
     T: Invert + TryFrom<M>,
-    <T as TryFrom<M>>::Error: std::fmt::Debug,
     M: Modulus<T>,
 {
     type Output = T;
@@ -166,14 +447,26 @@ where
         let inverse = rhs.invert(modulus)?;
         Some((inverse * self).constrain(modulus))
     }
+    fn div_mod_prime(self, rhs: T, modulus: M) -> Option<T>
+    where
+        T: FromPrimitive,
+    {
+        let inverse = rhs.invert_prime(modulus)?;
+        Some(inverse.mul_mod(self.constrain(modulus), modulus))
+    }
+    fn div_mod_exact(self, rhs: T, modulus: M) -> Option<(T, M)>
+    where
+        M: TryFrom<T>,
+    {
+        let solutions = solve_linear_congruence(rhs, self, modulus)?;
+        let reduced = M::try_from(solutions.step())
+            .unwrap_or_else(|_| panic!("reduced modulus fits the modulus type"));
+        Some((solutions.first(), reduced))
+    }
 }
 
 /// A trait to raise an integer to a power and constrain the result to a modulus.
-pub trait PowMod<M: Modulus<Self>, Rhs = Self>
-where
-    Self: TryFrom<M>,
-    <Self as TryFrom<M>>::Error: std::fmt::Debug,
-{
+pub trait PowMod<M, Rhs = Self>: Sized {
     /// The output type.
     type Output;
 
@@ -183,8 +476,7 @@ where
 
 impl<T, M> PowMod<M> for T
 where
-    T: Integer + TryFrom<M> + Constrain<M> + FromPrimitive + MulMod<M, Output = T> + DivMod<M>,
-    <T as TryFrom<M>>::Error: std::fmt::Debug,
+    T: Integer + TryFrom<M> + Constrain<M> + FromPrimitive + MulMod<M, Output = T>,
     M: Modulus<T>,
 {
     type Output = T;
@@ -204,12 +496,9 @@ where
 }
 
 /// A trait to check if two integers are congruent, that is, they are equal modulo a given modulus.
-pub trait EqMod<M: Modulus<Self>, Rhs = Self>
-where
-    Self: TryFrom<M>,
-    <Self as TryFrom<M>>::Error: std::fmt::Debug,
-{
+pub trait EqMod<M, Rhs = Self>: Sized {
     /// Check if two integers are congruent modulo a given modulus.
+    /// This is not constant time; see `ct::ct_eq_mod` for secret operands.
     fn eq_mod(self, rhs: Rhs, modulus: M) -> bool;
 
     /// Check if two integers are not congruent modulo a given modulus.
@@ -219,7 +508,6 @@ where
 impl<T, M> EqMod<M> for T
 where
     T: Integer + TryFrom<M> + Constrain<M>,
-    <T as TryFrom<M>>::Error: std::fmt::Debug,
     M: Modulus<T>,
 {
     fn eq_mod(self, rhs: T, modulus: M) -> bool {
@@ -242,6 +530,50 @@ mod tests {
         assert_eq!(10.constrain(11u8), 10);
     }
 
+    #[test]
+    fn test_constrain_into() {
+        let residue: u8 = (-3i64).constrain_into(7u8);
+        assert_eq!(residue, 4);
+        assert_eq!(i64::MIN.constrain_into(u32::MAX), 2_147_483_647);
+        assert_eq!((-1i64).constrain_into(1u32 << 31), (1 << 31) - 1);
+        assert_eq!((-5i16).add_mod_into(2, 200u8), 197);
+        assert_eq!((-5i64).mul_mod_into(3, 1_000_000_007u32), 999_999_992);
+        for x in -300i64..300 {
+            for m in [1u8, 2, 7, 200, 255] {
+                assert_eq!(x.constrain_into(m) as i64, x.constrain(m));
+            }
+            for m in [1u32, 13, 1 << 31, u32::MAX] {
+                assert_eq!(x.constrain_into(m) as i64, x.constrain(m));
+            }
+        }
+    }
+
+    #[test]
+    fn test_constrain_symmetric() {
+        assert_eq!(6.constrain_symmetric(7u8), -1);
+        assert_eq!(3.constrain_symmetric(7u8), 3);
+        assert_eq!((-3).constrain_symmetric(7u8), -3);
+        assert_eq!(4.constrain_symmetric(8u8), 4);
+        assert_eq!((-4).constrain_symmetric(8u8), 4);
+        assert_eq!(5.constrain_symmetric(8u8), -3);
+        assert_eq!(17.constrain_symmetric(1u8), 0);
+        for m in 1u8..20 {
+            for x in -50i32..50 {
+                let r = x.constrain_symmetric(m);
+                let m = m as i32;
+                assert!(-m < 2 * r && 2 * r <= m);
+                assert_eq!(r.constrain(m as u8), x.constrain(m as u8));
+            }
+        }
+        // Near the limits of the type, where `constrain` would overflow.
+        assert_eq!(i8::MIN.constrain_symmetric(127u8), -1);
+        assert_eq!(i8::MIN.constrain_symmetric(100u8), -28);
+        assert_eq!(i8::MAX.constrain_symmetric(127u8), 0);
+        assert_eq!(i8::MAX.constrain_symmetric(100u8), 27);
+        assert_eq!(i64::MIN.constrain_symmetric(i64::MAX as u64), -1);
+        assert_eq!((i64::MAX - 1).constrain_symmetric(i64::MAX as u64), -1);
+    }
+
     #[test]
     fn test_constrain_negative() {
         assert_eq!((-10).constrain(5u8), 0);
@@ -270,6 +602,14 @@ mod tests {
         assert_eq!(10.sub_mod(5, 13u8), 5);
     }
 
+    #[test]
+    fn test_sub_mod_constrained() {
+        assert_eq!(3u32.sub_mod_constrained(5, 7u8), 5);
+        assert_eq!(0u64.sub_mod_constrained(u64::MAX, 10u64), 5);
+        assert_eq!(5u8.sub_mod_constrained(3, 7u8), 2);
+        assert_eq!((-10).sub_mod_constrained(5, 7u8), (-10).sub_mod(5, 7u8));
+    }
+
     #[test]
     fn test_sub_negative_mod() {
         assert_eq!((-10).sub_mod(5, 7u8), 6);
@@ -307,6 +647,47 @@ mod tests {
         assert_eq!((-10).div_mod(5, 10u8), None);
     }
 
+    #[test]
+    fn test_div_mod_exact() {
+        // 5x ≡ 10 (mod 10) reduces to x ≡ 2 ≡ 0 (mod 2).
+        assert_eq!(10.div_mod_exact(5, 10u8), Some((0, 2)));
+        assert_eq!(9.div_mod_exact(6, 15u8), Some((4, 5)));
+        assert_eq!((-9).div_mod_exact(6, 15u32), Some((1, 5)));
+        assert_eq!(9i64.div_mod_exact(-6, 15u64), Some((1, 5)));
+        assert_eq!(0.div_mod_exact(0, 6u16), Some((0, 1)));
+    }
+
+    #[test]
+    fn test_div_mod_exact_invertible() {
+        for m in [7u8, 10, 12] {
+            for a in -15..15 {
+                for b in -15..15 {
+                    if let Some(quotient) = a.div_mod(b, m) {
+                        assert_eq!(a.div_mod_exact(b, m), Some((quotient, m)));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_div_mod_exact_unsolvable() {
+        assert_eq!(3.div_mod_exact(5, 10u8), None);
+        assert_eq!((-7).div_mod_exact(4, 8u32), None);
+        assert_eq!(1.div_mod_exact(0, 6u16), None);
+    }
+
+    #[test]
+    fn test_div_mod_prime() {
+        for p in [7u8, 11, 13] {
+            for a in -20..20 {
+                for b in -20..20 {
+                    assert_eq!(a.div_mod_prime(b, p), a.div_mod(b, p));
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_eq_mod() {
         assert!(10.eq_mod(3, 7u8));
@@ -0,0 +1,147 @@
+use crate::{Constrain, Egcd, Integer, Modulus, MulMod};
+
+/// The solutions of a linear congruence `ax ≡ b (mod m)` in `[0, m)`.
+/// They are `first + k * step` for `k` in `0..count`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LinearSolutions<T> {
+    first: T,
+    step: T,
+    count: T,
+}
+
+impl<T: Integer> LinearSolutions<T> {
+    /// The smallest solution in `[0, m)`.
+    pub fn first(&self) -> T {
+        self.first
+    }
+
+    /// The distance between consecutive solutions, that is, `m / gcd(a, m)`.
+    pub fn step(&self) -> T {
+        self.step
+    }
+
+    /// The number of solutions in `[0, m)`, that is, `gcd(a, m)`.
+    pub fn count(&self) -> T {
+        self.count
+    }
+
+    /// Iterate over all solutions in `[0, m)` in increasing order.
+    pub fn iter(&self) -> LinearSolutionsIter<T> {
+        LinearSolutionsIter {
+            next: self.first,
+            step: self.step,
+            remaining: self.count,
+        }
+    }
+}
+
+impl<T: Integer> IntoIterator for LinearSolutions<T> {
+    type Item = T;
+    type IntoIter = LinearSolutionsIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the solutions of a linear congruence.
+#[derive(Clone, Debug)]
+pub struct LinearSolutionsIter<T> {
+    next: T,
+    step: T,
+    remaining: T,
+}
+
+impl<T: Integer> Iterator for LinearSolutionsIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.remaining == T::zero() {
+            return None;
+        }
+        let current = self.next;
+        self.remaining = self.remaining - T::one();
+        if self.remaining != T::zero() {
+            self.next = self.next + self.step;
+        }
+        Some(current)
+    }
+}
+
+/// Solve the linear congruence `ax ≡ b (mod m)`.
+/// A solution exists if and only if `gcd(a, m)` divides `b`, in which case there are exactly `gcd(a, m)` of them.
+/// Returns `None` if there is no solution.
+pub fn solve_linear_congruence<T, M>(a: T, b: T, m: M) -> Option<LinearSolutions<T>>
+where
+    T: Egcd + TryFrom<M>,
+    M: Modulus<T> + TryFrom<T>,
+{
+    let a = a.constrain(m);
+    let b = b.constrain(m);
+    let (g, x) = a.half_egcd(m.cast());
+    if b % g != T::zero() {
+        return None;
+    }
+    let step = m / M::try_from(g).unwrap_or_else(|_| panic!("gcd fits the modulus type"));
+    let first = (b / g).constrain(step).mul_mod(x.constrain(step), step);
+    Some(LinearSolutions {
+        first,
+        step: step.cast(),
+        count: g,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DivMod;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_solve_linear_congruence() {
+        let solutions = solve_linear_congruence(14, 30, 100u32).unwrap();
+        assert_eq!(solutions.first(), 45);
+        assert_eq!(solutions.step(), 50);
+        assert_eq!(solutions.count(), 2);
+        assert_eq!(solutions.iter().collect::<Vec<_>>(), vec![45, 95]);
+    }
+
+    #[test]
+    fn test_solve_linear_congruence_none() {
+        assert_eq!(solve_linear_congruence(4, 3, 8u32), None);
+        assert_eq!(solve_linear_congruence(0, 3, 8u32), None);
+    }
+
+    #[test]
+    fn test_solve_linear_congruence_negative() {
+        let solutions = solve_linear_congruence(-14, -30, 100u32).unwrap();
+        assert_eq!(solutions.into_iter().collect::<Vec<_>>(), vec![45, 95]);
+        let solutions = solve_linear_congruence(6, -4, 10u32).unwrap();
+        assert_eq!(solutions.into_iter().collect::<Vec<_>>(), vec![1, 6]);
+    }
+
+    #[test]
+    fn test_solve_linear_congruence_all() {
+        let solutions = solve_linear_congruence(8, 16, 4u32).unwrap();
+        assert_eq!(solutions.into_iter().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+        let solutions = solve_linear_congruence(5, 3, 1u32).unwrap();
+        assert_eq!(solutions.into_iter().collect::<Vec<_>>(), vec![0]);
+    }
+
+    #[test]
+    fn test_solve_linear_congruence_invertible() {
+        for m in [7u8, 11, 12, 13] {
+            for a in -15..15 {
+                for b in -15..15 {
+                    let solutions = solve_linear_congruence(a, b, m);
+                    if let Some(quotient) = b.div_mod(a, m) {
+                        assert_eq!(solutions.map(|s| s.iter().collect()), Some(vec![quotient]));
+                    }
+                    if let Some(solutions) = solutions {
+                        assert!(solutions.iter().all(|x| (a * x - b) % m as i32 == 0));
+                    }
+                }
+            }
+        }
+    }
+}
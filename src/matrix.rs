@@ -0,0 +1,742 @@
+use std::fmt;
+use std::ops::{Add, Index, Mul, Sub};
+
+use num_traits::CheckedAdd;
+
+use crate::{parallel, AddMod, Constrain, Integer, Invert, Modulus, MulMod, SubModConstrained};
+
+/// An error combining two matrices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatrixError {
+    /// The dimensions do not fit the operation; the shapes are given as `(rows, cols)`.
+    DimensionMismatch {
+        /// The shape of the left operand.
+        left: (usize, usize),
+        /// The shape of the right operand.
+        right: (usize, usize),
+    },
+    /// The matrices are reduced modulo different moduli.
+    ModulusMismatch,
+}
+
+impl fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatrixError::DimensionMismatch { left, right } => write!(
+                f,
+                "dimension mismatch between {}x{} and {}x{} matrices",
+                left.0, left.1, right.0, right.1
+            ),
+            MatrixError::ModulusMismatch => write!(f, "matrices have different moduli"),
+        }
+    }
+}
+
+impl std::error::Error for MatrixError {}
+
+/// A matrix over the integers modulo `m`, stored row-major with dimensions chosen at runtime.
+/// All entries are kept constrained to `[0, m)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModMatrix<T, M> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+    modulus: M,
+}
+
+impl<T, M> ModMatrix<T, M>
+where
+    T: Integer + TryFrom<M> + CheckedAdd,
+    M: Modulus<T>,
+{
+    /// Create a matrix from its entries in row-major order, constraining each of them to the modulus.
+    /// Panics if `data` does not hold exactly `rows * cols` entries.
+    pub fn new(rows: usize, cols: usize, data: Vec<T>, modulus: M) -> Self {
+        assert_eq!(data.len(), rows * cols, "expected rows * cols entries");
+        let data = data.into_iter().map(|x| x.constrain(modulus)).collect();
+        Self {
+            rows,
+            cols,
+            data,
+            modulus,
+        }
+    }
+
+    /// Create a matrix with all entries zero.
+    pub fn zero(rows: usize, cols: usize, modulus: M) -> Self {
+        Self {
+            rows,
+            cols,
+            data: vec![T::zero(); rows * cols],
+            modulus,
+        }
+    }
+
+    /// Create the `n × n` identity matrix.
+    pub fn identity(n: usize, modulus: M) -> Self {
+        let mut matrix = Self::zero(n, n, modulus);
+        let one = T::one().constrain(modulus);
+        for i in 0..n {
+            matrix.data[i * n + i] = one;
+        }
+        matrix
+    }
+
+    /// The number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// The number of columns.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// The modulus the entries are reduced by.
+    pub fn modulus(&self) -> M {
+        self.modulus
+    }
+
+    /// Get the entries of row `i`.
+    /// Panics if `i` is out of bounds.
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    /// Set the entry at row `i` and column `j`, constraining it to the modulus.
+    /// Panics if the position is out of bounds.
+    pub fn set(&mut self, i: usize, j: usize, value: T) {
+        assert!(i < self.rows && j < self.cols, "index out of bounds");
+        self.data[i * self.cols + j] = value.constrain(self.modulus);
+    }
+
+    /// Add two matrices of the same shape entry by entry.
+    pub fn try_add(&self, rhs: &Self) -> Result<Self, MatrixError> {
+        self.check(rhs, self.shape() == rhs.shape())?;
+        Ok(self.zip_with(rhs, |a, b| a.add_mod(b, self.modulus)))
+    }
+
+    /// Subtract two matrices of the same shape entry by entry.
+    pub fn try_sub(&self, rhs: &Self) -> Result<Self, MatrixError> {
+        self.check(rhs, self.shape() == rhs.shape())?;
+        Ok(self.zip_with(rhs, |a, b| a.sub_mod_constrained(b, self.modulus)))
+    }
+
+    /// Multiply two matrices, which requires the columns of `self` to match the rows of `rhs`.
+    /// Each entry of the product accumulates the unreduced products of the entries, which fit `T` as they do in `mul_mod`,
+    /// and is reduced only when the sum would overflow `T` and once at the end,
+    /// so most dot products pay for a single reduction instead of one per term.
+    pub fn try_mul(&self, rhs: &Self) -> Result<Self, MatrixError> {
+        self.check(rhs, self.cols == rhs.rows)?;
+        let modulus = self.modulus;
+        let mut data = vec![T::zero(); self.rows * rhs.cols];
+        // Blocks of whole rows of the product, one per thread with the `parallel` feature.
+        let rows = parallel::chunk_len(self.rows, self.cols * rhs.cols);
+        if rhs.cols > 0 {
+            parallel::map_chunks_mut(&mut data, rows * rhs.cols, |block, data| {
+                for (offset, sums) in data.chunks_mut(rhs.cols).enumerate() {
+                    for (k, &a) in self.row(block * rows + offset).iter().enumerate() {
+                        if a == T::zero() {
+                            continue;
+                        }
+                        for (sum, &b) in sums.iter_mut().zip(rhs.row(k)) {
+                            let term = a * b;
+                            // Two residues add up to less than `m²`, which fits since the product of two entries does.
+                            *sum = match sum.checked_add(&term) {
+                                Some(s) => s,
+                                None => sum.constrain(modulus) + term.constrain(modulus),
+                            };
+                        }
+                    }
+                    for sum in sums.iter_mut() {
+                        *sum = sum.constrain(modulus);
+                    }
+                }
+            });
+        }
+        Ok(Self {
+            rows: self.rows,
+            cols: rhs.cols,
+            data,
+            modulus,
+        })
+    }
+
+    /// Raise a square matrix to a non-negative power by repeated squaring; the zeroth power is the identity.
+    /// Panics if the matrix is not square.
+    pub fn pow(&self, exponent: u64) -> Self {
+        assert_eq!(self.rows, self.cols, "only square matrices have powers");
+        let mut result = Self::identity(self.rows, self.modulus);
+        for i in (0..u64::BITS - exponent.leading_zeros()).rev() {
+            result = &result * &result;
+            if (exponent >> i) & 1 == 1 {
+                result = &result * self;
+            }
+        }
+        result
+    }
+
+    /// Compute the determinant modulo any modulus without inversions, in `O(n³ log m)` multiplications.
+    /// Below each pivot, the entries of a column are cleared by running the Euclidean algorithm on the rows,
+    /// which only adds integer multiples of one row to another and swaps rows.
+    /// Panics if the matrix is not square.
+    pub fn determinant_composite(&self) -> T {
+        assert_eq!(
+            self.rows, self.cols,
+            "only square matrices have determinants"
+        );
+        let (n, modulus) = (self.rows, self.modulus);
+        let mut a = self.clone();
+        let mut det = T::one().constrain(modulus);
+        for col in 0..n {
+            for r in col + 1..n {
+                while a[(r, col)] != T::zero() {
+                    let quotient = a[(col, col)] / a[(r, col)];
+                    a.sub_scaled_row(col, r, quotient, col);
+                    a.swap_rows(col, r);
+                    det = T::zero().sub_mod_constrained(det, modulus);
+                }
+            }
+            det = det.mul_mod(a[(col, col)], modulus);
+            if det == T::zero() {
+                return det;
+            }
+        }
+        det
+    }
+
+    fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// Check that the moduli agree and that the shapes satisfy `compatible`.
+    fn check(&self, rhs: &Self, compatible: bool) -> Result<(), MatrixError> {
+        if self.modulus != rhs.modulus {
+            return Err(MatrixError::ModulusMismatch);
+        }
+        if !compatible {
+            return Err(MatrixError::DimensionMismatch {
+                left: self.shape(),
+                right: rhs.shape(),
+            });
+        }
+        Ok(())
+    }
+
+    fn swap_rows(&mut self, i: usize, j: usize) {
+        for c in 0..self.cols {
+            self.data.swap(i * self.cols + c, j * self.cols + c);
+        }
+    }
+
+    fn scale_row(&mut self, i: usize, factor: T) {
+        for x in &mut self.data[i * self.cols..(i + 1) * self.cols] {
+            *x = x.mul_mod(factor, self.modulus);
+        }
+    }
+
+    /// Subtract `factor` times row `source` from row `target`, starting at column `from`.
+    fn sub_scaled_row(&mut self, target: usize, source: usize, factor: T, from: usize) {
+        if factor == T::zero() {
+            return;
+        }
+        for c in from..self.cols {
+            let delta = factor.mul_mod(self.data[source * self.cols + c], self.modulus);
+            let x = &mut self.data[target * self.cols + c];
+            *x = x.sub_mod_constrained(delta, self.modulus);
+        }
+    }
+
+    fn zip_with(&self, rhs: &Self, f: impl Fn(T, T) -> T) -> Self {
+        Self {
+            rows: self.rows,
+            cols: self.cols,
+            data: self
+                .data
+                .iter()
+                .zip(&rhs.data)
+                .map(|(&a, &b)| f(a, b))
+                .collect(),
+            modulus: self.modulus,
+        }
+    }
+}
+
+impl<T, M> ModMatrix<T, M>
+where
+    T: Invert + TryFrom<M> + CheckedAdd,
+    M: Modulus<T>,
+{
+    /// Compute the determinant modulo a prime with Gaussian elimination, in `O(n³)` multiplications.
+    /// Panics if the matrix is not square, or if a nonzero pivot has no inverse,
+    /// which can only happen when the modulus is not prime; see `determinant_composite`.
+    pub fn determinant(&self) -> T {
+        assert_eq!(
+            self.rows, self.cols,
+            "only square matrices have determinants"
+        );
+        let (n, modulus) = (self.rows, self.modulus);
+        let mut a = self.clone();
+        let mut det = T::one().constrain(modulus);
+        for col in 0..n {
+            let Some(pivot) = (col..n).find(|&r| a[(r, col)] != T::zero()) else {
+                return T::zero();
+            };
+            if pivot != col {
+                a.swap_rows(pivot, col);
+                det = T::zero().sub_mod_constrained(det, modulus);
+            }
+            let p = a[(col, col)];
+            det = det.mul_mod(p, modulus);
+            let inverse = p
+                .invert(modulus)
+                .expect("nonzero pivots are invertible modulo a prime");
+            for r in col + 1..n {
+                let factor = a[(r, col)].mul_mod(inverse, modulus);
+                a.sub_scaled_row(r, col, factor, col);
+            }
+        }
+        det
+    }
+
+    /// Compute the inverse modulo a prime with Gauss–Jordan elimination.
+    /// Returns `None` if the matrix is singular modulo the prime.
+    /// For a composite modulus a pivot may lack an inverse even though the matrix has one, which also gives `None`.
+    /// Panics if the matrix is not square.
+    pub fn inverse(&self) -> Option<Self> {
+        assert_eq!(self.rows, self.cols, "only square matrices have inverses");
+        let (n, modulus) = (self.rows, self.modulus);
+        let mut a = self.clone();
+        let mut inverse = Self::identity(n, modulus);
+        for col in 0..n {
+            let (pivot, scale) =
+                (col..n).find_map(|r| a[(r, col)].invert(modulus).map(|x| (r, x)))?;
+            a.swap_rows(pivot, col);
+            inverse.swap_rows(pivot, col);
+            a.scale_row(col, scale);
+            inverse.scale_row(col, scale);
+            for r in (0..n).filter(|&r| r != col) {
+                let factor = a[(r, col)];
+                a.sub_scaled_row(r, col, factor, col);
+                inverse.sub_scaled_row(r, col, factor, 0);
+            }
+        }
+        Some(inverse)
+    }
+}
+
+/// The solutions of a linear system `Ax = b` over a prime field.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Solution<T> {
+    /// The system has exactly one solution.
+    Unique(Vec<T>),
+    /// The system has no solution.
+    Inconsistent,
+    /// The solutions are `particular` plus any combination of the vectors in `null_space`,
+    /// which form a basis of the solutions of `Ax = 0`.
+    Parametric {
+        /// A solution with every free variable set to zero.
+        particular: Vec<T>,
+        /// A basis of the null space of `A`, one vector per free variable.
+        null_space: Vec<Vec<T>>,
+    },
+}
+
+/// Solve the linear system `Ax = b` modulo a prime by reducing the augmented matrix `[A | b]` to reduced row echelon form.
+/// Zero pivots are skipped by swapping rows, and pivots are normalized with `Invert`.
+/// The matrix may be rectangular, in which case the system may be over- or underdetermined.
+/// Panics if `b` does not have one entry per row of `A`.
+pub fn solve_mod<T, M>(a: &ModMatrix<T, M>, b: &[T]) -> Solution<T>
+where
+    T: Invert + TryFrom<M> + CheckedAdd,
+    M: Modulus<T>,
+{
+    assert_eq!(b.len(), a.rows, "one right-hand side is needed per row");
+    let (rows, cols, modulus) = (a.rows, a.cols, a.modulus);
+    let data = (0..rows)
+        .flat_map(|i| a.row(i).iter().chain(Some(&b[i])).copied())
+        .collect();
+    let mut augmented = ModMatrix::new(rows, cols + 1, data, modulus);
+    let mut pivots = Vec::new();
+    for col in 0..cols {
+        let rank = pivots.len();
+        let Some((pivot, scale)) =
+            (rank..rows).find_map(|r| augmented[(r, col)].invert(modulus).map(|x| (r, x)))
+        else {
+            continue;
+        };
+        augmented.swap_rows(pivot, rank);
+        augmented.scale_row(rank, scale);
+        for r in (0..rows).filter(|&r| r != rank) {
+            let factor = augmented[(r, col)];
+            augmented.sub_scaled_row(r, rank, factor, col);
+        }
+        pivots.push(col);
+    }
+    if (pivots.len()..rows).any(|r| augmented[(r, cols)] != T::zero()) {
+        return Solution::Inconsistent;
+    }
+    let mut particular = vec![T::zero(); cols];
+    for (i, &col) in pivots.iter().enumerate() {
+        particular[col] = augmented[(i, cols)];
+    }
+    if pivots.len() == cols {
+        return Solution::Unique(particular);
+    }
+    let one = T::one().constrain(modulus);
+    let null_space = (0..cols)
+        .filter(|col| !pivots.contains(col))
+        .map(|free| {
+            let mut vector = vec![T::zero(); cols];
+            vector[free] = one;
+            for (i, &col) in pivots.iter().enumerate() {
+                vector[col] = T::zero().sub_mod_constrained(augmented[(i, free)], modulus);
+            }
+            vector
+        })
+        .collect();
+    Solution::Parametric {
+        particular,
+        null_space,
+    }
+}
+
+impl<T, M> Index<(usize, usize)> for ModMatrix<T, M> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        assert!(i < self.rows && j < self.cols, "index out of bounds");
+        &self.data[i * self.cols + j]
+    }
+}
+
+impl<T, M> Add for &ModMatrix<T, M>
+where
+    T: Integer + TryFrom<M> + CheckedAdd,
+    M: Modulus<T>,
+{
+    type Output = ModMatrix<T, M>;
+
+    /// Panics if the shapes or the moduli differ; see `try_add`.
+    fn add(self, rhs: Self) -> ModMatrix<T, M> {
+        self.try_add(rhs).expect("cannot add matrices")
+    }
+}
+
+impl<T, M> Sub for &ModMatrix<T, M>
+where
+    T: Integer + TryFrom<M> + CheckedAdd,
+    M: Modulus<T>,
+{
+    type Output = ModMatrix<T, M>;
+
+    /// Panics if the shapes or the moduli differ; see `try_sub`.
+    fn sub(self, rhs: Self) -> ModMatrix<T, M> {
+        self.try_sub(rhs).expect("cannot subtract matrices")
+    }
+}
+
+impl<T, M> Mul for &ModMatrix<T, M>
+where
+    T: Integer + TryFrom<M> + CheckedAdd,
+    M: Modulus<T>,
+{
+    type Output = ModMatrix<T, M>;
+
+    /// Panics if the shapes do not chain or the moduli differ; see `try_mul`.
+    fn mul(self, rhs: Self) -> ModMatrix<T, M> {
+        self.try_mul(rhs).expect("cannot multiply matrices")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fibonacci_mod, SplitMix64};
+    use pretty_assertions::assert_eq;
+
+    fn random_matrix(
+        rng: &mut SplitMix64,
+        rows: usize,
+        cols: usize,
+        modulus: u64,
+    ) -> ModMatrix<u64, u64> {
+        let data = (0..rows * cols).map(|_| rng.next_u64() % modulus).collect();
+        ModMatrix::new(rows, cols, data, modulus)
+    }
+
+    #[test]
+    fn test_matrix_fibonacci() {
+        let modulus = 1_000_000_007u64;
+        let q = ModMatrix::new(2, 2, vec![1u64, 1, 1, 0], modulus);
+        for n in [1, 2, 10, 90, 1_000_000_000_000] {
+            let power = q.pow(n);
+            assert_eq!(power[(0, 1)], fibonacci_mod::<u64, u64>(n, modulus));
+            assert_eq!(power[(0, 0)], fibonacci_mod::<u64, u64>(n + 1, modulus));
+        }
+    }
+
+    #[test]
+    fn test_matrix_pow_zero() {
+        let a = ModMatrix::new(3, 3, vec![2i64, -1, 0, 5, 7, 3, 1, 1, 1], 11u32);
+        assert_eq!(a.pow(0), ModMatrix::identity(3, 11u32));
+        assert_eq!(a.pow(1), a);
+        assert_eq!(a.pow(3), &(&a * &a) * &a);
+        assert_eq!(
+            ModMatrix::<u32, u32>::identity(2, 1).pow(0),
+            ModMatrix::zero(2, 2, 1)
+        );
+    }
+
+    #[test]
+    fn test_matrix_associativity() {
+        let mut rng = SplitMix64::new(317);
+        for modulus in [2u64, 97, 1_000_000_007] {
+            for _ in 0..20 {
+                let a = random_matrix(&mut rng, 3, 4, modulus);
+                let b = random_matrix(&mut rng, 4, 2, modulus);
+                let c = random_matrix(&mut rng, 2, 5, modulus);
+                assert_eq!(&(&a * &b) * &c, &a * &(&b * &c));
+                let d = random_matrix(&mut rng, 4, 2, modulus);
+                assert_eq!(&a * &(&b + &d), &(&a * &b) + &(&a * &d));
+                assert_eq!(&(&b - &d) + &d, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_mul_large() {
+        let mut rng = SplitMix64::new(337);
+        let modulus = 1_000_000_007u64;
+        let (a, b) = (
+            random_matrix(&mut rng, 45, 40, modulus),
+            random_matrix(&mut rng, 40, 50, modulus),
+        );
+        let product = &a * &b;
+        for i in 0..45 {
+            for j in 0..50 {
+                let expected = (0..40).fold(0, |sum: u64, k| {
+                    sum.add_mod(a[(i, k)].mul_mod(b[(k, j)], modulus), modulus)
+                });
+                assert_eq!(product[(i, j)], expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_delayed_reduction() {
+        // With `u8` entries modulo 15 a product is up to 196, so the accumulator overflows every few terms.
+        let mut rng = SplitMix64::new(15);
+        let wide = random_matrix(&mut rng, 5, 60, 15);
+        let other = random_matrix(&mut rng, 60, 4, 15);
+        let narrow = |m: &ModMatrix<u64, u64>| {
+            let data = (0..m.rows() * m.cols())
+                .map(|k| m[(k / m.cols(), k % m.cols())] as u8)
+                .collect();
+            ModMatrix::new(m.rows(), m.cols(), data, 15u8)
+        };
+        let expected = &wide * &other;
+        let product = &narrow(&wide) * &narrow(&other);
+        for i in 0..5 {
+            for j in 0..4 {
+                assert_eq!(product[(i, j)] as u64, expected[(i, j)]);
+            }
+        }
+        let row = ModMatrix::new(1, 40, vec![14u8; 40], 15u8);
+        let column = ModMatrix::new(40, 1, vec![14u8; 40], 15u8);
+        assert_eq!(row.try_mul(&column).unwrap()[(0, 0)], 10);
+        // Modulo 16 a product of two entries is up to 225, which leaves almost no room in `u8`.
+        let row = ModMatrix::new(1, 40, vec![15u8; 40], 16u8);
+        let column = ModMatrix::new(40, 1, vec![15u8; 40], 16u8);
+        assert_eq!(row.try_mul(&column).unwrap()[(0, 0)], 8);
+    }
+
+    #[test]
+    fn test_matrix_mismatch() {
+        let a = ModMatrix::<u32, u32>::zero(2, 3, 7);
+        let b = ModMatrix::<u32, u32>::zero(2, 3, 7);
+        assert_eq!(
+            a.try_mul(&b),
+            Err(MatrixError::DimensionMismatch {
+                left: (2, 3),
+                right: (2, 3)
+            })
+        );
+        assert!(a.try_add(&b).is_ok());
+        assert_eq!(
+            a.try_sub(&ModMatrix::zero(3, 2, 7)),
+            Err(MatrixError::DimensionMismatch {
+                left: (2, 3),
+                right: (3, 2)
+            })
+        );
+        assert_eq!(
+            a.try_add(&ModMatrix::zero(2, 3, 5)),
+            Err(MatrixError::ModulusMismatch)
+        );
+    }
+
+    #[test]
+    fn test_matrix_accessors() {
+        let mut a = ModMatrix::new(2, 3, vec![-1i32, 8, 3, 4, 5, 6], 7u32);
+        assert_eq!((a.rows(), a.cols(), a.modulus()), (2, 3, 7));
+        assert_eq!(a.row(0), &[6, 1, 3]);
+        a.set(1, 2, -2);
+        assert_eq!(a[(1, 2)], 5);
+    }
+
+    /// The determinant by cofactor expansion along the first row, for small matrices.
+    fn cofactor_determinant(a: &[Vec<i64>]) -> i64 {
+        if a.is_empty() {
+            return 1;
+        }
+        (0..a.len())
+            .map(|j| {
+                let minor: Vec<Vec<i64>> = a[1..]
+                    .iter()
+                    .map(|row| [&row[..j], &row[j + 1..]].concat())
+                    .collect();
+                let sign = if j % 2 == 0 { 1 } else { -1 };
+                sign * a[0][j] * cofactor_determinant(&minor)
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_matrix_determinant() {
+        let a = ModMatrix::new(3, 3, vec![2i64, -3, 1, 2, 0, -1, 1, 4, 5], 1_000_000_007u64);
+        assert_eq!(a.determinant(), 49);
+        assert_eq!(a.determinant_composite(), 49);
+        let b = ModMatrix::new(2, 2, vec![1i32, 2, 3, 4], 7u32);
+        assert_eq!(b.determinant(), 5);
+        assert_eq!(ModMatrix::<i32, u32>::identity(0, 7).determinant(), 1);
+        assert_eq!(ModMatrix::<i32, u32>::zero(3, 3, 7).determinant(), 0);
+        let swapped = ModMatrix::new(2, 2, vec![0i32, 1, 1, 0], 7u32);
+        assert_eq!(swapped.determinant(), 6);
+        assert_eq!(swapped.determinant_composite(), 6);
+    }
+
+    #[test]
+    fn test_matrix_determinant_composite() {
+        let mut rng = SplitMix64::new(318);
+        for modulus in [1u64, 4, 12, 36, 100, 1 << 20] {
+            for n in 1..=5 {
+                let entries: Vec<Vec<i64>> = (0..n)
+                    .map(|_| (0..n).map(|_| (rng.next_u64() % 41) as i64 - 20).collect())
+                    .collect();
+                let a = ModMatrix::new(n, n, entries.concat(), modulus);
+                let expected = cofactor_determinant(&entries).rem_euclid(modulus as i64);
+                assert_eq!(
+                    a.determinant_composite(),
+                    expected,
+                    "{entries:?} mod {modulus}"
+                );
+            }
+        }
+        let a = ModMatrix::new(2, 2, vec![2i64, 3, 3, 2], 6u64);
+        assert_eq!(a.determinant_composite(), 1);
+        assert!(a.inverse().is_none());
+    }
+
+    #[test]
+    fn test_matrix_inverse() {
+        let mut rng = SplitMix64::new(1_000_000_007);
+        let modulus = 1_000_000_007u64;
+        for n in 1..=6 {
+            for _ in 0..10 {
+                let data = (0..n * n)
+                    .map(|_| (rng.next_u64() % modulus) as i64)
+                    .collect();
+                let a = ModMatrix::new(n, n, data, modulus);
+                let Some(inverse) = a.inverse() else {
+                    assert_eq!(a.determinant(), 0);
+                    continue;
+                };
+                assert_eq!(&a * &inverse, ModMatrix::identity(n, modulus));
+                assert_eq!(&inverse * &a, ModMatrix::identity(n, modulus));
+                let det = a.determinant();
+                assert_eq!(det.mul_mod(inverse.determinant(), modulus), 1);
+                assert_eq!(a.determinant_composite(), det);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_singular() {
+        let a = ModMatrix::new(3, 3, vec![1i64, 2, 3, 4, 5, 6, 7, 8, 9], 1_000_000_007u64);
+        assert_eq!(a.determinant(), 0);
+        assert_eq!(a.inverse(), None);
+        // Singular modulo 5 only.
+        let b = ModMatrix::new(2, 2, vec![1i32, 2, 3, 11], 5u32);
+        assert_eq!(b.determinant(), 0);
+        assert_eq!(b.inverse(), None);
+        assert!(ModMatrix::new(2, 2, vec![1i32, 2, 3, 11], 7u32)
+            .inverse()
+            .is_some());
+    }
+
+    /// Compute `Ax` for a vector `x`.
+    fn apply(a: &ModMatrix<i64, u64>, x: &[i64]) -> Vec<i64> {
+        let column = ModMatrix::new(x.len(), 1, x.to_vec(), a.modulus());
+        let product = a * &column;
+        (0..a.rows()).map(|i| product[(i, 0)]).collect()
+    }
+
+    #[test]
+    fn test_solve_mod_unique() {
+        let a = ModMatrix::new(3, 3, vec![0i64, 2, 1, 1, 1, 1, 2, 1, -1], 13u64);
+        assert_eq!(solve_mod(&a, &[7, 6, 1]), Solution::Unique(vec![1, 2, 3]));
+        let mut rng = SplitMix64::new(319);
+        let modulus = 1_000_000_007u64;
+        for n in 1..=6 {
+            let data = (0..n * n)
+                .map(|_| (rng.next_u64() % modulus) as i64)
+                .collect();
+            let a = ModMatrix::new(n, n, data, modulus);
+            let x: Vec<i64> = (0..n).map(|_| (rng.next_u64() % modulus) as i64).collect();
+            assert_eq!(solve_mod(&a, &apply(&a, &x)), Solution::Unique(x));
+        }
+    }
+
+    #[test]
+    fn test_solve_mod_inconsistent() {
+        let a = ModMatrix::new(2, 2, vec![1i64, 2, 2, 4], 7u64);
+        assert_eq!(solve_mod(&a, &[1, 3]), Solution::Inconsistent);
+        // Overdetermined: three equations in two unknowns.
+        let a = ModMatrix::new(3, 2, vec![1i64, 0, 0, 1, 1, 1], 7u64);
+        assert_eq!(solve_mod(&a, &[1, 2, 4]), Solution::Inconsistent);
+        assert_eq!(solve_mod(&a, &[1, 2, 3]), Solution::Unique(vec![1, 2]));
+    }
+
+    #[test]
+    fn test_solve_mod_parametric() {
+        let modulus = 11u64;
+        // Underdetermined: two equations in four unknowns.
+        let a = ModMatrix::new(2, 4, vec![1i64, 2, 0, 3, 2, 4, 1, 1], modulus);
+        let b = [4, 5];
+        let Solution::Parametric {
+            particular,
+            null_space,
+        } = solve_mod(&a, &b)
+        else {
+            panic!("expected a parametric solution");
+        };
+        assert_eq!(null_space.len(), 2);
+        assert_eq!(apply(&a, &particular), b);
+        for vector in &null_space {
+            assert_eq!(apply(&a, vector), [0, 0]);
+        }
+        let combination: Vec<i64> = (0..4)
+            .map(|i| particular[i] + 3 * null_space[0][i] + 7 * null_space[1][i])
+            .collect();
+        assert_eq!(apply(&a, &combination), b);
+        let zero = ModMatrix::<i64, u64>::zero(2, 3, modulus);
+        assert_eq!(
+            solve_mod(&zero, &[0, 0]),
+            Solution::Parametric {
+                particular: vec![0; 3],
+                null_space: vec![vec![1, 0, 0], vec![0, 1, 0], vec![0, 0, 1]],
+            }
+        );
+    }
+}
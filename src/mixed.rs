@@ -0,0 +1,120 @@
+use crate::{AddMod, EqMod, Modulus, MulMod, SubMod};
+
+/// Implement the arithmetic and comparison traits for right-hand sides that convert to the left-hand type without loss.
+/// The right-hand side is widened, with sign extension, before the operation.
+/// The left-hand types are limited to `i32`, `i64` and `i128`: an unsuffixed literal on the right falls back
+/// to `i32` when several right-hand types are possible, and that fallback must remain valid
+/// so that calls such as `x.add_mod(1, m)` keep compiling. The output type is then only known after the fallback,
+/// so comparing it directly with another unsuffixed literal needs a suffix on one of them.
+macro_rules! widening_rhs {
+    ($($t:ty: $($r:ty),*);*) => {$($(
+        impl<M> AddMod<M, $r> for $t
+        where
+            $t: TryFrom<M>,
+            M: Modulus<$t>,
+        {
+            type Output = $t;
+            fn add_mod(self, rhs: $r, modulus: M) -> $t {
+                self.add_mod(<$t>::from(rhs), modulus)
+            }
+        }
+
+        impl<M> SubMod<M, $r> for $t
+        where
+            $t: TryFrom<M>,
+            M: Modulus<$t>,
+        {
+            type Output = $t;
+            fn sub_mod(self, rhs: $r, modulus: M) -> $t {
+                self.sub_mod(<$t>::from(rhs), modulus)
+            }
+        }
+
+        impl<M> MulMod<M, $r> for $t
+        where
+            $t: TryFrom<M>,
+            M: Modulus<$t>,
+        {
+            type Output = $t;
+            fn mul_mod(self, rhs: $r, modulus: M) -> $t {
+                self.mul_mod(<$t>::from(rhs), modulus)
+            }
+        }
+
+        impl<M> EqMod<M, $r> for $t
+        where
+            $t: TryFrom<M>,
+            M: Modulus<$t>,
+        {
+            fn eq_mod(self, rhs: $r, modulus: M) -> bool {
+                self.eq_mod(<$t>::from(rhs), modulus)
+            }
+            fn ne_mod(self, rhs: $r, modulus: M) -> bool {
+                self.ne_mod(<$t>::from(rhs), modulus)
+            }
+        }
+    )*)*};
+}
+
+widening_rhs!(
+    i32: i8, i16, u8, u16;
+    i64: i8, i16, i32, u8, u16, u32;
+    i128: i8, i16, i32, i64, u8, u16, u32, u64
+);
+
+/// A signed integer and the unsigned integer of the same width do not convert into each other,
+/// but a congruence between them has no output to fit, so both are widened to a common type.
+macro_rules! widening_eq {
+    ($($t:ty, $r:ty => $w:ty);*) => {$(
+        impl<M> EqMod<M, $r> for $t
+        where
+            $t: TryFrom<M>,
+            $w: TryFrom<M>,
+            M: Modulus<$t> + Modulus<$w>,
+        {
+            fn eq_mod(self, rhs: $r, modulus: M) -> bool {
+                <$w>::from(self).eq_mod(<$w>::from(rhs), modulus)
+            }
+            fn ne_mod(self, rhs: $r, modulus: M) -> bool {
+                <$w>::from(self).ne_mod(<$w>::from(rhs), modulus)
+            }
+        }
+    )*};
+}
+
+widening_eq!(i32, u32 => i64; i64, u64 => i128);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_mixed_arithmetic() {
+        assert_eq!(5i64.add_mod(3i32, 7u8), 1);
+        assert_eq!(5i64.add_mod(-3i32, 7u8), 2);
+        assert_eq!(5i64.sub_mod(-3i8, 7u8), 1);
+        assert_eq!(5i32.mul_mod(-1i16, 7u8), 2);
+        assert_eq!(5i64.mul_mod(u32::MAX, 7u32), 5 * (u32::MAX as i64 % 7) % 7);
+        assert_eq!((-1i128).add_mod(u64::MAX, 1_000_000_007u64), 582_344_006);
+        assert_eq!(
+            5i128.sub_mod(i64::MIN, 7u64),
+            (5 - i64::MIN as i128).rem_euclid(7)
+        );
+        // An unsuffixed literal on the right falls back to `i32`.
+        let product: i64 = 5i64.mul_mod(3, 7u8);
+        assert_eq!(product, 1);
+        assert_eq!(5i32.add_mod(3, 7u8), 1i32);
+    }
+
+    #[test]
+    fn test_mixed_eq() {
+        assert!(6i64.eq_mod(-1i32, 7u8));
+        assert!((-1i64).eq_mod(6u8, 7u8));
+        assert!((-1i32).eq_mod(u32::MAX, 1u32 << 31));
+        assert!((-1i32).ne_mod(u32::MAX, 7u32));
+        assert!(i64::MIN.eq_mod(1u64 << 63, 1u64 << 40));
+        assert!(i64::MIN.ne_mod(u64::MAX, 3u64));
+        assert!((-1i64).eq_mod(u64::MAX - 1, u64::MAX));
+    }
+}
@@ -0,0 +1,528 @@
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+use std::str::FromStr;
+
+use num_traits::{Inv, Num, One, Pow, Zero};
+
+use crate::residue::{parse_integer, split_congruence};
+use crate::{Constrain, Invert, ParseResidueError};
+
+/// An integer modulo a modulus `M` fixed at compile time, stored in canonical form in `[0, M)`.
+/// Every modulus of up to 64 bits is supported. Below `2^32`, products fit in `u64` and are reduced
+/// with a Barrett constant computed at compile time; larger moduli multiply and reduce in `u128`.
+/// Using a zero modulus fails to compile.
+/// Congruent values are equal and hash identically, and residues are ordered by canonical value.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ModInt<const M: u64> {
+    value: u64,
+}
+
+/// Residues modulo the NTT-friendly prime `998244353 = 119 · 2^23 + 1`.
+pub type Mint998 = ModInt<998_244_353>;
+
+/// Residues modulo the prime `10^9 + 7`.
+pub type Mint1e9 = ModInt<1_000_000_007>;
+
+impl<const M: u64> ModInt<M> {
+    /// The modulus.
+    pub const MODULUS: u64 = {
+        assert!(M != 0, "the modulus must be nonzero");
+        M
+    };
+
+    /// `⌊(2^64 - 1) / M⌋`, for Barrett reduction of products modulo moduli below `2^32`.
+    const BARRETT: u64 = u64::MAX / Self::MODULUS;
+
+    /// Create a residue, constraining the value to the modulus.
+    pub fn new(value: u64) -> Self {
+        Self {
+            value: value % Self::MODULUS,
+        }
+    }
+
+    /// The canonical value in `[0, M)`.
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// The modulus.
+    pub fn modulus(&self) -> u64 {
+        Self::MODULUS
+    }
+
+    /// The representative in the symmetric range `(-M/2, M/2]`, as `constrain_symmetric`.
+    /// It fits in `i64` for every modulus.
+    pub fn to_symmetric(&self) -> i64 {
+        if self.value <= M / 2 {
+            self.value as i64
+        } else {
+            -((M - self.value) as i64)
+        }
+    }
+
+    #[cfg(feature = "zeroize")]
+    pub(crate) fn value_mut(&mut self) -> &mut u64 {
+        &mut self.value
+    }
+
+    /// Raise the residue to a non-negative power.
+    pub fn pow(self, exponent: u64) -> Self {
+        let mut result = Self::new(1);
+        for i in (0..u64::BITS - exponent.leading_zeros()).rev() {
+            result *= result;
+            if (exponent >> i) & 1 == 1 {
+                result *= self;
+            }
+        }
+        result
+    }
+
+    /// The inverse of the residue, or `None` if it is not a unit.
+    pub fn inverse(self) -> Option<Self> {
+        (self.value as i128)
+            .invert(M)
+            .map(|v| Self { value: v as u64 })
+    }
+
+    /// Divide by another residue, or return `None` if the divisor is not a unit.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        rhs.inverse().map(|inverse| self * inverse)
+    }
+}
+
+impl<const M: u64> fmt::Display for ModInt<M> {
+    /// Format the canonical value, or `a (mod m)` with the alternate flag.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "{} (mod {M})", self.value)
+        } else {
+            fmt::Display::fmt(&self.value, f)
+        }
+    }
+}
+
+impl<const M: u64> fmt::Debug for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ModInt")
+            .field("value", &self.value)
+            .field("modulus", &M)
+            .finish()
+    }
+}
+
+impl<const M: u64> FromStr for ModInt<M> {
+    type Err = ParseResidueError;
+
+    /// Parse `a`, `a mod m` or `a (mod m)`, constraining the value, which may be negative.
+    /// A modulus in the text must equal `M`.
+    fn from_str(s: &str) -> Result<Self, ParseResidueError> {
+        let (value, modulus) = split_congruence(s)?;
+        if let Some(modulus) = modulus {
+            if parse_integer::<u64>(modulus)? != M {
+                return Err(ParseResidueError::ModulusMismatch);
+            }
+        }
+        let value: i128 = parse_integer(value)?;
+        Ok(Self {
+            value: value.constrain(Self::MODULUS) as u64,
+        })
+    }
+}
+
+macro_rules! from_unsigned {
+    ($($t:ty),*) => {$(
+        impl<const M: u64> From<$t> for ModInt<M> {
+            fn from(value: $t) -> Self {
+                Self::new(value as u64)
+            }
+        }
+    )*};
+}
+
+macro_rules! from_signed {
+    ($($t:ty),*) => {$(
+        impl<const M: u64> From<$t> for ModInt<M> {
+            fn from(value: $t) -> Self {
+                Self {
+                    value: (value as i128).constrain(ModInt::<M>::MODULUS) as u64,
+                }
+            }
+        }
+    )*};
+}
+
+from_unsigned!(u8, u16, u32, u64, usize);
+from_signed!(i8, i16, i32, i64, isize);
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        // Both values are below `M`, so a sum that overflows or reaches `M` exceeds it by less than `M`.
+        let (sum, overflow) = self.value.overflowing_add(rhs.value);
+        Self {
+            value: if overflow || sum >= M {
+                sum.wrapping_sub(M)
+            } else {
+                sum
+            },
+        }
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        if M >= 1 << 32 {
+            return Self {
+                value: ((self.value as u128 * rhs.value as u128) % M as u128) as u64,
+            };
+        }
+        // The product is below `M^2 < 2^64`. The estimated quotient is at most one short, because the constant
+        // is at least `2^64 / M - 1`, so one subtraction completes the reduction.
+        let product = self.value * rhs.value;
+        let quotient = ((product as u128 * Self::BARRETT as u128) >> 64) as u64;
+        let remainder = product - quotient * M;
+        Self {
+            value: if remainder >= M {
+                remainder - M
+            } else {
+                remainder
+            },
+        }
+    }
+}
+
+impl<const M: u64> Div for ModInt<M> {
+    type Output = Self;
+
+    /// Panics if the divisor is not a unit; see `checked_div`.
+    fn div(self, rhs: Self) -> Self {
+        self.checked_div(rhs)
+            .expect("the divisor must be invertible")
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        match self.value {
+            0 => self,
+            v => Self { value: M - v },
+        }
+    }
+}
+
+impl<const M: u64> Rem for ModInt<M> {
+    type Output = Self;
+
+    /// Every unit divides every residue exactly, so the remainder is zero, as `Num` requires of a field.
+    /// Panics if the divisor is not a unit, like `Div`.
+    fn rem(self, rhs: Self) -> Self {
+        rhs.inverse().expect("the divisor must be invertible");
+        Self::new(0)
+    }
+}
+
+impl<const M: u64> Inv for ModInt<M> {
+    type Output = Self;
+
+    /// Panics if the residue is not a unit; see `inverse`.
+    fn inv(self) -> Self {
+        self.inverse().expect("the residue must be invertible")
+    }
+}
+
+impl<const M: u64> Pow<u64> for ModInt<M> {
+    type Output = Self;
+
+    fn pow(self, exponent: u64) -> Self {
+        ModInt::pow(self, exponent)
+    }
+}
+
+impl<const M: u64> Zero for ModInt<M> {
+    fn zero() -> Self {
+        Self::new(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+}
+
+impl<const M: u64> One for ModInt<M> {
+    fn one() -> Self {
+        Self::new(1)
+    }
+}
+
+impl<const M: u64> Num for ModInt<M> {
+    type FromStrRadixErr = ParseResidueError;
+
+    /// Parse an integer, which may be negative, in the given radix and constrain it.
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseResidueError> {
+        let value =
+            i128::from_str_radix(s, radix).map_err(|_| ParseResidueError::InvalidInteger)?;
+        Ok(Self {
+            value: value.constrain(Self::MODULUS) as u64,
+        })
+    }
+}
+
+impl<const M: u64> Sum for ModInt<M> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), Add::add)
+    }
+}
+
+impl<'a, const M: u64> Sum<&'a ModInt<M>> for ModInt<M> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl<const M: u64> Product for ModInt<M> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), Mul::mul)
+    }
+}
+
+impl<'a, const M: u64> Product<&'a ModInt<M>> for ModInt<M> {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().product()
+    }
+}
+
+macro_rules! assign {
+    ($($trait:ident $method:ident $op:ident),*) => {$(
+        impl<const M: u64> $trait for ModInt<M> {
+            fn $method(&mut self, rhs: Self) {
+                *self = self.$op(rhs);
+            }
+        }
+    )*};
+}
+
+assign!(
+    AddAssign add_assign add,
+    SubAssign sub_assign sub,
+    MulAssign mul_assign mul,
+    DivAssign div_assign div
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MulMod, PowMod, SplitMix64};
+    use pretty_assertions::assert_eq;
+    use std::collections::HashSet;
+
+    type Mint = ModInt<998_244_353>;
+
+    #[test]
+    fn test_modint_new() {
+        assert_eq!(Mint::new(998_244_354).value(), 1);
+        assert_eq!(Mint::from(-1i32).value(), 998_244_352);
+        assert_eq!(
+            Mint::from(i64::MIN),
+            Mint::new(i64::MIN.constrain(998_244_353u64) as u64)
+        );
+        assert_eq!(Mint::from(7u8).modulus(), 998_244_353);
+        assert_eq!(ModInt::<1>::from(5u32).value(), 0);
+        assert_eq!(ModInt::<7>::MODULUS, 7);
+    }
+
+    #[test]
+    fn test_modint_to_symmetric() {
+        assert_eq!(ModInt::<7>::new(6).to_symmetric(), -1);
+        assert_eq!(ModInt::<7>::new(3).to_symmetric(), 3);
+        assert_eq!(ModInt::<8>::new(4).to_symmetric(), 4);
+        assert_eq!(Mint::from(-5i32).to_symmetric(), -5);
+        assert_eq!(
+            ModInt::<{ u64::MAX }>::new(1 << 63).to_symmetric(),
+            -i64::MAX
+        );
+        assert_eq!(
+            ModInt::<{ u64::MAX }>::new((1 << 63) - 1).to_symmetric(),
+            i64::MAX
+        );
+    }
+
+    #[test]
+    fn test_modint_format() {
+        let a = Mint::from(-2i32);
+        assert_eq!(a.to_string(), "998244351");
+        assert_eq!(format!("{a:#}"), "998244351 (mod 998244353)");
+        assert_eq!(
+            format!("{a:?}"),
+            "ModInt { value: 998244351, modulus: 998244353 }"
+        );
+        assert_eq!(format!("{:#}", ModInt::<7>::new(0)), "0 (mod 7)");
+        assert_eq!(format!("{:#}", ModInt::<1>::new(5)), "0 (mod 1)");
+        assert_eq!(format!("{:03}", ModInt::<7>::new(12)), "005");
+    }
+
+    #[test]
+    fn test_modint_from_str() {
+        assert_eq!("17".parse(), Ok(ModInt::<23>::new(17)));
+        assert_eq!(" -5 (mod 7) ".parse(), Ok(ModInt::<7>::new(2)));
+        assert_eq!("40 mod 23".parse(), Ok(ModInt::<23>::new(17)));
+        assert_eq!(
+            "17 mod 22".parse::<ModInt<23>>(),
+            Err(ParseResidueError::ModulusMismatch)
+        );
+        assert_eq!(
+            "17 mod".parse::<ModInt<23>>(),
+            Err(ParseResidueError::MissingModulus)
+        );
+        assert_eq!(
+            "1 7".parse::<ModInt<23>>(),
+            Err(ParseResidueError::InvalidInteger)
+        );
+        for a in [Mint::new(0), Mint::from(-1i32), Mint::new(12345)] {
+            assert_eq!(a.to_string().parse(), Ok(a));
+            assert_eq!(format!("{a:#}").parse(), Ok(a));
+        }
+    }
+
+    #[test]
+    fn test_modint_hash_ord() {
+        let set: HashSet<_> = [ModInt::<7>::from(-3i32), ModInt::new(4)].into();
+        assert_eq!(set.len(), 1);
+        assert!(ModInt::<7>::from(-1i32) > ModInt::new(5));
+        assert_eq!(ModInt::<7>::new(8).max(ModInt::new(3)), ModInt::new(3));
+    }
+
+    #[test]
+    fn test_modint_arithmetic() {
+        let mut rng = SplitMix64::new(339);
+        type Big = ModInt<{ u64::MAX - 58 }>;
+        let m = u64::MAX - 58;
+        for _ in 0..1000 {
+            let (x, y) = (rng.next_u64() % m, rng.next_u64() % m);
+            let (a, b) = (Big::new(x), Big::new(y));
+            assert_eq!((a + b).value() as u128, (x as u128 + y as u128) % m as u128);
+            assert_eq!((a - b + b), a);
+            assert_eq!((a * b).value() as u128, (x as u128).mul_mod(y as u128, m));
+            assert_eq!((-a + a).value(), 0);
+            if y != 0 {
+                assert_eq!(a / b * b, a);
+            }
+        }
+    }
+
+    #[test]
+    fn test_modint_pow_inverse() {
+        let a = Mint::new(3);
+        assert_eq!(a.pow(100).value(), 3u64.pow_mod(100, 998_244_353u64));
+        assert_eq!(a.pow(0), Mint::new(1));
+        assert_eq!(a * a.inverse().unwrap(), Mint::new(1));
+        assert_eq!(Mint::new(0).inverse(), None);
+        let (b, c) = (ModInt::<12>::new(4), ModInt::<12>::new(8));
+        assert_eq!(b.checked_div(c), None);
+        assert_eq!(c.checked_div(ModInt::new(5)), Some(ModInt::new(4)));
+        let mut d = a;
+        d += a;
+        d *= a;
+        d -= Mint::new(1);
+        d /= a;
+        assert_eq!(d, (a + a) * a / a - Mint::new(1) / a);
+    }
+
+    fn total<N: Num + Copy>(values: &[N]) -> N {
+        values.iter().fold(N::zero(), |sum, &x| sum + x)
+    }
+
+    #[test]
+    fn test_modint_num() {
+        let values: Vec<ModInt<7>> = (1..=10u32).map(ModInt::from).collect();
+        assert_eq!(total(&values), ModInt::new(55));
+        assert_eq!(values.iter().sum::<ModInt<7>>(), ModInt::new(55));
+        assert_eq!(
+            values.iter().copied().product::<ModInt<7>>(),
+            ModInt::new(0)
+        );
+        assert_eq!(values[..6].iter().product::<ModInt<7>>(), ModInt::new(6));
+        assert_eq!(ModInt::<7>::from_str_radix("-1010", 2), Ok(ModInt::new(4)));
+        assert_eq!(ModInt::<7>::from_str_radix("zz", 36), Ok(ModInt::new(1295)));
+        assert_eq!(
+            ModInt::<7>::from_str_radix("12", 2),
+            Err(ParseResidueError::InvalidInteger)
+        );
+        assert!(ModInt::<7>::zero().is_zero() && ModInt::<7>::one().is_one());
+        assert_eq!(ModInt::<7>::new(5) % ModInt::new(3), ModInt::zero());
+    }
+
+    #[test]
+    fn test_modint_pow_inv() {
+        let mut rng = SplitMix64::new(342);
+        for _ in 0..100 {
+            let (base, exponent) = (rng.next_u64(), rng.next_u64());
+            let expected = (base as u128).pow_mod(exponent as u128, 998_244_353u128) as u64;
+            assert_eq!(Pow::pow(Mint::new(base), exponent).value(), expected);
+        }
+        assert_eq!(Mint::new(3).inv() * Mint::new(3), Mint::one());
+    }
+
+    fn check_arithmetic<const M: u64>(rng: &mut SplitMix64) {
+        let check = |a: u64, b: u64| {
+            let (x, y) = (ModInt::<M>::new(a), ModInt::<M>::new(b));
+            let (a, b) = (x.value() as u128, y.value() as u128);
+            let m = M as u128;
+            assert_eq!((x * y).value() as u128, a * b % m, "{a} * {b} mod {M}");
+            assert_eq!((x + y).value() as u128, (a + b) % m, "{a} + {b} mod {M}");
+            assert_eq!(
+                (x - y).value() as u128,
+                (a + m - b) % m,
+                "{a} - {b} mod {M}"
+            );
+        };
+        for _ in 0..10_000 {
+            check(rng.next_u64(), rng.next_u64());
+        }
+        let top = M.wrapping_sub(1);
+        for (a, b) in [(top, top), (top, 1), (0, top), (M / 2, M / 2 + 1)] {
+            check(a, b);
+        }
+    }
+
+    #[test]
+    fn test_modint_matches_u128() {
+        let mut rng = SplitMix64::new(369);
+        check_arithmetic::<998_244_353>(&mut rng);
+        check_arithmetic::<1_000_000_007>(&mut rng);
+        check_arithmetic::<1>(&mut rng);
+        check_arithmetic::<2>(&mut rng);
+        check_arithmetic::<{ 1 << 31 }>(&mut rng);
+        check_arithmetic::<{ (1 << 32) - 1 }>(&mut rng);
+        check_arithmetic::<{ 1 << 32 }>(&mut rng);
+        check_arithmetic::<{ (1 << 61) - 1 }>(&mut rng);
+        check_arithmetic::<{ u64::MAX }>(&mut rng);
+        assert_eq!(Mint998::new(2).pow(23).inv().value(), 998_244_234);
+        assert_eq!((Mint1e9::new(500_000_004) * Mint1e9::new(2)).value(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "the residue must be invertible")]
+    fn test_modint_inv_zero() {
+        Mint::zero().inv();
+    }
+
+    #[test]
+    #[should_panic(expected = "the divisor must be invertible")]
+    fn test_modint_div_by_zero() {
+        let _ = Mint::new(1) / Mint::new(0);
+    }
+}
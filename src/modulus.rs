@@ -1,26 +1,278 @@
-use num_traits::Unsigned;
+use std::ops::{Add, Div, Mul, Rem, Sub};
 
-use crate::Integer;
+use num_traits::{FromPrimitive, Num, One, Signed, ToPrimitive, Unsigned, Zero};
+
+use crate::prime::is_probable_prime_u128;
+use crate::{DivMod, Integer, Invert, ModError};
 
 /// Modulus is an unsigned integer that can be cast to some other type `T`.
 /// The purpose is to restrict the modulus to unsigned integers yet allow calculations with signed integers
 /// when necessary.
-pub trait Modulus<T>: Integer + Unsigned
-where
-    T: TryFrom<Self>,
-    <T as TryFrom<Self>>::Error: std::fmt::Debug,
-{
+/// It is implemented for every unsigned integer type that converts to `T` with `TryFrom`, whatever the conversion error,
+/// so generic code needs no bound on that error.
+pub trait Modulus<T>: Integer + Unsigned {
     /// Cast the modulus to some other type `T`.
     /// Panics if the modulus cannot be converted to `T`.
-    fn cast(self) -> T {
-        self.try_into().expect("cannot convert modulus")
-    }
+    fn cast(self) -> T;
+
+    /// Cast the modulus to some other type `T`, or fail with `ModError::ModulusTooLarge` if it does not fit.
+    fn try_cast(self) -> Result<T, ModError>;
 }
 
 impl<T, M> Modulus<T> for M
 where
     T: TryFrom<M>,
-    <T as TryFrom<M>>::Error: std::fmt::Debug,
     M: Integer + Unsigned,
 {
+    fn cast(self) -> T {
+        self.try_cast().expect("cannot convert modulus")
+    }
+
+    fn try_cast(self) -> Result<T, ModError> {
+        T::try_from(self).map_err(|_| ModError::ModulusTooLarge)
+    }
+}
+
+/// A modulus that is known to be prime, for the operations that are only correct or only fast modulo a prime.
+pub trait PrimeModulus<T>: Modulus<T> {}
+
+/// A modulus verified to be prime, usable anywhere a `Modulus` is.
+/// Its arithmetic operators exist only because `Modulus` requires them; they act on the inner value
+/// and their results are not checked for primality.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Prime<M>(M);
+
+impl<M: Integer + Unsigned + ToPrimitive> Prime<M> {
+    /// Verify that the modulus is prime with the Miller–Rabin test, which is deterministic up to 64 bits
+    /// and runs 32 rounds with pseudo-random bases up to 128 bits, so a composite passes with probability at most `4^-32`.
+    /// Returns `None` if it is not prime, or if it does not fit in 128 bits and so cannot be verified.
+    pub fn new(m: M) -> Option<Self> {
+        m.to_u128()
+            .is_some_and(|m| is_probable_prime_u128(m, 32))
+            .then_some(Self(m))
+    }
+}
+
+impl<M> Prime<M> {
+    /// Wrap a modulus that the caller has already verified to be prime.
+    /// The operations that rely on primality give meaningless results if it is not.
+    pub const fn new_unchecked(m: M) -> Self {
+        Self(m)
+    }
+
+    /// The modulus.
+    pub fn get(self) -> M {
+        self.0
+    }
+
+    /// Invert an integer with Fermat's little theorem, skipping the extended Euclidean algorithm of `Invert::invert`.
+    /// Returns `None` only when the integer is congruent to zero.
+    pub fn invert<T>(self, a: T) -> Option<T>
+    where
+        T: Invert + TryFrom<M> + FromPrimitive,
+        M: Modulus<T>,
+    {
+        a.invert_prime(self.0)
+    }
+
+    /// Divide two integers, inverting the divisor with Fermat's little theorem as `DivMod::div_mod_prime`.
+    /// Returns `None` only when the divisor is congruent to zero.
+    pub fn div_mod<T>(self, a: T, b: T) -> Option<T>
+    where
+        T: Invert + TryFrom<M> + FromPrimitive,
+        M: Modulus<T>,
+    {
+        a.div_mod_prime(b, self.0)
+    }
+}
+
+impl<T, M> PrimeModulus<T> for Prime<M> where Prime<M>: Modulus<T> {}
+
+/// A positive modulus of a signed type, validated once so that moduli parsed as signed integers
+/// can be used anywhere a `Modulus` is without a cast at every call site.
+/// Like `Prime`, its arithmetic operators exist only because `Modulus` requires them and act on the inner value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SignedModulus<S>(S);
+
+impl<S: Integer + Signed> SignedModulus<S> {
+    /// Validate a signed modulus, failing with `ModError::ZeroModulus` or `ModError::NegativeModulus`.
+    pub fn new(m: S) -> Result<Self, ModError> {
+        if m.is_zero() {
+            Err(ModError::ZeroModulus)
+        } else if m.is_negative() {
+            Err(ModError::NegativeModulus)
+        } else {
+            Ok(Self(m))
+        }
+    }
+}
+
+impl<S> SignedModulus<S> {
+    /// The modulus.
+    pub fn get(self) -> S {
+        self.0
+    }
+}
+
+/// Forward the operations that `Modulus` requires from a wrapper to the inner value,
+/// and convert the wrapper to every primitive integer that the inner value converts to.
+macro_rules! modulus_wrapper {
+    ($wrapper:ident) => {
+        modulus_wrapper!(@op $wrapper, Add add, Sub sub, Mul mul, Div div, Rem rem);
+
+        impl<M: Zero> Zero for $wrapper<M> {
+            fn zero() -> Self {
+                Self(M::zero())
+            }
+
+            fn is_zero(&self) -> bool {
+                self.0.is_zero()
+            }
+        }
+
+        impl<M: One + PartialEq> One for $wrapper<M> {
+            fn one() -> Self {
+                Self(M::one())
+            }
+        }
+
+        impl<M: Num> Num for $wrapper<M> {
+            type FromStrRadixErr = M::FromStrRadixErr;
+
+            fn from_str_radix(s: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+                M::from_str_radix(s, radix).map(Self)
+            }
+        }
+
+        modulus_wrapper!(@try_from $wrapper, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+    };
+    (@op $wrapper:ident, $($trait:ident $method:ident),*) => {$(
+        impl<M: $trait<Output = M>> $trait for $wrapper<M> {
+            type Output = Self;
+
+            fn $method(self, rhs: Self) -> Self {
+                Self(self.0.$method(rhs.0))
+            }
+        }
+    )*};
+    (@try_from $wrapper:ident, $($t:ty),*) => {$(
+        impl<M> TryFrom<$wrapper<M>> for $t
+        where
+            $t: TryFrom<M>,
+        {
+            type Error = <$t as TryFrom<M>>::Error;
+
+            fn try_from(m: $wrapper<M>) -> Result<$t, Self::Error> {
+                <$t>::try_from(m.0)
+            }
+        }
+    )*};
+}
+
+modulus_wrapper!(Prime);
+modulus_wrapper!(SignedModulus);
+
+impl<M: Unsigned> Unsigned for Prime<M> {}
+
+/// The inner value is signed, but it was verified to be positive.
+impl<S: Num> Unsigned for SignedModulus<S> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AddMod, Constrain, DivMod, EqMod, Invert, MulMod, PowMod, SqrtMod, SubMod};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_prime_new() {
+        assert_eq!(
+            Prime::new(1_000_000_007u64).map(Prime::get),
+            Some(1_000_000_007)
+        );
+        assert_eq!(Prime::new(2u8).map(Prime::get), Some(2));
+        for composite in [0u64, 1, 4, 91, 1_000_000_008, u64::MAX] {
+            assert_eq!(Prime::new(composite), None, "{composite}");
+        }
+        for carmichael in [561u64, 1105, 41_041, 825_265, 321_197_185] {
+            assert_eq!(Prime::new(carmichael), None, "{carmichael}");
+        }
+        assert_eq!(Prime::new(u64::MAX as u128 + 2), None);
+        let p = (1u128 << 127) - 1;
+        assert_eq!(Prime::new(p).map(Prime::get), Some(p));
+        assert_eq!(
+            Prime::new(u128::MAX - 158).map(Prime::get),
+            Some(u128::MAX - 158)
+        );
+        assert_eq!(
+            Prime::new(18_446_744_073_709_551_557u128 * 1_000_000_007),
+            None
+        );
+        assert_eq!(Prime::new_unchecked(u128::MAX).get(), u128::MAX);
+    }
+
+    #[test]
+    fn test_prime_as_modulus() {
+        let p = Prime::new(13u32).unwrap();
+        assert_eq!(7i64.add_mod(9i64, p), 3);
+        assert_eq!(5u64.mul_mod(8, p), 1);
+        assert_eq!(2i32.pow_mod(12, p), 1);
+        assert_eq!((-3i64).invert(p), Some(4));
+        assert_eq!(10i64.sqrt_mod(p), Some(6));
+        assert_eq!(7i64.div_mod(3, p), 7i64.div_mod(3, 13u32));
+    }
+
+    #[test]
+    fn test_prime_fast_paths() {
+        for m in [2u64, 3, 13, 65_537, 1_000_000_007] {
+            let p = Prime::new(m).unwrap();
+            for a in -50i64..50 {
+                assert_eq!(p.invert(a), a.invert(m), "a = {a}, m = {m}");
+                assert_eq!(p.div_mod(a, 7), a.div_mod(7, m), "a = {a}, m = {m}");
+            }
+        }
+    }
+
+    fn requires_prime<P>(a: i64, p: P) -> i64
+    where
+        i64: TryFrom<P>,
+        P: PrimeModulus<i64>,
+    {
+        a.pow_mod(p.cast() - 1, p)
+    }
+
+    // No bound on the conversion error is needed for a generic integer type.
+    fn invert_generic<T, P>(a: T, p: P) -> Option<T>
+    where
+        T: Invert + TryFrom<P>,
+        P: PrimeModulus<T>,
+    {
+        a.invert(p)
+    }
+
+    #[test]
+    fn test_prime_modulus_marker() {
+        let p = Prime::new(101u16).unwrap();
+        assert_eq!(requires_prime(5, p), 1);
+        assert_eq!(invert_generic(3i32, p), Some(34));
+        assert_eq!(invert_generic(-3i8, Prime::new(127u8).unwrap()), Some(42));
+    }
+
+    #[test]
+    fn test_signed_modulus() {
+        assert_eq!(SignedModulus::new(0i64), Err(ModError::ZeroModulus));
+        assert_eq!(SignedModulus::new(-7i64), Err(ModError::NegativeModulus));
+        assert_eq!(SignedModulus::new(i8::MIN), Err(ModError::NegativeModulus));
+        assert_eq!(ModError::NegativeModulus.to_string(), "modulus is negative");
+        let m = SignedModulus::new(7i64).unwrap();
+        assert_eq!(m.get(), 7);
+        assert_eq!((-3i64).constrain(m), 4);
+        assert_eq!(5i64.add_mod(4i64, m), 2);
+        assert_eq!(2i32.sub_mod(5, m), 4);
+        assert_eq!(3i64.div_mod(5, m), Some(2));
+        assert_eq!((-2i128).invert(m), Some(3));
+        assert_eq!(10u64.mul_mod(10, m), 2);
+        assert_eq!(3u32.pow_mod(6, m), 1);
+        assert!(13u8.eq_mod(6, m));
+        assert_eq!(9u64.constrain(m), 9u64.constrain(7u64));
+    }
 }
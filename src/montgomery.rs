@@ -0,0 +1,241 @@
+use crate::ct::{ct_select, ct_swap, Choice, CtUnsigned};
+
+/// Arithmetic in Montgomery form modulo a fixed odd modulus `m`, for `u32` and `u64`.
+/// A residue `x` is represented as `xR mod m` with `R = 2^bits`, so that products reduce with multiplications and shifts
+/// instead of a division. Products are computed in the double-width type, so `u128` is not supported.
+///
+/// The multiplication, the conversions and `pow_ct` are constant time with respect to the residues and the exponent:
+/// they contain no branches or memory accesses that depend on them, and the final conditional subtraction
+/// of the reduction is a mask select. The modulus itself is treated as public.
+/// This relies on the compiler keeping the masks branch-free, which holds for the current code generation
+/// but is not something the language guarantees.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Montgomery<T> {
+    modulus: T,
+    /// `-m⁻¹ mod R`.
+    inverse: T,
+    /// `R² mod m`, which converts into Montgomery form with a single multiplication.
+    r2: T,
+    /// `R mod m`, the Montgomery form of one.
+    one: T,
+}
+
+/// A trait to raise an unsigned integer to a secret power in constant time.
+pub trait PowModCt {
+    /// Raise an integer to a power modulo an odd modulus with a Montgomery ladder.
+    /// Every exponent takes the same sequence of operations over the full width of the type,
+    /// and neither the base nor the exponent affects branches or memory accesses.
+    /// Panics if the modulus is even.
+    fn pow_mod_ct(self, exponent: Self, modulus: Self) -> Self;
+}
+
+/// A trait to invert an unsigned integer modulo a prime in constant time.
+pub trait InvertCt: Sized {
+    /// Invert an integer modulo an odd prime `p` with Fermat's little theorem, as `a^(p-2)` through `pow_mod_ct`.
+    /// The running time depends on neither `a` nor whether it is invertible: the zero check is a mask computed
+    /// alongside the exponentiation, and only the final `Option` reveals the outcome.
+    /// The result is meaningless if `p` is not prime. Panics if `p` is even.
+    fn invert_ct(self, p: Self) -> Option<Self>;
+}
+
+#[cfg(feature = "zeroize")]
+impl<T> Montgomery<T> {
+    pub(crate) fn constants_mut(&mut self) -> [&mut T; 4] {
+        [
+            &mut self.modulus,
+            &mut self.inverse,
+            &mut self.r2,
+            &mut self.one,
+        ]
+    }
+}
+
+macro_rules! montgomery {
+    ($t:ty, $wide:ty) => {
+        impl Montgomery<$t> {
+            /// Prepare Montgomery arithmetic modulo `modulus`, or `None` if the modulus is even.
+            pub fn new(modulus: $t) -> Option<Self> {
+                if modulus % 2 == 0 {
+                    return None;
+                }
+                // Newton's iteration doubles the number of correct low bits of `m⁻¹`, starting from three.
+                let mut inverse = modulus;
+                for _ in 0..5 {
+                    inverse =
+                        inverse.wrapping_mul((2 as $t).wrapping_sub(modulus.wrapping_mul(inverse)));
+                }
+                let one = ((1 as $wide) << <$t>::BITS) % modulus as $wide;
+                let r2 = (one * one % modulus as $wide) as $t;
+                Some(Self {
+                    modulus,
+                    inverse: inverse.wrapping_neg(),
+                    r2,
+                    one: one as $t,
+                })
+            }
+
+            /// The modulus.
+            pub fn modulus(&self) -> $t {
+                self.modulus
+            }
+
+            /// Convert an integer into Montgomery form, reducing it modulo `m`.
+            pub fn to_montgomery(&self, x: $t) -> $t {
+                self.reduce(x as $wide * self.r2 as $wide)
+            }
+
+            /// Convert a residue out of Montgomery form.
+            pub fn from_montgomery(&self, x: $t) -> $t {
+                self.reduce(x as $wide)
+            }
+
+            /// Multiply two residues in Montgomery form.
+            pub fn mul(&self, a: $t, b: $t) -> $t {
+                self.reduce(a as $wide * b as $wide)
+            }
+
+            /// Raise an integer, not in Montgomery form, to a power with a Montgomery ladder.
+            /// The ladder runs over every bit of the exponent and swaps its two accumulators with masks,
+            /// so the sequence of operations is the same for every base and exponent.
+            pub fn pow_ct(&self, base: $t, exponent: $t) -> $t {
+                let (mut low, mut high) = (self.one, self.to_montgomery(base));
+                for i in (0..<$t>::BITS).rev() {
+                    let bit = (exponent >> i) & 1;
+                    ct_swap(Choice::from(bit == 1), &mut low, &mut high);
+                    high = self.mul(low, high);
+                    low = self.mul(low, low);
+                    ct_swap(Choice::from(bit == 1), &mut low, &mut high);
+                }
+                self.from_montgomery(low)
+            }
+
+            /// Invert an integer, not in Montgomery form, modulo a prime modulus as `a^(m-2)`.
+            /// See `InvertCt::invert_ct`.
+            pub fn invert_ct(&self, a: $t) -> Option<$t> {
+                let inverse = self.pow_ct(a, self.modulus.wrapping_sub(2));
+                // `a` is invertible exactly when the reduced `a` and hence its inverse are nonzero.
+                let zero = inverse.ct_is_zero();
+                bool::from(!zero).then_some(inverse)
+            }
+
+            /// Montgomery reduction of `t < mR` to `tR⁻¹ mod m`.
+            fn reduce(&self, t: $wide) -> $t {
+                let q = (t as $t).wrapping_mul(self.inverse);
+                let (sum, carry) = t.overflowing_add(q as $wide * self.modulus as $wide);
+                // `sum / R` with the carry as its top bit is below `2m`; subtract `m` once if it is not below `m`.
+                let u = (sum >> <$t>::BITS) as $t;
+                let (difference, borrow) = u.overflowing_sub(self.modulus);
+                ct_select(Choice::from(carry | !borrow), difference, u)
+            }
+        }
+
+        impl PowModCt for $t {
+            fn pow_mod_ct(self, exponent: $t, modulus: $t) -> $t {
+                Montgomery::<$t>::new(modulus)
+                    .expect("the modulus must be odd")
+                    .pow_ct(self, exponent)
+            }
+        }
+
+        impl InvertCt for $t {
+            fn invert_ct(self, p: $t) -> Option<$t> {
+                Montgomery::<$t>::new(p)
+                    .expect("the modulus must be odd")
+                    .invert_ct(self)
+            }
+        }
+    };
+}
+
+montgomery!(u32, u64);
+montgomery!(u64, u128);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Invert, PowMod, SplitMix64};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_montgomery_roundtrip() {
+        for modulus in [1u64, 3, 1_000_000_007, u64::MAX - 58, u64::MAX] {
+            let context = Montgomery::<u64>::new(modulus).unwrap();
+            for x in [0, 1, 2, modulus - 1, modulus, u64::MAX] {
+                let y = context.from_montgomery(context.to_montgomery(x));
+                assert_eq!(y, x % modulus, "x = {x}, m = {modulus}");
+            }
+        }
+        assert_eq!(Montgomery::<u32>::new(10), None);
+    }
+
+    #[test]
+    fn test_montgomery_mul() {
+        let mut rng = SplitMix64::new(310);
+        for _ in 0..1000 {
+            let modulus = rng.next_u64() | 1;
+            let context = Montgomery::<u64>::new(modulus).unwrap();
+            let (a, b) = (rng.next_u64(), rng.next_u64());
+            let product = context
+                .from_montgomery(context.mul(context.to_montgomery(a), context.to_montgomery(b)));
+            assert_eq!(
+                product as u128,
+                (a as u128 % modulus as u128) * (b as u128 % modulus as u128) % modulus as u128
+            );
+        }
+    }
+
+    #[test]
+    fn test_pow_mod_ct_u64() {
+        let mut rng = SplitMix64::new(3100);
+        for modulus in [1u64, 3, 998_244_353, u64::MAX - 58, u64::MAX] {
+            for _ in 0..100 {
+                let (base, exponent) = (rng.next_u64(), rng.next_u64());
+                let expected = (base as u128).pow_mod(exponent as u128, modulus) as u64;
+                assert_eq!(base.pow_mod_ct(exponent, modulus), expected);
+            }
+            assert_eq!(7u64.pow_mod_ct(0, modulus), 1 % modulus);
+            assert_eq!(0u64.pow_mod_ct(5, modulus), 0);
+        }
+    }
+
+    #[test]
+    fn test_pow_mod_ct_u32() {
+        let mut rng = SplitMix64::new(3101);
+        for _ in 0..1000 {
+            let modulus = rng.next_u64() as u32 | 1;
+            let (base, exponent) = (rng.next_u64() as u32, rng.next_u64() as u32);
+            let expected = (base as u64).pow_mod(exponent as u64, modulus) as u32;
+            assert_eq!(base.pow_mod_ct(exponent, modulus), expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the modulus must be odd")]
+    fn test_pow_mod_ct_even() {
+        3u32.pow_mod_ct(5, 8);
+    }
+
+    #[test]
+    fn test_invert_ct_full_range() {
+        for p in [3u32, 5, 7, 13, 101, 65_537] {
+            for a in 0..2 * p {
+                let expected = (a as i64).invert(p).map(|x| x as u32);
+                assert_eq!(a.invert_ct(p), expected, "a = {a}, p = {p}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_invert_ct_u64() {
+        let mut rng = SplitMix64::new(311);
+        for p in [1_000_000_007u64, 18_446_744_073_709_551_557] {
+            for _ in 0..200 {
+                let a = rng.next_u64();
+                let expected = (a as i128).invert(p).map(|x| x as u64);
+                assert_eq!(a.invert_ct(p), expected);
+            }
+            assert_eq!(0u64.invert_ct(p), None);
+            assert_eq!(p.invert_ct(p), None);
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use std::fmt::Debug;
+
+use crate::integer::power;
+use crate::{factorize, Gcd, Integer};
+
+/// Compute Euler's totient `φ(n)`, the number of integers in `1..=n` coprime to `n`.
+/// `n` is factorized first; use `euler_phi_factored` if the factorization is already known.
+/// `φ(0)` is taken to be `0`.
+pub fn euler_phi<T>(n: T) -> T
+where
+    T: Into<u64> + TryFrom<u64>,
+    <T as TryFrom<u64>>::Error: Debug,
+{
+    let n = n.into();
+    let phi = if n == 0 {
+        0
+    } else {
+        euler_phi_factored(&factorize(n))
+    };
+    T::try_from(phi).expect("the totient does not exceed its argument")
+}
+
+/// Compute Euler's totient from the factorization `[(p, k), ...]` of `n`.
+/// This evaluates `n · Π(1 - 1/p)` in integer arithmetic, so `n` itself must fit in `T`.
+pub fn euler_phi_factored<T: Integer>(factors: &[(T, u32)]) -> T {
+    let n = factors
+        .iter()
+        .fold(T::one(), |acc, &(p, k)| acc * power(p, k));
+    factors
+        .iter()
+        .fold(n, |result, &(p, _)| result - result / p)
+}
+
+/// Compute the Carmichael function `λ(n)`, the exponent of the multiplicative group modulo `n`.
+/// It divides `φ(n)`, and `a^e ≡ a^(e mod λ(n)) (mod n)` for every `a` coprime to `n`.
+/// `n` is factorized first; use `carmichael_lambda_factored` if the factorization is already known.
+/// `λ(0)` is taken to be `0`.
+pub fn carmichael_lambda<T>(n: T) -> T
+where
+    T: Into<u64> + TryFrom<u64>,
+    <T as TryFrom<u64>>::Error: Debug,
+{
+    let n = n.into();
+    let lambda = if n == 0 {
+        0
+    } else {
+        carmichael_lambda_factored(&factorize(n))
+    };
+    T::try_from(lambda).expect("the Carmichael function does not exceed its argument")
+}
+
+/// Compute the Carmichael function from the factorization `[(p, k), ...]` of `n`.
+/// This is the lcm of `λ(p^k)`, which equals `φ(p^k)` except for `λ(2^k) = 2^(k-2)` when `k ≥ 3`.
+pub fn carmichael_lambda_factored<T: Gcd>(factors: &[(T, u32)]) -> T {
+    let two = T::one() + T::one();
+    factors
+        .iter()
+        .map(|&(p, k)| match k {
+            0 => T::one(),
+            k if p == two && k >= 3 => power(two, k - 2),
+            k => power(p, k - 1) * (p - T::one()),
+        })
+        .fold(T::one(), |acc, lambda| acc / acc.gcd(lambda) * lambda)
+}
+
+/// Compute the Möbius function `μ(n)`: `0` if a square divides `n`, and otherwise `(-1)^r` for `n` with `r` prime factors.
+/// `n` is factorized first; use `mobius_factored` if the factorization is already known,
+/// or `Sieve::mobius` to compute every value up to a limit at once.
+/// `μ(0)` is taken to be `0`.
+pub fn mobius<T: Into<u64>>(n: T) -> i8 {
+    let n = n.into();
+    if n == 0 {
+        return 0;
+    }
+    mobius_factored(&factorize(n))
+}
+
+/// Compute the Möbius function from the factorization `[(p, k), ...]` of `n`.
+/// Only the exponents are used, so the primes may be of any type.
+pub fn mobius_factored<T>(factors: &[(T, u32)]) -> i8 {
+    if factors.iter().any(|&(_, k)| k > 1) {
+        0
+    } else if factors
+        .iter()
+        .filter(|&&(_, k)| k == 1)
+        .count()
+        .is_multiple_of(2)
+    {
+        1
+    } else {
+        -1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PowMod;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_euler_phi_small() {
+        assert_eq!(euler_phi(0u32), 0);
+        assert_eq!(euler_phi(1u32), 1);
+        assert_eq!(euler_phi(36u8), 12);
+        for n in 1..500u64 {
+            let expected = (1..=n).filter(|&k| k.gcd(n) == 1).count() as u64;
+            assert_eq!(euler_phi(n), expected, "n = {n}");
+        }
+    }
+
+    #[test]
+    fn test_euler_phi_primes() {
+        for p in [2u64, 3, 1_000_000_007, 9_223_372_036_854_775_783] {
+            assert_eq!(euler_phi(p), p - 1);
+        }
+        assert_eq!(euler_phi(3u64.pow(40)), 2 * 3u64.pow(39));
+        assert_eq!(euler_phi(1u64 << 63), 1 << 62);
+        assert_eq!(
+            euler_phi(1_000_003u64.pow(3)),
+            1_000_002 * 1_000_003u64.pow(2)
+        );
+    }
+
+    #[test]
+    fn test_euler_phi_large() {
+        // 2^64 - 1 = 3 · 5 · 17 · 257 · 641 · 65537 · 6700417
+        assert_eq!(
+            euler_phi(u64::MAX),
+            2 * 4 * 16 * 256 * 640 * 65_536 * 6_700_416
+        );
+        let n = 1_000_000_007u64 * 998_244_353;
+        assert_eq!(euler_phi(n), 1_000_000_006 * 998_244_352);
+    }
+
+    #[test]
+    fn test_euler_phi_factored() {
+        assert_eq!(euler_phi_factored::<u32>(&[]), 1);
+        assert_eq!(euler_phi_factored(&[(2u32, 3), (3, 2), (5, 1)]), 96);
+        assert_eq!(euler_phi_factored(&[(7i64, 2)]), 42);
+    }
+
+    #[test]
+    fn test_mobius() {
+        assert_eq!(mobius(0u32), 0);
+        assert_eq!(mobius(1u32), 1);
+        assert_eq!(mobius(2u32), -1);
+        assert_eq!(mobius(6u32), 1);
+        assert_eq!(mobius(30u32), -1);
+        assert_eq!(mobius(12u32), 0);
+        assert_eq!(mobius(1_000_000_007u64 * 998_244_353), 1);
+        assert_eq!(mobius(u64::MAX), -1);
+        assert_eq!(mobius(1_000_000_007u64), -1);
+        assert_eq!(mobius(4 * 1_000_000_007u64), 0);
+    }
+
+    #[test]
+    fn test_mobius_factored() {
+        assert_eq!(mobius_factored::<u32>(&[]), 1);
+        assert_eq!(mobius_factored(&[(2u32, 1), (3, 1)]), 1);
+        assert_eq!(mobius_factored(&[(2u32, 1), (3, 1), (5, 1)]), -1);
+        assert_eq!(mobius_factored(&[(3u64, 2), (5, 1)]), 0);
+        // A zero exponent contributes no prime.
+        assert_eq!(mobius_factored(&[(2u64, 0), (5, 1)]), -1);
+    }
+
+    #[test]
+    fn test_mobius_divisor_sum() {
+        // The sum of μ(d) over the divisors d of n is one for n = 1 and zero otherwise.
+        for n in 1..=1000u64 {
+            let sum: i64 = (1..=n)
+                .filter(|d| n.is_multiple_of(*d))
+                .map(|d| mobius(d) as i64)
+                .sum();
+            assert_eq!(sum, (n == 1) as i64, "n = {n}");
+        }
+    }
+
+    #[test]
+    fn test_carmichael_lambda() {
+        assert_eq!(carmichael_lambda(0u32), 0);
+        assert_eq!(carmichael_lambda(1u32), 1);
+        assert_eq!(carmichael_lambda(2u32), 1);
+        assert_eq!(carmichael_lambda(4u32), 2);
+        assert_eq!(carmichael_lambda(8u32), 2);
+        assert_eq!(carmichael_lambda(1u64 << 40), 1 << 38);
+        assert_eq!(carmichael_lambda(15u32), 4);
+        assert_eq!(carmichael_lambda(561u32), 80);
+        assert_eq!(carmichael_lambda(u64::MAX), 65_536 * 5 * 52_347);
+    }
+
+    #[test]
+    fn test_carmichael_lambda_prime_powers() {
+        for p in [3u64, 5, 7, 101, 1_000_003] {
+            for k in 1..4 {
+                let n = p.pow(k);
+                assert_eq!(carmichael_lambda(n), euler_phi(n), "n = {n}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_carmichael_lambda_is_exponent() {
+        for n in 2..300u64 {
+            let lambda = carmichael_lambda(n);
+            assert_eq!(euler_phi(n) % lambda, 0);
+            let units: Vec<u64> = (1..n).filter(|&a| a.gcd(n) == 1).collect();
+            assert!(units.iter().all(|&a| a.pow_mod(lambda, n) == 1), "n = {n}");
+            // No proper divisor of λ(n) annihilates every unit.
+            for d in (1..lambda).filter(|d| lambda % d == 0) {
+                assert!(
+                    units.iter().any(|&a| a.pow_mod(d, n) != 1),
+                    "n = {n}, d = {d}"
+                );
+            }
+        }
+    }
+}
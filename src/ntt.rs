@@ -0,0 +1,353 @@
+//! The number-theoretic transform over primes of the form `c · 2^k + 1`.
+//!
+//! Such a prime has roots of unity of every order `2^j` with `j ≤ k`, so the discrete Fourier transform
+//! of a power-of-two length up to `2^k` exists modulo the prime and turns convolution into pointwise multiplication.
+//! The usual choice is `998244353 = 119 · 2^23 + 1`.
+
+use std::fmt::Debug;
+
+use crate::{
+    garner, is_prime_u64, parallel, primitive_root, AddMod, MulMod, PowMod, SubModConstrained,
+};
+
+/// NTT-friendly primes with their smallest primitive roots, which spares factoring `p - 1`.
+const KNOWN_ROOTS: [(u64, u64); 4] = [
+    (998_244_353, 3),
+    (754_974_721, 11),
+    (469_762_049, 3),
+    (167_772_161, 3),
+];
+
+/// The primes behind `convolve_mod`, all with roots of unity of order `2^24` or more.
+const CONVOLUTION_PRIMES: [u64; 3] = [167_772_161, 469_762_049, 754_974_721];
+
+/// A number-theoretic transform modulo a prime, with its primitive root precomputed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ntt {
+    modulus: u64,
+    root: u64,
+    two_adicity: u32,
+}
+
+impl Ntt {
+    /// Prepare transforms modulo `p`, which must be an odd prime below `2^62`.
+    /// The largest supported length is the largest power of two dividing `p - 1`.
+    /// Returns `None` if `p` is not such a prime.
+    pub fn new(p: u64) -> Option<Self> {
+        if !(3..1 << 62).contains(&p) || !is_prime_u64(p) {
+            return None;
+        }
+        let root = match KNOWN_ROOTS.iter().find(|&&(q, _)| q == p) {
+            Some(&(_, g)) => g,
+            None => primitive_root(p)?,
+        };
+        Some(Self {
+            modulus: p,
+            root,
+            two_adicity: (p - 1).trailing_zeros(),
+        })
+    }
+
+    /// The prime modulus.
+    pub fn modulus(&self) -> u64 {
+        self.modulus
+    }
+
+    /// The largest supported length.
+    pub fn max_len(&self) -> usize {
+        1 << self.two_adicity.min(usize::BITS - 1)
+    }
+
+    /// Transform `values` in place, reducing them modulo the prime first.
+    /// The output is in natural order: entry `i` is the polynomial with these coefficients evaluated at `ω^i`,
+    /// where `ω` is a root of unity of order `values.len()`.
+    /// Panics if the length is not a power of two or exceeds `max_len`.
+    pub fn forward(&self, values: &mut [u64]) {
+        self.transform(values, self.root);
+    }
+
+    /// Invert `forward` in place, including the division by the length.
+    /// Panics if the length is not a power of two or exceeds `max_len`.
+    pub fn inverse(&self, values: &mut [u64]) {
+        let p = self.modulus;
+        self.transform(values, self.pow(self.root, p - 2));
+        let scale = self.pow(values.len() as u64, p - 2);
+        for x in values.iter_mut() {
+            *x = self.mul(*x, scale);
+        }
+    }
+
+    /// Convolve two sequences of residues, that is, multiply them as polynomials modulo the prime.
+    /// The inputs are zero-padded to the next power of two of the output length; empty inputs give an empty output.
+    /// Panics if that length exceeds `max_len`.
+    pub fn convolve(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        if a.is_empty() || b.is_empty() {
+            return Vec::new();
+        }
+        let len = a.len() + b.len() - 1;
+        let size = len.next_power_of_two();
+        let mut fa = a.to_vec();
+        let mut fb = b.to_vec();
+        fa.resize(size, 0);
+        fb.resize(size, 0);
+        self.forward(&mut fa);
+        self.forward(&mut fb);
+        for (x, &y) in fa.iter_mut().zip(&fb) {
+            *x = self.mul(*x, y);
+        }
+        self.inverse(&mut fa);
+        fa.truncate(len);
+        fa
+    }
+
+    /// The iterative Cooley–Tukey transform with `generator` as the primitive root.
+    fn transform(&self, values: &mut [u64], generator: u64) {
+        let n = values.len();
+        assert!(n.is_power_of_two(), "the length must be a power of two");
+        assert!(
+            n <= self.max_len(),
+            "the length exceeds the prime's roots of unity"
+        );
+        let p = self.modulus;
+        for x in values.iter_mut() {
+            *x %= p;
+        }
+        let bits = n.trailing_zeros();
+        for i in 0..n {
+            let j = i
+                .reverse_bits()
+                .checked_shr(usize::BITS - bits)
+                .unwrap_or(0);
+            if i < j {
+                values.swap(i, j);
+            }
+        }
+        let mut half = 1;
+        while half < n {
+            let step = self.pow(generator, (p - 1) / (2 * half as u64));
+            for block in values.chunks_exact_mut(2 * half) {
+                let (low, high) = block.split_at_mut(half);
+                let mut w = 1;
+                for (u, v) in low.iter_mut().zip(high.iter_mut()) {
+                    let t = self.mul(*v, w);
+                    *v = u.sub_mod_constrained(t, p);
+                    *u = u.add_mod(t, p);
+                    w = self.mul(w, step);
+                }
+            }
+            half *= 2;
+        }
+    }
+
+    fn mul(&self, a: u64, b: u64) -> u64 {
+        (a as u128).mul_mod(b as u128, self.modulus) as u64
+    }
+
+    fn pow(&self, base: u64, exponent: u64) -> u64 {
+        (base as u128).pow_mod(exponent as u128, self.modulus) as u64
+    }
+}
+
+/// Convolve two sequences modulo an NTT-friendly prime `p`, that is, multiply them as polynomials.
+/// The result has `a.len() + b.len() - 1` entries, or none if either input is empty.
+/// Panics if `p` is not an odd prime below `2^62`, or if `p - 1` is not divisible by a power of two
+/// at least the output length.
+pub fn convolve<T>(a: &[T], b: &[T], p: T) -> Vec<T>
+where
+    T: Into<u64> + TryFrom<u64> + Copy,
+    <T as TryFrom<u64>>::Error: Debug,
+{
+    let ntt = Ntt::new(p.into()).expect("the modulus must be an odd prime below 2^62");
+    let a: Vec<u64> = a.iter().map(|&x| x.into()).collect();
+    let b: Vec<u64> = b.iter().map(|&x| x.into()).collect();
+    ntt.convolve(&a, &b)
+        .into_iter()
+        .map(|x| T::try_from(x).expect("the residue is smaller than the modulus"))
+        .collect()
+}
+
+/// Convolve two sequences modulo any modulus `m`.
+/// The exact convolution is computed modulo three fixed NTT-friendly primes and each coefficient is
+/// reconstructed modulo `m` with Garner's algorithm, so the primes never show up in the result.
+/// This is exact while the coefficients of the exact convolution, at most `min(a.len(), b.len()) · (m - 1)²`,
+/// stay below the product of the primes, about `5.9 · 10^25`; moduli up to `2^31` are fine for any supported length.
+/// Panics if that bound is exceeded, if `m` is zero, or if the output has more than `2^24` entries.
+pub fn convolve_mod<T>(a: &[T], b: &[T], m: T) -> Vec<T>
+where
+    T: Into<u64> + TryFrom<u64> + Copy,
+    <T as TryFrom<u64>>::Error: Debug,
+{
+    let m: u64 = m.into();
+    assert!(m != 0, "the modulus must be nonzero");
+    let bound = CONVOLUTION_PRIMES
+        .iter()
+        .map(|&p| p as u128)
+        .product::<u128>();
+    let terms = a.len().min(b.len()) as u128;
+    let largest = (m - 1) as u128;
+    assert!(
+        largest
+            .checked_mul(largest)
+            .and_then(|x| x.checked_mul(terms))
+            .is_some_and(|x| x < bound),
+        "the coefficients exceed the product of the convolution primes"
+    );
+    let a: Vec<u64> = a.iter().map(|&x| x.into() % m).collect();
+    let b: Vec<u64> = b.iter().map(|&x| x.into() % m).collect();
+    // The three convolutions are independent, so with the `parallel` feature large ones run on threads of their own.
+    let mut primes = CONVOLUTION_PRIMES;
+    let chunk = parallel::chunk_len(primes.len(), a.len() + b.len());
+    let products: Vec<Vec<u64>> = parallel::map_chunks_mut(&mut primes, chunk, |_, primes| {
+        primes
+            .iter()
+            .map(|&p| {
+                Ntt::new(p)
+                    .expect("the convolution primes are NTT-friendly")
+                    .convolve(&a, &b)
+            })
+            .collect::<Vec<_>>()
+    })
+    .into_iter()
+    .flatten()
+    .collect();
+    (0..products[0].len())
+        .map(|i| {
+            let residues: Vec<i128> = products.iter().map(|c| c[i] as i128).collect();
+            let x = garner(&residues, &CONVOLUTION_PRIMES, m)
+                .expect("the convolution primes are coprime");
+            T::try_from(x as u64).expect("the residue is smaller than the modulus")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SplitMix64;
+    use pretty_assertions::assert_eq;
+
+    fn naive_convolve(a: &[u64], b: &[u64], p: u64) -> Vec<u64> {
+        if a.is_empty() || b.is_empty() {
+            return Vec::new();
+        }
+        let mut result = vec![0u64; a.len() + b.len() - 1];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                let product = (x as u128).mul_mod(y as u128, p) as u64;
+                result[i + j] = result[i + j].add_mod(product, p);
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_ntt_new() {
+        let ntt = Ntt::new(998_244_353).unwrap();
+        assert_eq!(ntt.modulus(), 998_244_353);
+        assert_eq!(ntt.max_len(), 1 << 23);
+        assert_eq!(Ntt::new(17).unwrap().max_len(), 16);
+        assert_eq!(Ntt::new(97).unwrap().max_len(), 32);
+        assert_eq!(Ntt::new(2), None);
+        assert_eq!(Ntt::new(15), None);
+        assert_eq!(Ntt::new(1 << 62), None);
+        for (p, g) in KNOWN_ROOTS {
+            assert_eq!(primitive_root(p), Some(g));
+        }
+    }
+
+    #[test]
+    fn test_ntt_round_trip() {
+        let mut rng = SplitMix64::new(323);
+        for p in [998_244_353u64, 469_762_049, 7_340_033, 17] {
+            let ntt = Ntt::new(p).unwrap();
+            for size in [1, 2, 4, 16] {
+                let original: Vec<u64> = (0..size).map(|_| rng.next_u64() % p).collect();
+                let mut values = original.clone();
+                ntt.forward(&mut values);
+                ntt.inverse(&mut values);
+                assert_eq!(values, original);
+            }
+        }
+        // The transform of a constant is concentrated in the first entry.
+        let ntt = Ntt::new(17).unwrap();
+        let mut values = vec![3u64; 8];
+        ntt.forward(&mut values);
+        assert_eq!(values, [7, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_convolve_naive() {
+        let mut rng = SplitMix64::new(998_244_353);
+        let p = 998_244_353u64;
+        for (n, m) in [(1, 1), (1, 7), (3, 5), (17, 16), (100, 37), (64, 65)] {
+            let a: Vec<u64> = (0..n).map(|_| rng.next_u64() % p).collect();
+            let b: Vec<u64> = (0..m).map(|_| rng.next_u64() % p).collect();
+            assert_eq!(convolve(&a, &b, p), naive_convolve(&a, &b, p));
+        }
+        assert_eq!(convolve(&[1u32, 2, 3], &[4, 5], 97), [4, 13, 22, 15]);
+    }
+
+    #[test]
+    fn test_convolve_empty() {
+        assert_eq!(
+            convolve::<u64>(&[], &[1, 2], 998_244_353),
+            Vec::<u64>::new()
+        );
+        assert_eq!(convolve::<u64>(&[3], &[], 998_244_353), Vec::<u64>::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds")]
+    fn test_convolve_too_long() {
+        convolve(&[1u64; 9], &[1; 9], 17);
+    }
+
+    #[test]
+    fn test_convolve_mod_naive() {
+        let mut rng = SplitMix64::new(324);
+        for m in [1_000_000_007u64, 1 << 31, 1 << 20, (1 << 31) - 1, 2, 1] {
+            for (n, k) in [(1, 1), (5, 3), (40, 33), (128, 200)] {
+                let a: Vec<u64> = (0..n).map(|_| rng.next_u64() % m).collect();
+                let b: Vec<u64> = (0..k).map(|_| rng.next_u64() % m).collect();
+                assert_eq!(
+                    convolve_mod(&a, &b, m),
+                    naive_convolve(&a, &b, m),
+                    "m = {m}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_convolve_mod_edges() {
+        let m = 1_000_000_007u64;
+        assert_eq!(convolve_mod(&[m - 1], &[m - 1], m), [1]);
+        assert_eq!(convolve_mod(&[5u32], &[7], 3), [2]);
+        assert_eq!(convolve_mod::<u64>(&[], &[1], m), Vec::<u64>::new());
+        let ones = vec![m - 1; 1000];
+        assert_eq!(convolve_mod(&ones, &ones, m)[999], 1000);
+    }
+
+    #[test]
+    fn test_convolve_mod_large() {
+        let mut rng = SplitMix64::new(337);
+        let m = 1_000_000_007u64;
+        let a: Vec<u64> = (0..6000).map(|_| rng.next_u64() % m).collect();
+        let b: Vec<u64> = (0..7000).map(|_| rng.next_u64() % m).collect();
+        let product = convolve_mod(&a, &b, m);
+        assert_eq!(product.len(), 12_999);
+        for _ in 0..20 {
+            let k = (rng.next_u64() % 12_999) as usize;
+            let expected = (k.saturating_sub(6999)..=k.min(5999)).fold(0, |sum: u64, i| {
+                sum.add_mod((a[i] as u128).mul_mod(b[k - i] as u128, m) as u64, m)
+            });
+            assert_eq!(product[k], expected, "k = {k}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "exceed")]
+    fn test_convolve_mod_bound() {
+        convolve_mod(&[1u64; 16], &[1; 16], u64::MAX);
+    }
+}
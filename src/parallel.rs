@@ -0,0 +1,54 @@
+//! Splitting batch operations across threads with the `parallel` feature.
+//! Without the feature, or below the size threshold, every operation runs as a single chunk on the calling thread.
+
+use crate::integer::Shareable;
+
+/// The total amount of work, in element operations, below which spawning threads costs more than it saves.
+const THRESHOLD: usize = 1 << 15;
+
+/// The length of the chunks to split `len` items costing about `cost` element operations each into, one chunk per thread.
+/// This is `len` itself, a single chunk, without the `parallel` feature or below the threshold, and always at least one.
+pub(crate) fn chunk_len(len: usize, cost: usize) -> usize {
+    let threads = if cfg!(feature = "parallel") && len.saturating_mul(cost) >= THRESHOLD {
+        std::thread::available_parallelism().map_or(1, |n| n.get())
+    } else {
+        1
+    };
+    len.div_ceil(threads).max(1)
+}
+
+/// Apply `f` to each chunk of `values` of length `chunk` together with the index of the chunk, and collect the results in order.
+/// With the `parallel` feature, each chunk after the first runs on a scoped thread of its own.
+pub(crate) fn map_chunks_mut<A, R, F>(values: &mut [A], chunk: usize, f: F) -> Vec<R>
+where
+    A: Shareable,
+    R: Shareable,
+    F: Fn(usize, &mut [A]) -> R + Shareable,
+{
+    #[cfg(feature = "parallel")]
+    if values.len() > chunk {
+        return std::thread::scope(|scope| {
+            let mut chunks = values.chunks_mut(chunk).enumerate();
+            let first = chunks.next();
+            let handles: Vec<_> = chunks
+                .map(|(i, c)| {
+                    let f = &f;
+                    scope.spawn(move || f(i, c))
+                })
+                .collect();
+            let mut results = Vec::with_capacity(handles.len() + 1);
+            results.extend(first.map(|(i, c)| f(i, c)));
+            results.extend(
+                handles
+                    .into_iter()
+                    .map(|h| h.join().expect("a worker thread panicked")),
+            );
+            results
+        });
+    }
+    values
+        .chunks_mut(chunk)
+        .enumerate()
+        .map(|(i, c)| f(i, c))
+        .collect()
+}
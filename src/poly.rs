@@ -0,0 +1,574 @@
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+
+use crate::{batch_invert, AddMod, Constrain, Integer, Invert, Modulus, MulMod, SubModConstrained};
+
+/// An error dividing polynomials.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PolyError {
+    /// The divisor is the zero polynomial.
+    DivisionByZero,
+    /// The leading coefficient of the divisor has no inverse modulo the modulus.
+    NotInvertible,
+    /// Two interpolation points share their x-coordinate modulo the modulus.
+    DuplicatePoint,
+}
+
+impl fmt::Display for PolyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolyError::DivisionByZero => write!(f, "division by the zero polynomial"),
+            PolyError::NotInvertible => write!(f, "leading coefficient is not invertible"),
+            PolyError::DuplicatePoint => write!(f, "x-coordinates are not distinct"),
+        }
+    }
+}
+
+impl std::error::Error for PolyError {}
+
+/// A polynomial over the integers modulo `m`, stored as its coefficients from the constant term up.
+/// The coefficients are kept constrained to `[0, m)` and trailing zeros are trimmed,
+/// so the zero polynomial has no coefficients at all.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModPoly<T, M> {
+    coefficients: Vec<T>,
+    modulus: M,
+}
+
+impl<T, M> ModPoly<T, M>
+where
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
+{
+    /// Create a polynomial from its coefficients, lowest degree first, constraining each of them to the modulus.
+    pub fn new(coefficients: Vec<T>, modulus: M) -> Self {
+        let coefficients = coefficients
+            .into_iter()
+            .map(|c| c.constrain(modulus))
+            .collect();
+        Self::normalized(coefficients, modulus)
+    }
+
+    /// Create the zero polynomial.
+    pub fn zero(modulus: M) -> Self {
+        Self {
+            coefficients: Vec::new(),
+            modulus,
+        }
+    }
+
+    /// The coefficients, lowest degree first, without trailing zeros.
+    pub fn coefficients(&self) -> &[T] {
+        &self.coefficients
+    }
+
+    /// The modulus the coefficients are reduced by.
+    pub fn modulus(&self) -> M {
+        self.modulus
+    }
+
+    /// The degree, or `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    /// The coefficient of the highest power, or zero for the zero polynomial.
+    pub fn leading_coefficient(&self) -> T {
+        self.coefficients.last().copied().unwrap_or(T::zero())
+    }
+
+    /// Check if this is the zero polynomial.
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    /// Evaluate the polynomial at `x` with Horner's rule.
+    pub fn evaluate(&self, x: T) -> T {
+        let x = x.constrain(self.modulus);
+        self.coefficients.iter().rev().fold(T::zero(), |acc, &c| {
+            acc.mul_mod(x, self.modulus).add_mod(c, self.modulus)
+        })
+    }
+
+    /// Trim the trailing zeros of coefficients that are already constrained.
+    fn normalized(mut coefficients: Vec<T>, modulus: M) -> Self {
+        while coefficients.last() == Some(&T::zero()) {
+            coefficients.pop();
+        }
+        Self {
+            coefficients,
+            modulus,
+        }
+    }
+
+    /// Combine the coefficients pairwise, padding the shorter polynomial with zeros.
+    fn zip_with(&self, rhs: &Self, f: impl Fn(T, T) -> T) -> Self {
+        assert!(self.modulus == rhs.modulus, "the moduli must be equal");
+        let len = self.coefficients.len().max(rhs.coefficients.len());
+        let coefficient = |p: &Self, i| p.coefficients.get(i).copied().unwrap_or(T::zero());
+        let coefficients = (0..len)
+            .map(|i| f(coefficient(self, i), coefficient(rhs, i)))
+            .collect();
+        Self::normalized(coefficients, self.modulus)
+    }
+}
+
+impl<T, M> ModPoly<T, M>
+where
+    T: Invert + TryFrom<M>,
+    M: Modulus<T>,
+{
+    /// Divide by a polynomial with long division, returning the quotient and the remainder,
+    /// whose degree is below that of the divisor.
+    /// The leading coefficient of the divisor must be invertible, which modulo a prime means nonzero.
+    /// Panics if the moduli differ.
+    pub fn div_rem(&self, divisor: &Self) -> Result<(Self, Self), PolyError> {
+        assert!(self.modulus == divisor.modulus, "the moduli must be equal");
+        let modulus = self.modulus;
+        let d = divisor.degree().ok_or(PolyError::DivisionByZero)?;
+        let inverse = divisor
+            .leading_coefficient()
+            .invert(modulus)
+            .ok_or(PolyError::NotInvertible)?;
+        if self.coefficients.len() <= d {
+            return Ok((Self::zero(modulus), self.clone()));
+        }
+        let mut remainder = self.coefficients.clone();
+        let mut quotient = vec![T::zero(); remainder.len() - d];
+        for i in (0..quotient.len()).rev() {
+            let factor = remainder[i + d].mul_mod(inverse, modulus);
+            quotient[i] = factor;
+            for (j, &c) in divisor.coefficients.iter().enumerate() {
+                remainder[i + j] =
+                    remainder[i + j].sub_mod_constrained(factor.mul_mod(c, modulus), modulus);
+            }
+        }
+        remainder.truncate(d);
+        Ok((
+            Self::normalized(quotient, modulus),
+            Self::normalized(remainder, modulus),
+        ))
+    }
+
+    /// The remainder of the division by a polynomial; see `div_rem`.
+    pub fn rem(&self, divisor: &Self) -> Result<Self, PolyError> {
+        self.div_rem(divisor).map(|(_, r)| r)
+    }
+
+    /// Compute the monic greatest common divisor with the Euclidean algorithm.
+    /// The gcd of two zero polynomials is zero.
+    /// Modulo a prime this always succeeds; modulo a composite a remainder may have a non-invertible leading coefficient.
+    /// Panics if the moduli differ.
+    pub fn gcd(&self, other: &Self) -> Result<Self, PolyError> {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let r = a.rem(&b)?;
+            (a, b) = (b, r);
+        }
+        a.monic()
+    }
+
+    /// Compute `self^exponent mod f` by repeated squaring, reducing after every multiplication.
+    /// This is the step behind Kitamasa's method and distinct-degree factorization, usually with `self = x`.
+    /// Panics if the moduli differ.
+    pub fn pow_mod(&self, exponent: u64, f: &Self) -> Result<Self, PolyError> {
+        let base = self.rem(f)?;
+        let mut result = Self::new(vec![T::one()], self.modulus).rem(f)?;
+        for i in (0..u64::BITS - exponent.leading_zeros()).rev() {
+            result = (&result * &result).rem(f)?;
+            if (exponent >> i) & 1 == 1 {
+                result = (&result * &base).rem(f)?;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Scale the polynomial so that its leading coefficient is one; the zero polynomial stays zero.
+    fn monic(&self) -> Result<Self, PolyError> {
+        if self.is_zero() {
+            return Ok(self.clone());
+        }
+        let inverse = self
+            .leading_coefficient()
+            .invert(self.modulus)
+            .ok_or(PolyError::NotInvertible)?;
+        let coefficients = self
+            .coefficients
+            .iter()
+            .map(|c| c.mul_mod(inverse, self.modulus))
+            .collect();
+        Ok(Self::normalized(coefficients, self.modulus))
+    }
+}
+
+/// Find the unique polynomial of degree below `n` through `n` points with distinct x-coordinates modulo a prime,
+/// in `O(n²)` multiplications and a single batched inversion.
+/// No points give the zero polynomial.
+/// Returns `PolyError::DuplicatePoint` if two x-coordinates are congruent modulo the prime.
+pub fn lagrange_interpolate<T, M>(points: &[(T, T)], p: M) -> Result<ModPoly<T, M>, PolyError>
+where
+    T: Invert + TryFrom<M>,
+    M: Modulus<T>,
+{
+    let xs: Vec<T> = points.iter().map(|&(x, _)| x.constrain(p)).collect();
+    let weights = lagrange_weights(&xs, p)?;
+    let n = xs.len();
+    // The coefficients of the product of all `X - x_i`.
+    let mut product = vec![T::zero(); n + 1];
+    product[0] = T::one().constrain(p);
+    for (i, &x) in xs.iter().enumerate() {
+        for k in (1..=i + 1).rev() {
+            product[k] = product[k - 1].sub_mod_constrained(x.mul_mod(product[k], p), p);
+        }
+        product[0] = T::zero().sub_mod_constrained(x.mul_mod(product[0], p), p);
+    }
+    let mut coefficients = vec![T::zero(); n];
+    for ((&x, &(_, y)), &weight) in xs.iter().zip(points).zip(&weights) {
+        let scale = y.constrain(p).mul_mod(weight, p);
+        // Divide the product by `X - x` synthetically, from the top coefficient down.
+        let mut carry = T::zero();
+        for k in (0..n).rev() {
+            carry = product[k + 1].add_mod(x.mul_mod(carry, p), p);
+            coefficients[k] = coefficients[k].add_mod(scale.mul_mod(carry, p), p);
+        }
+    }
+    Ok(ModPoly::normalized(coefficients, p))
+}
+
+/// Evaluate the interpolating polynomial of `n` points with distinct x-coordinates modulo a prime at a single point,
+/// without building the polynomial.
+/// The products `∏_{j≠i} (x - x_j)` come from prefix and suffix products in `O(n)`,
+/// and the Lagrange denominators are inverted in a single batch.
+/// The denominators take `O(n²)` multiplications in general and `O(n)` when the x-coordinates are consecutive,
+/// `x_i = x_0 + i`, as when evaluating sums of powers from their first values.
+/// Returns `PolyError::DuplicatePoint` if two x-coordinates are congruent modulo the prime.
+pub fn lagrange_evaluate_at<T, M>(points: &[(T, T)], x: T, p: M) -> Result<T, PolyError>
+where
+    T: Invert + TryFrom<M>,
+    M: Modulus<T>,
+{
+    let xs: Vec<T> = points.iter().map(|&(x, _)| x.constrain(p)).collect();
+    let weights = lagrange_weights(&xs, p)?;
+    let differences: Vec<T> = xs.iter().map(|&xi| x.sub_mod_constrained(xi, p)).collect();
+    let one = T::one().constrain(p);
+    let mut suffix = vec![one; xs.len() + 1];
+    for i in (0..xs.len()).rev() {
+        suffix[i] = suffix[i + 1].mul_mod(differences[i], p);
+    }
+    let mut prefix = one;
+    let mut sum = T::zero();
+    for (i, &(_, y)) in points.iter().enumerate() {
+        let numerator = prefix.mul_mod(suffix[i + 1], p);
+        let term = y.constrain(p).mul_mod(weights[i], p).mul_mod(numerator, p);
+        sum = sum.add_mod(term, p);
+        prefix = prefix.mul_mod(differences[i], p);
+    }
+    Ok(sum)
+}
+
+/// Compute the inverses of the Lagrange denominators `∏_{j≠i} (x_i - x_j)` for constrained x-coordinates.
+fn lagrange_weights<T, M>(xs: &[T], p: M) -> Result<Vec<T>, PolyError>
+where
+    T: Invert + TryFrom<M>,
+    M: Modulus<T>,
+{
+    let n = xs.len();
+    let one = T::one().constrain(p);
+    let consecutive = xs.windows(2).all(|w| w[1] == w[0].add_mod(one, p));
+    let denominators: Vec<T> = if consecutive {
+        // With `x_i = x_0 + i` the denominator is `(-1)^(n-1-i) i! (n-1-i)!`.
+        let mut factorials = vec![one; n.max(1)];
+        let mut k = T::zero();
+        for i in 1..n {
+            k = k.add_mod(one, p);
+            factorials[i] = factorials[i - 1].mul_mod(k, p);
+        }
+        (0..n)
+            .map(|i| {
+                let d = factorials[i].mul_mod(factorials[n - 1 - i], p);
+                if (n - 1 - i) % 2 == 1 {
+                    T::zero().sub_mod_constrained(d, p)
+                } else {
+                    d
+                }
+            })
+            .collect()
+    } else {
+        (0..n)
+            .map(|i| {
+                (0..n).filter(|&j| j != i).fold(one, |d, j| {
+                    d.mul_mod(xs[i].sub_mod_constrained(xs[j], p), p)
+                })
+            })
+            .collect()
+    };
+    batch_invert(&denominators, p).ok_or(PolyError::DuplicatePoint)
+}
+
+impl<T, M> Add for &ModPoly<T, M>
+where
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
+{
+    type Output = ModPoly<T, M>;
+
+    /// Panics if the moduli differ.
+    fn add(self, rhs: Self) -> ModPoly<T, M> {
+        self.zip_with(rhs, |a, b| a.add_mod(b, self.modulus))
+    }
+}
+
+impl<T, M> Sub for &ModPoly<T, M>
+where
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
+{
+    type Output = ModPoly<T, M>;
+
+    /// Panics if the moduli differ.
+    fn sub(self, rhs: Self) -> ModPoly<T, M> {
+        self.zip_with(rhs, |a, b| a.sub_mod_constrained(b, self.modulus))
+    }
+}
+
+impl<T, M> Mul for &ModPoly<T, M>
+where
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
+{
+    type Output = ModPoly<T, M>;
+
+    /// Multiply with the schoolbook method in `O(nm)` multiplications.
+    /// Panics if the moduli differ.
+    fn mul(self, rhs: Self) -> ModPoly<T, M> {
+        assert!(self.modulus == rhs.modulus, "the moduli must be equal");
+        let modulus = self.modulus;
+        if self.is_zero() || rhs.is_zero() {
+            return ModPoly::zero(modulus);
+        }
+        let mut product = vec![T::zero(); self.coefficients.len() + rhs.coefficients.len() - 1];
+        for (i, &a) in self.coefficients.iter().enumerate() {
+            for (j, &b) in rhs.coefficients.iter().enumerate() {
+                product[i + j] = product[i + j].add_mod(a.mul_mod(b, modulus), modulus);
+            }
+        }
+        // The leading product may vanish modulo a composite.
+        ModPoly::normalized(product, modulus)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PowMod, SplitMix64};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_poly_new() {
+        let p = ModPoly::new(vec![-1i32, 7, 0, 14], 7u32);
+        assert_eq!(p.coefficients(), &[6]);
+        assert_eq!(p.degree(), Some(0));
+        assert_eq!(p.leading_coefficient(), 6);
+        let zero = ModPoly::new(vec![0i32, 7, -14], 7u32);
+        assert!(zero.is_zero());
+        assert_eq!(zero, ModPoly::zero(7));
+        assert_eq!(zero.degree(), None);
+        assert_eq!(zero.leading_coefficient(), 0);
+        assert_eq!(zero.modulus(), 7);
+    }
+
+    #[test]
+    fn test_poly_difference_of_squares() {
+        for modulus in [2u32, 3, 10, 97, 65_521] {
+            let plus = ModPoly::new(vec![1i64, 1], modulus);
+            let minus = ModPoly::new(vec![-1i64, 1], modulus);
+            assert_eq!(&plus * &minus, ModPoly::new(vec![-1, 0, 1], modulus));
+            assert_eq!(&plus - &minus, ModPoly::new(vec![2], modulus));
+            assert_eq!(&plus + &minus, ModPoly::new(vec![0, 2], modulus));
+        }
+    }
+
+    #[test]
+    fn test_poly_mul_zero() {
+        let p = ModPoly::new(vec![3u64, 1, 4, 1, 5], 11u64);
+        let zero = ModPoly::zero(11u64);
+        assert_eq!(&p * &zero, zero);
+        assert_eq!(&zero * &p, zero);
+        assert_eq!(&p - &p, zero);
+        assert_eq!(&p + &zero, p);
+        // The leading coefficients are zero divisors modulo 6.
+        let a = ModPoly::new(vec![1u64, 2], 6u64);
+        let b = ModPoly::new(vec![1u64, 3], 6u64);
+        assert_eq!((&a * &b).coefficients(), &[1, 5]);
+    }
+
+    #[test]
+    fn test_poly_evaluate() {
+        let mut rng = SplitMix64::new(320);
+        let modulus = 1_000_000_007u64;
+        for _ in 0..20 {
+            let coefficients: Vec<i64> = (0..8).map(|_| rng.next_u64() as i64 % 1_000).collect();
+            let p = ModPoly::new(coefficients.clone(), modulus);
+            let q = ModPoly::new(coefficients.iter().rev().copied().collect(), modulus);
+            let x = (rng.next_u64() % modulus) as i64;
+            let direct = coefficients.iter().enumerate().fold(0, |acc, (i, &c)| {
+                acc.add_mod(c.mul_mod(x.pow_mod(i as i64, modulus), modulus), modulus)
+            });
+            assert_eq!(p.evaluate(x), direct);
+            assert_eq!(
+                (&p * &q).evaluate(x),
+                p.evaluate(x).mul_mod(q.evaluate(x), modulus)
+            );
+        }
+        assert_eq!(ModPoly::<i64, u64>::zero(5).evaluate(3), 0);
+        assert_eq!(ModPoly::new(vec![1i64, 2, 3], 5u64).evaluate(-1), 2);
+    }
+
+    #[test]
+    fn test_poly_gcd() {
+        for modulus in [3u64, 5, 7, 97, 1_000_000_007] {
+            let square = ModPoly::new(vec![-1i64, 0, 1], modulus);
+            let cube = ModPoly::new(vec![-1i64, 0, 0, 1], modulus);
+            let expected = ModPoly::new(vec![-1, 1], modulus);
+            assert_eq!(square.gcd(&cube), Ok(expected.clone()));
+            assert_eq!(cube.gcd(&square), Ok(expected));
+            let scaled = &square * &ModPoly::new(vec![2], modulus);
+            assert_eq!(scaled.gcd(&ModPoly::zero(modulus)), Ok(square));
+        }
+        let zero = ModPoly::<i64, u64>::zero(7);
+        assert_eq!(zero.gcd(&zero), Ok(zero));
+    }
+
+    #[test]
+    fn test_poly_div_rem() {
+        let mut rng = SplitMix64::new(321);
+        for modulus in [2u64, 13, 1_000_000_007] {
+            for _ in 0..20 {
+                let random = |len: u64, rng: &mut SplitMix64| {
+                    let len = (rng.next_u64() % len) as usize;
+                    ModPoly::new((0..len).map(|_| rng.next_u64() as i64).collect(), modulus)
+                };
+                let a = random(12, &mut rng);
+                let d = random(6, &mut rng);
+                if d.is_zero() {
+                    assert_eq!(a.div_rem(&d), Err(PolyError::DivisionByZero));
+                    continue;
+                }
+                let (q, r) = a.div_rem(&d).unwrap();
+                assert!(r.degree() < d.degree());
+                assert_eq!(&(&q * &d) + &r, a);
+                assert_eq!(a.rem(&d), Ok(r));
+            }
+        }
+    }
+
+    #[test]
+    fn test_poly_div_rem_not_invertible() {
+        let a = ModPoly::new(vec![1i64, 2, 3], 6u64);
+        let d = ModPoly::new(vec![1i64, 2], 6u64);
+        assert_eq!(a.div_rem(&d), Err(PolyError::NotInvertible));
+        assert_eq!(a.gcd(&d), Err(PolyError::NotInvertible));
+        let (q, r) = a.div_rem(&ModPoly::new(vec![1, 5], 6u64)).unwrap();
+        assert_eq!(&(&q * &ModPoly::new(vec![1, 5], 6u64)) + &r, a);
+    }
+
+    #[test]
+    fn test_poly_pow_mod() {
+        let modulus = 998_244_353u64;
+        let f = ModPoly::new(vec![7i64, -3, 0, 11, 2, 1], modulus);
+        let x = ModPoly::new(vec![0i64, 1], modulus);
+        let mut expected = x.clone();
+        for _ in 0..20 {
+            expected = (&expected * &expected).rem(&f).unwrap();
+        }
+        assert_eq!(x.pow_mod(1 << 20, &f), Ok(expected));
+        assert_eq!(x.pow_mod(0, &f), Ok(ModPoly::new(vec![1], modulus)));
+        assert_eq!(
+            x.pow_mod(3, &f),
+            Ok(ModPoly::new(vec![0, 0, 0, 1], modulus))
+        );
+        let one = ModPoly::new(vec![1i64], modulus);
+        assert_eq!(x.pow_mod(5, &one), Ok(ModPoly::zero(modulus)));
+    }
+
+    #[test]
+    fn test_lagrange_interpolate_cubic() {
+        let modulus = 1_000_000_007u64;
+        let cubic = ModPoly::new(vec![5i64, -3, 0, 2], modulus);
+        let points: Vec<(i64, i64)> = [-2, 0, 3, 10]
+            .iter()
+            .map(|&x| (x, cubic.evaluate(x)))
+            .collect();
+        assert_eq!(lagrange_interpolate(&points, modulus), Ok(cubic.clone()));
+        let consecutive: Vec<(i64, i64)> = (1..=4).map(|x| (x, cubic.evaluate(x))).collect();
+        assert_eq!(lagrange_interpolate(&consecutive, modulus), Ok(cubic));
+        assert_eq!(
+            lagrange_interpolate::<i64, u64>(&[], modulus),
+            Ok(ModPoly::zero(modulus))
+        );
+        assert_eq!(
+            lagrange_interpolate(&[(4i64, 9i64)], modulus),
+            Ok(ModPoly::new(vec![9], modulus))
+        );
+    }
+
+    #[test]
+    fn test_lagrange_evaluate_at() {
+        let mut rng = SplitMix64::new(322);
+        let modulus = 998_244_353u64;
+        for n in 1..12 {
+            let coefficients = (0..n).map(|_| (rng.next_u64() % modulus) as i64).collect();
+            let poly = ModPoly::new(coefficients, modulus);
+            let random: Vec<(i64, i64)> = (0..n)
+                .map(|_| (rng.next_u64() % modulus) as i64)
+                .map(|x| (x, poly.evaluate(x)))
+                .collect();
+            let start = (rng.next_u64() % modulus) as i64;
+            let consecutive: Vec<(i64, i64)> = (start..start + n as i64)
+                .map(|x| (x, poly.evaluate(x)))
+                .collect();
+            for points in [&random, &consecutive] {
+                let interpolated = lagrange_interpolate(points, modulus).unwrap();
+                assert_eq!(interpolated, poly);
+                for x in [0, 1, points[0].0, (rng.next_u64() % modulus) as i64] {
+                    assert_eq!(
+                        lagrange_evaluate_at(points, x, modulus),
+                        Ok(poly.evaluate(x))
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lagrange_sum_of_squares() {
+        // The sum of the first `n` squares is a cubic in `n`, determined by its first four values.
+        let points = [(0i64, 0i64), (1, 1), (2, 5), (3, 14)];
+        let n = 1_000_000i64;
+        let expected = (n * (n + 1) % 1_000_000_007 * (2 * n + 1) % 1_000_000_007) * 166_666_668
+            % 1_000_000_007;
+        assert_eq!(
+            lagrange_evaluate_at(&points, n, 1_000_000_007u64),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn test_lagrange_duplicate() {
+        let points = [(1i64, 2i64), (8, 3)];
+        assert_eq!(
+            lagrange_interpolate(&points, 7u64),
+            Err(PolyError::DuplicatePoint)
+        );
+        assert_eq!(
+            lagrange_evaluate_at(&points, 0, 7u64),
+            Err(PolyError::DuplicatePoint)
+        );
+        let consecutive: Vec<(i64, i64)> = (0..8).map(|x| (x, x)).collect();
+        assert_eq!(
+            lagrange_evaluate_at(&consecutive, 3, 7u64),
+            Err(PolyError::DuplicatePoint)
+        );
+        assert_eq!(lagrange_evaluate_at(&consecutive[..7], 9, 7u64), Ok(2));
+    }
+}
@@ -0,0 +1,505 @@
+use num_traits::{FromPrimitive, PrimInt};
+
+use crate::{AddMod, Constrain, Integer, Modulus, MulMod};
+
+/// The number of bases sharing one table of products in `multi_pow_mod`.
+const MULTI_POW_CHUNK: usize = 4;
+
+/// Compute the product `Π base^exponent` modulo a modulus for non-negative exponents.
+/// This is Shamir's trick: the exponents are scanned jointly from the top bit so that all terms share one chain of squarings,
+/// and the bases are grouped in fours whose subset products are precomputed so each bit costs one multiplication per group.
+/// For two terms this takes about 40% fewer multiplications than two `pow_mod` calls.
+pub fn multi_pow_mod<T, M>(terms: &[(T, T)], modulus: M) -> T
+where
+    T: Integer + PrimInt + TryFrom<M> + Constrain<M> + MulMod<M, Output = T>,
+    M: Modulus<T>,
+{
+    let one = T::one().constrain(modulus);
+    if terms.is_empty() {
+        return one;
+    }
+    // The products of every subset of each group of bases, in blocks of `2^MULTI_POW_CHUNK`.
+    let size = 1 << MULTI_POW_CHUNK;
+    let mut tables = Vec::with_capacity(terms.len().div_ceil(MULTI_POW_CHUNK) * size);
+    for chunk in terms.chunks(MULTI_POW_CHUNK) {
+        let start = tables.len();
+        tables.push(one);
+        for &(base, _) in chunk {
+            let base = base.constrain(modulus);
+            for k in start..tables.len() {
+                tables.push(tables[k].mul_mod(base, modulus));
+            }
+        }
+        tables.resize(start + size, one);
+    }
+    let width = T::zero().count_zeros() as usize;
+    let length = terms
+        .iter()
+        .map(|&(_, exponent)| width - exponent.leading_zeros() as usize)
+        .max()
+        .unwrap_or(0);
+    let mut result = one;
+    for i in (0..length).rev() {
+        result = result.mul_mod(result, modulus);
+        for (table, chunk) in tables.chunks(size).zip(terms.chunks(MULTI_POW_CHUNK)) {
+            let index = chunk
+                .iter()
+                .enumerate()
+                .filter(|&(_, &(_, exponent))| (exponent >> i) & T::one() == T::one())
+                .fold(0, |index, (j, _)| index | 1 << j);
+            if index != 0 {
+                result = result.mul_mod(table[index], modulus);
+            }
+        }
+    }
+    result
+}
+
+/// Raise an integer to a power with a sliding window of `window` bits and constrain the result to a modulus.
+/// Precomputing the odd powers below `2^window` replaces most multiplications of the binary method
+/// with one per window, which pays off for large exponents. The result is identical to `PowMod::pow_mod`.
+/// Panics if `window` is zero.
+pub fn pow_mod_window<T, M>(base: T, exponent: T, modulus: M, window: u32) -> T
+where
+    T: Integer + TryFrom<M> + Constrain<M> + FromPrimitive + MulMod<M, Output = T>,
+    M: Modulus<T>,
+{
+    assert!(window > 0, "the window must be at least one bit wide");
+    let two = T::from_i8(2).expect("two");
+    let mut bits = Vec::new();
+    let mut rest = exponent;
+    while rest != T::zero() {
+        bits.push(rest % two == T::one());
+        rest = rest / two;
+    }
+    if bits.is_empty() {
+        return T::one();
+    }
+    // The odd powers `base^1, base^3, ..., base^(2^window - 1)`, capped by the exponent length.
+    let window = (window as usize).min(bits.len());
+    let square = base.mul_mod(base, modulus);
+    let mut odd = vec![base.constrain(modulus)];
+    for i in 1..1 << (window - 1) {
+        odd.push(odd[i - 1].mul_mod(square, modulus));
+    }
+    let mut result = T::one();
+    let mut top = bits.len();
+    while top > 0 {
+        if !bits[top - 1] {
+            result = result.mul_mod(result, modulus);
+            top -= 1;
+            continue;
+        }
+        // The longest window starting at the top bit that ends in a set bit.
+        let mut bottom = top.saturating_sub(window);
+        while !bits[bottom] {
+            bottom += 1;
+        }
+        let mut value = 0;
+        for i in (bottom..top).rev() {
+            result = result.mul_mod(result, modulus);
+            value = value << 1 | bits[i] as usize;
+        }
+        result = result.mul_mod(odd[value >> 1], modulus);
+        top = bottom;
+    }
+    result
+}
+
+/// Raise an integer to a power given as big-endian bytes and constrain the result to a modulus.
+/// The exponent can be arbitrarily long; leading zero bytes are allowed and an empty slice is zero.
+pub fn pow_mod_bytes<T, M>(base: T, exponent: &[u8], modulus: M) -> T
+where
+    T: Integer + TryFrom<M> + Constrain<M> + MulMod<M, Output = T>,
+    M: Modulus<T>,
+{
+    pow_mod_digits(base, exponent.iter().map(|&byte| byte as u64), 8, modulus)
+}
+
+/// Raise an integer to a power given as 64-bit limbs, most significant first, and constrain the result to a modulus.
+/// The exponent can be arbitrarily long; leading zero limbs are allowed and an empty slice is zero.
+pub fn pow_mod_limbs<T, M>(base: T, exponent: &[u64], modulus: M) -> T
+where
+    T: Integer + TryFrom<M> + Constrain<M> + MulMod<M, Output = T>,
+    M: Modulus<T>,
+{
+    pow_mod_digits(base, exponent.iter().copied(), 64, modulus)
+}
+
+/// Left-to-right binary exponentiation with an exponent given as digits of `bits` bits, most significant first.
+fn pow_mod_digits<T, M>(base: T, digits: impl Iterator<Item = u64>, bits: u32, modulus: M) -> T
+where
+    T: Integer + TryFrom<M> + Constrain<M> + MulMod<M, Output = T>,
+    M: Modulus<T>,
+{
+    let base = base.constrain(modulus);
+    let mut result = T::one().constrain(modulus);
+    for digit in digits {
+        for i in (0..bits).rev() {
+            result = result.mul_mod(result, modulus);
+            if (digit >> i) & 1 == 1 {
+                result = result.mul_mod(base, modulus);
+            }
+        }
+    }
+    result
+}
+
+/// Exponentiation of a fixed base modulo a fixed modulus with precomputed powers.
+/// The table holds `base^(j·2^(w·i))` for every window `i` of `w` bits in the exponent and every digit `j < 2^w`,
+/// so each exponentiation is a product of one table entry per nonzero window without any squarings.
+/// This pays off when many exponents are raised with the same base, such as a Diffie–Hellman generator.
+#[derive(Clone, Debug)]
+pub struct FixedBasePow<T, M> {
+    table: Vec<T>,
+    modulus: M,
+    window_bits: u32,
+}
+
+#[cfg(feature = "zeroize")]
+impl<T, M> Drop for FixedBasePow<T, M> {
+    /// The table holds powers of the base, which may be secret.
+    fn drop(&mut self) {
+        crate::wipe::wipe_vec(&mut self.table);
+    }
+}
+
+impl<T, M> FixedBasePow<T, M>
+where
+    T: Integer + PrimInt + TryFrom<M> + Constrain<M> + MulMod<M, Output = T>,
+    M: Modulus<T>,
+{
+    /// Precompute the powers of `base` for exponents of the full width of `T`, `window_bits` at a time.
+    /// The table has `⌈width / window_bits⌉ · 2^window_bits` entries.
+    /// Panics if `window_bits` is not in `1..=16`.
+    pub fn new(base: T, modulus: M, window_bits: u32) -> Self {
+        assert!(
+            (1..=16).contains(&window_bits),
+            "window_bits must be in 1..=16"
+        );
+        let width = T::zero().count_zeros();
+        // Wider windows than the exponent would not fit the digit mask in `T`.
+        let window_bits = window_bits.min(width - 1);
+        let windows = width.div_ceil(window_bits) as usize;
+        let size = 1 << window_bits;
+        let one = T::one().constrain(modulus);
+        let mut table = Vec::with_capacity(windows * size);
+        let mut power = base.constrain(modulus);
+        for _ in 0..windows {
+            let mut entry = one;
+            for _ in 0..size {
+                table.push(entry);
+                entry = entry.mul_mod(power, modulus);
+            }
+            power = entry;
+        }
+        Self {
+            table,
+            modulus,
+            window_bits,
+        }
+    }
+
+    /// Raise the base to a non-negative power modulo the modulus.
+    pub fn pow(&self, exponent: T) -> T {
+        let size = 1 << self.window_bits;
+        let mask = T::from(size - 1).expect("the window mask fits");
+        let mut result = self.table[0];
+        let mut exponent = exponent;
+        let mut offset = 0;
+        while exponent != T::zero() {
+            let digit = (exponent & mask).to_usize().expect("the digit fits");
+            if digit != 0 {
+                result = result.mul_mod(self.table[offset + digit], self.modulus);
+            }
+            exponent = exponent.unsigned_shr(self.window_bits);
+            offset += size;
+        }
+        result
+    }
+}
+
+/// An infinite iterator over the powers `1, base, base², …` of a base modulo a modulus.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PowersMod<T, M> {
+    next: T,
+    base: T,
+    modulus: M,
+}
+
+impl<T, M> Iterator for PowersMod<T, M>
+where
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let current = self.next;
+        self.next = current.mul_mod(self.base, self.modulus);
+        Some(current)
+    }
+}
+
+/// Iterate over the powers `1, base, base², …` of a base modulo a modulus, one multiplication per step.
+/// The first power is `1` constrained to the modulus, so modulo one every power is zero.
+pub fn powers_mod<T, M>(base: T, modulus: M) -> PowersMod<T, M>
+where
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
+{
+    PowersMod {
+        next: T::one().constrain(modulus),
+        base: base.constrain(modulus),
+        modulus,
+    }
+}
+
+/// Collect the powers `base^0, …, base^n` modulo a modulus, which is `n + 1` values.
+pub fn powers_mod_upto<T, M>(base: T, n: usize, modulus: M) -> Vec<T>
+where
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
+{
+    powers_mod(base, modulus).take(n + 1).collect()
+}
+
+/// Compute the geometric sum `1 + r + r² + … + r^(n-1)` modulo a modulus in `O(log n)` multiplications.
+/// Rather than the fraction `(r^n - 1) / (r - 1)`, which needs `r - 1` to be invertible, this walks the bits of `n`
+/// with `S(2k) = S(k)(1 + r^k)` and `S(k + 1) = S(k) + r^k`, so any modulus works. No terms sum to zero.
+pub fn geometric_sum_mod<T, M>(r: T, n: u64, modulus: M) -> T
+where
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
+{
+    let r = r.constrain(modulus);
+    let one = T::one().constrain(modulus);
+    // The sum of the first k terms and r^k, for the prefix k of the bits of n.
+    let (mut sum, mut power) = (T::zero(), one);
+    for i in (0..u64::BITS - n.leading_zeros()).rev() {
+        sum = sum.mul_mod(one.add_mod(power, modulus), modulus);
+        power = power.mul_mod(power, modulus);
+        if (n >> i) & 1 == 1 {
+            sum = sum.add_mod(power, modulus);
+            power = power.mul_mod(r, modulus);
+        }
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PowMod, SplitMix64};
+    use pretty_assertions::assert_eq;
+
+    fn naive(terms: &[(u128, u128)], modulus: u64) -> u128 {
+        terms
+            .iter()
+            .fold(1 % modulus as u128, |acc, &(base, exponent)| {
+                acc.mul_mod(base.pow_mod(exponent, modulus), modulus)
+            })
+    }
+
+    #[test]
+    fn test_multi_pow_mod() {
+        assert_eq!(
+            multi_pow_mod(&[(2, 10), (3, 4)], 1_000u32),
+            1024 * 81 % 1000
+        );
+        assert_eq!(multi_pow_mod(&[(-2, 3), (5, 0)], 7u32), 6);
+        assert_eq!(multi_pow_mod::<i32, u32>(&[], 7), 1);
+        assert_eq!(multi_pow_mod(&[(5, 0)], 1u32), 0);
+    }
+
+    #[test]
+    fn test_multi_pow_mod_random() {
+        let mut rng = SplitMix64::new(307);
+        for len in 0..10 {
+            for _ in 0..20 {
+                let modulus = rng.next_u64() | 1;
+                let terms: Vec<(u128, u128)> = (0..len)
+                    .map(|_| (rng.next_u64() as u128, rng.next_u64() as u128))
+                    .collect();
+                assert_eq!(multi_pow_mod(&terms, modulus), naive(&terms, modulus));
+            }
+        }
+    }
+
+    #[test]
+    fn test_fixed_base_pow() {
+        let pow = FixedBasePow::new(3, 1_000u32, 4);
+        assert_eq!(pow.pow(0), 1);
+        assert_eq!(pow.pow(7), 187);
+        assert_eq!(FixedBasePow::new(-2i64, 7u32, 3).pow(3), 6);
+        assert_eq!(FixedBasePow::new(5u8, 1u8, 2).pow(0), 0);
+        assert_eq!(
+            FixedBasePow::new(3u16, 251u16, 16).pow(65_535),
+            3u16.pow_mod(65_535, 251u16)
+        );
+    }
+
+    #[test]
+    fn test_fixed_base_pow_random() {
+        let mut rng = SplitMix64::new(308);
+        for window_bits in [1, 2, 3, 4, 5, 8] {
+            let modulus = rng.next_u64() | 1;
+            let base = rng.next_u64() as u128;
+            let pow = FixedBasePow::new(base, modulus, window_bits);
+            for _ in 0..50 {
+                let exponent = rng.next_u64() as u128;
+                assert_eq!(pow.pow(exponent), base.pow_mod(exponent, modulus));
+            }
+            let exponent = u128::MAX;
+            assert_eq!(pow.pow(exponent), base.pow_mod(exponent, modulus));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "window_bits must be in 1..=16")]
+    fn test_fixed_base_pow_zero_window() {
+        FixedBasePow::new(3, 7u32, 0);
+    }
+
+    #[test]
+    fn test_pow_mod_window() {
+        assert_eq!(pow_mod_window(3i32, 0, 7u32, 4), 1);
+        assert_eq!(pow_mod_window(3i32, 7, 1_000u32, 3), 187);
+        assert_eq!(pow_mod_window(-2i64, 3, 7u32, 2), 6);
+        assert_eq!(pow_mod_window(5u8, 3, 1u8, 2), 0);
+    }
+
+    #[test]
+    fn test_pow_mod_window_matches_pow_mod() {
+        let mut rng = SplitMix64::new(309);
+        for window in 1..=6 {
+            for _ in 0..200 {
+                let modulus = rng.next_u64() | 1;
+                let base = rng.next_u64() as u128;
+                let exponent = rng.next_u64() as u128 >> (rng.next_u64() % 64);
+                assert_eq!(
+                    pow_mod_window(base, exponent, modulus, window),
+                    base.pow_mod(exponent, modulus),
+                    "{base}^{exponent} mod {modulus}, window {window}"
+                );
+            }
+            for exponent in 0..300u128 {
+                assert_eq!(
+                    pow_mod_window(7u128, exponent, 1_000_003u64, window),
+                    7u128.pow_mod(exponent, 1_000_003u64)
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the window must be at least one bit wide")]
+    fn test_pow_mod_window_zero() {
+        pow_mod_window(3i32, 5, 7u32, 0);
+    }
+
+    #[test]
+    fn test_pow_mod_bytes() {
+        assert_eq!(pow_mod_bytes(3i32, &[], 7u32), 1);
+        assert_eq!(pow_mod_bytes(3i32, &[], 1u32), 0);
+        assert_eq!(pow_mod_bytes(3i32, &[0, 0, 7], 1_000u32), 187);
+        assert_eq!(
+            pow_mod_bytes(3i32, &[1, 0], 1_000u32),
+            3i32.pow_mod(256, 1_000u32)
+        );
+        // By Fermat, the exponent 2^72 of 2^(2^72) mod p can be reduced modulo p - 1.
+        let exponent = [1, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        let reduced = (1u128 << 72) % 1_000_000_006;
+        assert_eq!(
+            pow_mod_bytes(2i64, &exponent, 1_000_000_007u64),
+            2i64.pow_mod(reduced as i64, 1_000_000_007u64)
+        );
+    }
+
+    #[test]
+    fn test_pow_mod_bytes_matches_pow_mod() {
+        let mut rng = SplitMix64::new(313);
+        for _ in 0..500 {
+            let modulus = rng.next_u64() | 2;
+            let base = rng.next_u64() as u128;
+            let exponent = rng.next_u64() >> (rng.next_u64() % 64);
+            let expected = base.pow_mod(exponent as u128, modulus);
+            let mut bytes = vec![0, 0];
+            bytes.extend(exponent.to_be_bytes());
+            assert_eq!(pow_mod_bytes(base, &bytes, modulus), expected);
+            assert_eq!(pow_mod_limbs(base, &[0, exponent], modulus), expected);
+            let wide = (exponent as u128) << 64 | rng.next_u64() as u128;
+            assert_eq!(
+                pow_mod_limbs(base, &[(wide >> 64) as u64, wide as u64], modulus),
+                base.pow_mod(wide, modulus)
+            );
+        }
+    }
+
+    #[test]
+    fn test_powers_mod() {
+        let powers: Vec<u32> = powers_mod(3u32, 7u32).take(10).collect();
+        assert_eq!(powers, [1, 3, 2, 6, 4, 5, 1, 3, 2, 6]);
+        let negative: Vec<i64> = powers_mod(-2i64, 5u32).take(5).collect();
+        assert_eq!(negative, [1, 3, 4, 2, 1]);
+        assert_eq!(powers_mod(0u8, 9u8).take(3).collect::<Vec<_>>(), [1, 0, 0]);
+        assert!(powers_mod(5u64, 1u64).take(10).all(|x| x == 0));
+    }
+
+    #[test]
+    fn test_powers_mod_upto() {
+        assert_eq!(powers_mod_upto(10u64, 0, 7u64), [1]);
+        assert_eq!(powers_mod_upto(10u64, 0, 1u64), [0]);
+        assert_eq!(powers_mod_upto(2u64, 10, 1_000u64).last(), Some(&24));
+        let modulus = 1_000_000_007u64;
+        let powers = powers_mod_upto(31u64, 100, modulus);
+        for (i, &x) in powers.iter().enumerate() {
+            assert_eq!(x, 31u64.pow_mod(i as u64, modulus));
+        }
+        let data = [4u64, 5, 6];
+        let iter = powers_mod(10u64, modulus);
+        let value = data
+            .iter()
+            .zip(iter.clone())
+            .fold(0, |acc, (&d, p)| acc + d * p);
+        assert_eq!(value, 654);
+        assert_eq!(iter.take(2).collect::<Vec<_>>(), [1, 10]);
+    }
+
+    #[test]
+    fn test_geometric_sum_mod_small() {
+        for modulus in [1u64, 2, 6, 9, 12, 16, 97, 1_000] {
+            for r in -5i64..12 {
+                let mut direct = 0;
+                let mut power = 1i64.constrain(modulus);
+                for n in 0..40 {
+                    assert_eq!(
+                        geometric_sum_mod(r, n, modulus),
+                        direct,
+                        "r = {r}, n = {n}, m = {modulus}"
+                    );
+                    direct = direct.add_mod(power, modulus);
+                    power = power.mul_mod(r, modulus);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_geometric_sum_mod_large() {
+        let n = 1_000_000_000_000_000_000u64;
+        assert_eq!(
+            geometric_sum_mod(1u128, n, 1_000_000_007u64),
+            (n % 1_000_000_007) as u128
+        );
+        assert_eq!(geometric_sum_mod(0u128, n, 1_000_000_007u64), 1);
+        assert_eq!(geometric_sum_mod(5u64, 0, 7u64), 0);
+        // The repunit with n ones is the geometric sum with ratio 10; 10^6 - 1 = 999_999.
+        assert_eq!(geometric_sum_mod(10u128, 6, u64::MAX), 111_111);
+        // 10 - 1 is not invertible modulo 9, and the repunit 1…1 is congruent to its number of digits.
+        assert_eq!(geometric_sum_mod(10u128, n, 9u64), (n % 9) as u128);
+        let p = 1_000_000_007u128;
+        let expected = (2u128.pow_mod(61, p as u64) + p - 1) % p;
+        assert_eq!(geometric_sum_mod(2u128, 61, p as u64), expected);
+    }
+}
@@ -0,0 +1,419 @@
+use crate::{jacobi, AddMod, Constrain, MulMod, PowMod, SplitMix64, SubModConstrained};
+
+/// The primes used for trial division before running Miller–Rabin.
+const SMALL_PRIMES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// Miller–Rabin bases that are deterministic for all `n < 4_759_123_141`, which covers `u32`.
+const BASES_32: [u64; 3] = [2, 7, 61];
+
+/// Check if an unsigned integer of at most 64 bits is prime.
+/// This is the deterministic Miller–Rabin test; see `is_prime_u64`.
+pub fn is_prime<T: Into<u64>>(n: T) -> bool {
+    is_prime_u64(n.into())
+}
+
+/// Check if a `u64` is prime with the deterministic variant of the Miller–Rabin test.
+/// The bases 2, 7 and 61 are known to have no strong pseudoprimes below `4_759_123_141`, and the primes
+/// up to 37 have none below `3.18 * 10^23`, so the answer is exact for every `u64`.
+pub fn is_prime_u64(n: u64) -> bool {
+    if let Some(result) = trial_division(n) {
+        return result;
+    }
+    let bases: &[u64] = if n < 4_759_123_141 {
+        &BASES_32
+    } else {
+        &SMALL_PRIMES
+    };
+    bases.iter().all(|&base| is_strong_probable_prime(n, base))
+}
+
+/// Check if an unsigned integer is probably prime with `rounds` rounds of the Miller–Rabin test.
+/// A composite passes a single round with probability at most 1/4.
+/// The bases are drawn from a `SplitMix64` generator seeded with `n`, so the result is reproducible.
+pub fn is_probable_prime<T: Into<u64>>(n: T, rounds: u32) -> bool {
+    let n = n.into();
+    let mut rng = SplitMix64::new(n);
+    is_probable_prime_with(n, rounds, || rng.next_u64())
+}
+
+/// Check if an unsigned integer is probably prime with `rounds` rounds of the Miller–Rabin test,
+/// drawing the bases from a caller-provided random generator.
+pub fn is_probable_prime_with<T: Into<u64>>(
+    n: T,
+    rounds: u32,
+    mut rng: impl FnMut() -> u64,
+) -> bool {
+    let n = n.into();
+    if let Some(result) = trial_division(n) {
+        return result;
+    }
+    (0..rounds).all(|_| is_strong_probable_prime(n, 2 + rng() % (n - 3)))
+}
+
+/// Check if an odd `n > 2` is a strong probable prime to the given base, that is, if `n` passes
+/// a single round of the Miller–Rabin test. Writing `n - 1 = d * 2^s` with `d` odd, this holds when
+/// `base^d ≡ 1` or `base^(d * 2^r) ≡ -1 (mod n)` for some `0 ≤ r < s`.
+/// Returns `false` for even `n`, for `n < 3`, and for a base divisible by `n`, which proves nothing.
+/// Products are computed in `u128`, so there is no overflow for any `n`.
+pub fn is_strong_probable_prime(n: u64, base: u64) -> bool {
+    if n < 3 || n.is_multiple_of(2) {
+        return false;
+    }
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    let minus_one = (n - 1) as u128;
+    let mut x = (base as u128).pow_mod(d as u128, n);
+    if x == 1 || x == minus_one {
+        return true;
+    }
+    for _ in 1..s {
+        x = x.mul_mod(x, n);
+        if x == minus_one {
+            return true;
+        }
+    }
+    false
+}
+
+/// Check if a `u128` is probably prime with `rounds` rounds of the Miller–Rabin test.
+/// Integers of at most 64 bits are tested deterministically with `is_prime_u64`.
+/// The bases are drawn from a `SplitMix64` generator seeded with `n`, so the result is reproducible.
+pub(crate) fn is_probable_prime_u128(n: u128, rounds: u32) -> bool {
+    if let Ok(n) = u64::try_from(n) {
+        return is_prime_u64(n);
+    }
+    if SMALL_PRIMES.iter().any(|&p| n.is_multiple_of(p as u128)) {
+        return false;
+    }
+    let mut rng = SplitMix64::new(n as u64 ^ (n >> 64) as u64);
+    (0..rounds).all(|_| {
+        let base = ((rng.next_u64() as u128) << 64 | rng.next_u64() as u128) % (n - 3) + 2;
+        is_strong_probable_prime_u128(n, base)
+    })
+}
+
+/// The strong probable prime test of `is_strong_probable_prime` for an odd `n` above 64 bits.
+fn is_strong_probable_prime_u128(n: u128, base: u128) -> bool {
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    let minus_one = n - 1;
+    let mut x = pow_mod_u128(base, d, n);
+    if x == 1 || x == minus_one {
+        return true;
+    }
+    for _ in 1..s {
+        x = mul_mod_u128(x, x, n);
+        if x == minus_one {
+            return true;
+        }
+    }
+    false
+}
+
+fn pow_mod_u128(mut base: u128, mut exponent: u128, n: u128) -> u128 {
+    let mut result = 1;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod_u128(result, base, n);
+        }
+        base = mul_mod_u128(base, base, n);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// Multiply `a` and `b` in `[0, n)` by doubling and adding, since there is no wider type to hold the product.
+fn mul_mod_u128(a: u128, b: u128, n: u128) -> u128 {
+    // Both operands are below `n`, so comparing with the distance to `n` avoids overflowing the sum.
+    let add = |x: u128, y: u128| if x >= n - y { x - (n - y) } else { x + y };
+    (0..128 - b.leading_zeros()).rev().fold(0, |acc, bit| {
+        let acc = add(acc, acc);
+        if (b >> bit) & 1 == 1 {
+            add(acc, a)
+        } else {
+            acc
+        }
+    })
+}
+
+/// Check if an unsigned integer of at most 64 bits is prime with the Baillie–PSW test,
+/// that is, a strong probable prime test to base 2 followed by a strong Lucas probable prime test
+/// with Selfridge's parameters. No composite passing both is known.
+pub fn is_bpsw_prime<T: Into<u64>>(n: T) -> bool {
+    let n = n.into();
+    if let Some(result) = trial_division(n) {
+        return result;
+    }
+    is_strong_probable_prime(n, 2) && is_strong_lucas_probable_prime(n)
+}
+
+/// Check if an odd `n` without small factors is a strong Lucas probable prime.
+/// The parameters follow Selfridge: `D` is the first of `5, -7, 9, -11, …` with `(D | n) = -1`, `P = 1` and `Q = (1 - D) / 4`.
+/// Writing `n + 1 = d * 2^s` with `d` odd, `n` passes when `U_d ≡ 0` or `V_(d * 2^r) ≡ 0 (mod n)` for some `0 ≤ r < s`.
+fn is_strong_lucas_probable_prime(n: u64) -> bool {
+    // Selfridge's search never ends for perfect squares.
+    if n.isqrt() * n.isqrt() == n {
+        return false;
+    }
+    let mut d: i128 = 5;
+    loop {
+        match jacobi(d, n as i128) {
+            Some(-1) => break,
+            Some(0) if d.unsigned_abs() != n as u128 => return false,
+            _ => d = if d > 0 { -d - 2 } else { -d + 2 },
+        }
+    }
+    let q = ((1 - d) / 4).constrain(n) as u128;
+    let d = d.constrain(n) as u128;
+    let half = |x: u128| {
+        if x.is_multiple_of(2) {
+            x / 2
+        } else {
+            (x + n as u128) / 2
+        }
+    };
+
+    let s = (n + 1).trailing_zeros();
+    let k = (n + 1) >> s;
+    // Compute U_k, V_k and Q^k from the most significant bit down, starting from U_1 = 1, V_1 = P = 1.
+    let (mut u, mut v, mut qk) = (1u128, 1u128, q);
+    for bit in (0..k.ilog2()).rev() {
+        u = u.mul_mod(v, n);
+        v = v.mul_mod(v, n).sub_mod_constrained(qk.add_mod(qk, n), n);
+        qk = qk.mul_mod(qk, n);
+        if (k >> bit) & 1 == 1 {
+            (u, v) = (half(u.add_mod(v, n)), half(d.mul_mod(u, n).add_mod(v, n)));
+            qk = qk.mul_mod(q, n);
+        }
+    }
+    if u == 0 {
+        return true;
+    }
+    for _ in 0..s {
+        if v == 0 {
+            return true;
+        }
+        v = v.mul_mod(v, n).sub_mod_constrained(qk.add_mod(qk, n), n);
+        qk = qk.mul_mod(qk, n);
+    }
+    false
+}
+
+/// Settle the easy cases: numbers below two, small primes, and multiples of small primes.
+/// Returns `None` if `n` has no small factor and needs a full test.
+fn trial_division(n: u64) -> Option<bool> {
+    if n < 2 {
+        return Some(false);
+    }
+    for p in SMALL_PRIMES {
+        if n.is_multiple_of(p) {
+            return Some(n == p);
+        }
+    }
+    if n < 41 * 41 {
+        return Some(true);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn is_prime_naive(n: u64) -> bool {
+        n >= 2
+            && (2..)
+                .take_while(|d| d * d <= n)
+                .all(|d| !n.is_multiple_of(d))
+    }
+
+    #[test]
+    fn test_is_probable_prime_small() {
+        for n in 0..10_000u64 {
+            assert_eq!(is_probable_prime(n, 10), is_prime_naive(n), "n = {n}");
+        }
+        assert!(is_probable_prime(7u8, 10));
+        assert!(!is_probable_prime(255u8, 10));
+    }
+
+    #[test]
+    fn test_is_probable_prime_large() {
+        for n in [
+            1_000_000_007u64,
+            998_244_353,
+            (1 << 61) - 1,
+            18_446_744_073_709_551_557,
+        ] {
+            assert!(is_probable_prime(n, 20));
+        }
+        for n in [1_000_000_007u64 * 998_244_353, u64::MAX, 4_294_967_297] {
+            assert!(!is_probable_prime(n, 20));
+        }
+    }
+
+    #[test]
+    fn test_is_probable_prime_carmichael() {
+        for n in [
+            561u64,
+            1105,
+            1729,
+            2465,
+            2821,
+            6601,
+            8911,
+            41041,
+            825_265,
+            321_197_185,
+        ] {
+            assert!(!is_probable_prime(n, 20), "n = {n}");
+        }
+    }
+
+    #[test]
+    fn test_strong_pseudoprime() {
+        let n = 3_215_031_751u64;
+        assert!([2, 3, 5, 7]
+            .iter()
+            .all(|&base| is_strong_probable_prime(n, base)));
+        assert!(!is_strong_probable_prime(n, 11));
+        assert!(!is_probable_prime(n, 20));
+    }
+
+    #[test]
+    fn test_strong_probable_prime_degenerate() {
+        assert!(!is_strong_probable_prime(9, 9));
+        assert!(!is_strong_probable_prime(25, 5));
+        assert!(!is_strong_probable_prime(7, 14));
+        for n in [0, 1, 2, 4, 100] {
+            assert!(!is_strong_probable_prime(n, 2), "n = {n}");
+        }
+        assert!(is_strong_probable_prime(7, 3));
+    }
+
+    #[test]
+    fn test_is_prime_small() {
+        for n in 0..100_000u64 {
+            assert_eq!(is_prime_u64(n), is_prime_naive(n), "n = {n}");
+        }
+        assert!(is_prime(65_521u16));
+        assert!(!is_prime(65_535u16));
+        assert!(is_prime(4_294_967_291u32));
+        assert!(!is_prime(u32::MAX));
+    }
+
+    #[test]
+    fn test_is_prime_largest() {
+        // The largest primes below 2^64.
+        for n in [
+            18_446_744_073_709_551_557u64,
+            18_446_744_073_709_551_533,
+            18_446_744_073_709_551_521,
+        ] {
+            assert!(is_prime_u64(n));
+        }
+        for n in (18_446_744_073_709_551_522u64..18_446_744_073_709_551_533)
+            .chain([u64::MAX, u64::MAX - 1])
+        {
+            assert!(!is_prime_u64(n), "n = {n}");
+        }
+    }
+
+    #[test]
+    fn test_is_prime_squares() {
+        for p in [4_294_967_291u64, 1_000_000_007, 65_521, 41] {
+            assert!(!is_prime_u64(p * p));
+        }
+        assert!(!is_prime_u64(4_294_967_291 * 4_294_967_279));
+    }
+
+    #[test]
+    fn test_is_prime_strong_pseudoprimes() {
+        // Strong pseudoprimes to the base sets {2}, {2, 3}, {2, 3, 5}, {2, 3, 5, 7}, {2, 7, 61} and the first nine primes.
+        for n in [
+            2047u64,
+            1_373_653,
+            25_326_001,
+            3_215_031_751,
+            4_759_123_141,
+            3_825_123_056_546_413_051,
+        ] {
+            assert!(!is_prime_u64(n), "n = {n}");
+        }
+        assert!(is_strong_probable_prime(4_759_123_141, 61));
+        assert!([2, 3, 5, 7, 11, 13, 17, 19, 23]
+            .iter()
+            .all(|&base| is_strong_probable_prime(3_825_123_056_546_413_051, base)));
+    }
+
+    #[test]
+    fn test_is_bpsw_prime() {
+        for n in 0..100_000u64 {
+            assert_eq!(is_bpsw_prime(n), is_prime_naive(n), "n = {n}");
+        }
+        let mut rng = SplitMix64::new(2024);
+        for _ in 0..20_000 {
+            let n = rng.next_u64() | 1;
+            assert_eq!(is_bpsw_prime(n), is_prime_u64(n), "n = {n}");
+        }
+        for n in [18_446_744_073_709_551_557u64, (1 << 61) - 1, 1_000_000_007] {
+            assert!(is_bpsw_prime(n));
+        }
+        assert!(!is_bpsw_prime(u64::MAX));
+        assert!(!is_bpsw_prime(4_294_967_291u64 * 4_294_967_291));
+    }
+
+    #[test]
+    fn test_is_bpsw_prime_pseudoprimes() {
+        // Strong pseudoprimes to base 2 are caught by the Lucas test.
+        for n in [
+            2047u64,
+            3277,
+            4033,
+            4681,
+            8321,
+            3_215_031_751,
+            3_825_123_056_546_413_051,
+        ] {
+            assert!(is_strong_probable_prime(n, 2));
+            assert!(!is_strong_lucas_probable_prime(n), "n = {n}");
+            assert!(!is_bpsw_prime(n));
+        }
+        // Strong Lucas pseudoprimes are caught by the base 2 test.
+        for n in [
+            5459u64, 5777, 10_877, 16_109, 18_971, 22_499, 24_569, 25_199, 40_309, 58_519,
+        ] {
+            assert!(is_strong_lucas_probable_prime(n), "n = {n}");
+            assert!(!is_strong_probable_prime(n, 2));
+            assert!(!is_bpsw_prime(n));
+        }
+    }
+
+    #[test]
+    fn test_is_probable_prime_u128() {
+        // The largest prime below 2^128, and the Mersenne primes 2^127 - 1 and 2^89 - 1.
+        for n in [u128::MAX - 158, (1 << 127) - 1, (1 << 89) - 1] {
+            assert!(is_probable_prime_u128(n, 32), "n = {n}");
+        }
+        let p = 18_446_744_073_709_551_557u128;
+        for n in [p * p, p * ((1 << 61) - 1), u128::MAX, (1 << 64) + 1, 3 * p] {
+            assert!(!is_probable_prime_u128(n, 32), "n = {n}");
+        }
+        for n in 0..10_000u128 {
+            assert_eq!(is_probable_prime_u128(n, 32), is_prime_naive(n as u64));
+        }
+        let mut rng = SplitMix64::new(347);
+        for _ in 0..1000 {
+            let (a, b) = (rng.next_u64() as u128, rng.next_u64() as u128);
+            let n = (rng.next_u64() as u128).max(1);
+            assert_eq!(mul_mod_u128(a % n, b % n, n), a * b % n);
+        }
+    }
+
+    #[test]
+    fn test_is_probable_prime_with() {
+        let mut rng = SplitMix64::new(42);
+        assert!(is_probable_prime_with(104_729u32, 10, || rng.next_u64()));
+        assert!(!is_probable_prime_with(104_730u32, 10, || rng.next_u64()));
+    }
+}
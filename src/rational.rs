@@ -0,0 +1,131 @@
+use crate::egcd::remainder_sequence;
+use crate::{Constrain, Egcd, Integer, Modulus};
+
+/// Recover the fraction `p/q` congruent to `x` modulo `m` with `|p|, q ≤ N`, where `N` is the largest integer with `2N² < m`.
+/// Such a fraction is unique when it exists, so a residue computed from a small rational number gives that number back.
+/// Returns `(p, q)` with `q > 0` and `gcd(p, q) = 1`, or `None` if no such fraction exists.
+pub fn rational_reconstruct<T, M>(x: T, m: M) -> Option<(T, T)>
+where
+    T: Egcd + TryFrom<M>,
+    M: Modulus<T>,
+{
+    let m_t: T = m.cast();
+    let two = T::one() + T::one();
+    let bound = floor_sqrt((m_t - T::one()) / two);
+    rational_reconstruct_bounded(x, m, bound, bound)
+}
+
+/// Recover a fraction `p/q` congruent to `x` modulo `m` with `|p| ≤ numerator_bound` and `0 < q ≤ denominator_bound`,
+/// with Wang's algorithm: the extended Euclidean algorithm on `m` and `x`, stopped at the first remainder within the numerator bound.
+/// The fraction is unique if `2 · numerator_bound · denominator_bound < m`; otherwise another one may exist.
+/// Returns `(p, q)` with `q > 0` and `gcd(p, q) = 1`, or `None` if the algorithm finds no such fraction.
+pub fn rational_reconstruct_bounded<T, M>(
+    x: T,
+    m: M,
+    numerator_bound: T,
+    denominator_bound: T,
+) -> Option<(T, T)>
+where
+    T: Egcd + TryFrom<M>,
+    M: Modulus<T>,
+{
+    let x = x.constrain(m);
+    // Starting from `(x, m)`, the first step swaps the two, and the coefficients of `x` become those of the fractions:
+    // each remainder `r` with coefficient `t` satisfies `r ≡ t · x (mod m)`.
+    let [_, (p, q)] = remainder_sequence(x, m.cast(), |r| r > numerator_bound);
+    if p > numerator_bound || q == T::zero() || q.abs() > denominator_bound || p.gcd(q) != T::one()
+    {
+        return None;
+    }
+    Some(if q < T::zero() {
+        (T::zero() - p, T::zero() - q)
+    } else {
+        (p, q)
+    })
+}
+
+/// The integer square root of a non-negative integer, by Newton's iteration from above.
+fn floor_sqrt<T: Integer + PartialOrd>(n: T) -> T {
+    let two = T::one() + T::one();
+    if n < two {
+        return n;
+    }
+    let mut x = n / two + T::one();
+    loop {
+        let y = (x + n / x) / two;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DivMod, Gcd};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_floor_sqrt() {
+        for n in 0u64..10_000 {
+            assert_eq!(floor_sqrt(n), n.isqrt());
+        }
+        assert_eq!(floor_sqrt(u64::MAX), u64::MAX.isqrt());
+        assert_eq!(floor_sqrt(i64::MAX), i64::MAX.isqrt());
+    }
+
+    #[test]
+    fn test_rational_reconstruct() {
+        let m = 1_000_000_007u64;
+        let third = 1i64.div_mod(3, m).unwrap();
+        assert_eq!(rational_reconstruct(third, m), Some((1, 3)));
+
+        let m = (1u64 << 61) - 1;
+        let x = (-22i128).div_mod(7, m).unwrap();
+        assert_eq!(rational_reconstruct(x, m), Some((-22, 7)));
+        assert_eq!(rational_reconstruct(x - m as i128, m), Some((-22, 7)));
+
+        assert_eq!(rational_reconstruct(0i64, 101u32), Some((0, 1)));
+        assert_eq!(rational_reconstruct(5i64, 101u32), Some((5, 1)));
+        assert_eq!(rational_reconstruct(50i64, 101u32), Some((-1, 2)));
+        // Modulo 101, the fractions have `|p|, q ≤ 7`, and none of them is congruent to 8.
+        assert_eq!(rational_reconstruct(8i64, 101u32), None);
+        // The fractions within the bound cover only part of the residues modulo 1000000007.
+        let m = 1_000_000_007u64;
+        assert_eq!(rational_reconstruct(909_925_047i64, m), None);
+        assert_eq!(rational_reconstruct(861_425_548i64, m), None);
+    }
+
+    #[test]
+    fn test_rational_reconstruct_round_trip() {
+        let m = 1_000_003u32;
+        for p in -700i64..=700 {
+            for q in (1i64..=700).step_by(7) {
+                if p.gcd(q) != 1 {
+                    continue;
+                }
+                let x = p.div_mod(q, m).unwrap();
+                assert_eq!(rational_reconstruct(x, m), Some((p, q)), "p/q = {p}/{q}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_rational_reconstruct_bounded() {
+        let m = 1_000_000_007u64;
+        let x = 355i64.div_mod(113, m).unwrap();
+        assert_eq!(
+            rational_reconstruct_bounded(x, m, 1000, 200),
+            Some((355, 113))
+        );
+        assert_eq!(rational_reconstruct_bounded(x, m, 1000, 100), None);
+        assert_eq!(rational_reconstruct_bounded(x, m, 300, 1000), None);
+        // With a large numerator bound, integers up to it are recovered.
+        let x = 987_654i64;
+        assert_eq!(
+            rational_reconstruct_bounded(x, m, 1_000_000, 500),
+            Some((987_654, 1))
+        );
+    }
+}
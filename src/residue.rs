@@ -0,0 +1,651 @@
+use num_traits::{CheckedAdd, CheckedMul, Inv, Pow, Signed};
+use std::cmp::Ordering;
+use std::fmt;
+
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::checked::{add_canonical, constrain_canonical, mul_canonical, sub_canonical};
+use crate::{Constrain, ConstrainSymmetric, Integer, Invert, Modulus};
+
+/// An error constructing a residue from its parts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResidueError {
+    /// The modulus is zero.
+    ZeroModulus,
+    /// The value is not in `[0, m)`.
+    OutOfRange,
+}
+
+impl fmt::Display for ResidueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResidueError::ZeroModulus => write!(f, "modulus is zero"),
+            ResidueError::OutOfRange => write!(f, "value is out of range for the modulus"),
+        }
+    }
+}
+
+impl std::error::Error for ResidueError {}
+
+/// An error parsing a residue.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseResidueError {
+    /// The value or the modulus is not a valid integer, or there is trailing text.
+    InvalidInteger,
+    /// No modulus was given, or `mod` is not followed by one.
+    MissingModulus,
+    /// The modulus is zero.
+    ZeroModulus,
+    /// The text gives a modulus other than the expected one.
+    ModulusMismatch,
+}
+
+impl fmt::Display for ParseResidueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseResidueError::InvalidInteger => write!(f, "invalid integer"),
+            ParseResidueError::MissingModulus => write!(f, "missing modulus"),
+            ParseResidueError::ZeroModulus => write!(f, "modulus is zero"),
+            ParseResidueError::ModulusMismatch => {
+                write!(f, "modulus differs from the expected one")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseResidueError {}
+
+/// Split `a`, `a mod m` or `a (mod m)` into the text of the value and of the modulus, if any,
+/// trimming whitespace around every part.
+pub(crate) fn split_congruence(s: &str) -> Result<(&str, Option<&str>), ParseResidueError> {
+    let (value, modulus) = match s.split_once('(') {
+        Some((value, rest)) => {
+            let inner = rest
+                .trim_end()
+                .strip_suffix(')')
+                .ok_or(ParseResidueError::InvalidInteger)?;
+            let modulus = inner
+                .trim_start()
+                .strip_prefix("mod")
+                .ok_or(ParseResidueError::InvalidInteger)?;
+            (value, Some(modulus))
+        }
+        None => match s.split_once("mod") {
+            Some((value, modulus)) => (value, Some(modulus)),
+            None => (s, None),
+        },
+    };
+    match modulus.map(str::trim) {
+        Some("") => Err(ParseResidueError::MissingModulus),
+        modulus => Ok((value.trim(), modulus)),
+    }
+}
+
+/// Parse an integer, mapping any failure to `ParseResidueError::InvalidInteger`.
+pub(crate) fn parse_integer<T: FromStr>(s: &str) -> Result<T, ParseResidueError> {
+    s.parse().map_err(|_| ParseResidueError::InvalidInteger)
+}
+
+/// An integer modulo a modulus chosen at run time, stored in canonical form in `[0, m)`.
+/// Operations between residues panic if their moduli differ.
+///
+/// Every constructor and operation keeps the value canonical, so congruent residues are equal and hash identically.
+/// Residues are ordered by modulus first and then by value, so that those with the same modulus compare by canonical value
+/// and those with different moduli still have a consistent total order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Residue<T, M> {
+    value: T,
+    modulus: M,
+}
+
+impl<T, M> Residue<T, M>
+where
+    T: Integer + TryFrom<M> + CheckedAdd + CheckedMul,
+    M: Modulus<T>,
+{
+    /// Create a residue, constraining the value to the modulus.
+    /// Panics if the modulus is zero.
+    pub fn new(value: T, modulus: M) -> Self {
+        assert!(modulus != M::zero(), "the modulus must be nonzero");
+        Self {
+            value: constrain_canonical(value, modulus.cast()),
+            modulus,
+        }
+    }
+
+    /// Create a residue from a value that is already canonical, validating both parts instead of constraining,
+    /// as when reading a residue back from storage.
+    pub fn from_parts(value: T, modulus: M) -> Result<Self, ResidueError> {
+        if modulus == M::zero() {
+            return Err(ResidueError::ZeroModulus);
+        }
+        if constrain_canonical(value, modulus.cast()) != value {
+            return Err(ResidueError::OutOfRange);
+        }
+        Ok(Self { value, modulus })
+    }
+
+    /// Parse `a`, `a mod m` or `a (mod m)` as a residue modulo the given modulus, constraining the value.
+    /// A modulus in the text must equal the given one.
+    pub fn parse_with_modulus(s: &str, modulus: M) -> Result<Self, ParseResidueError>
+    where
+        T: FromStr,
+        M: FromStr,
+    {
+        if modulus == M::zero() {
+            return Err(ParseResidueError::ZeroModulus);
+        }
+        let (value, text_modulus) = split_congruence(s)?;
+        if let Some(text_modulus) = text_modulus {
+            if parse_integer::<M>(text_modulus)? != modulus {
+                return Err(ParseResidueError::ModulusMismatch);
+            }
+        }
+        Ok(Self::new(parse_integer(value)?, modulus))
+    }
+
+    /// The canonical value in `[0, m)`.
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    /// The modulus.
+    pub fn modulus(&self) -> M {
+        self.modulus
+    }
+
+    /// The representative in the symmetric range `(-m/2, m/2]`, as `constrain_symmetric`.
+    pub fn to_symmetric(&self) -> T
+    where
+        T: Signed + PartialOrd,
+    {
+        self.value.constrain_symmetric(self.modulus)
+    }
+
+    /// Raise the residue to a non-negative power.
+    pub fn pow(self, exponent: u64) -> Self {
+        let mut result = Self::new(T::one(), self.modulus);
+        for i in (0..u64::BITS - exponent.leading_zeros()).rev() {
+            result = result * result;
+            if (exponent >> i) & 1 == 1 {
+                result *= self;
+            }
+        }
+        result
+    }
+
+    fn with_value(self, value: T) -> Self {
+        Self {
+            value,
+            modulus: self.modulus,
+        }
+    }
+
+    fn check(&self, rhs: &Self) {
+        assert!(self.modulus == rhs.modulus, "the moduli must be equal");
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<T, M> Residue<T, M> {
+    pub(crate) fn value_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+/// Format the congruence `a ≡ b (mod m)` for diagnostics, with both sides constrained to the modulus.
+pub fn format_congruence<T, M>(a: T, b: T, modulus: M) -> String
+where
+    T: Integer + TryFrom<M> + fmt::Display,
+    M: Modulus<T> + fmt::Display,
+{
+    format!(
+        "{} ≡ {} (mod {modulus})",
+        a.constrain(modulus),
+        b.constrain(modulus)
+    )
+}
+
+impl<T, M> Residue<T, M>
+where
+    T: Invert + TryFrom<M> + CheckedAdd + CheckedMul,
+    M: Modulus<T>,
+{
+    /// The inverse of the residue, or `None` if it is not a unit.
+    pub fn inverse(self) -> Option<Self> {
+        self.value.invert(self.modulus).map(|v| self.with_value(v))
+    }
+
+    /// Divide by another residue, or return `None` if the divisor is not a unit.
+    /// Panics if the moduli differ.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        self.check(&rhs);
+        rhs.inverse().map(|inverse| self * inverse)
+    }
+}
+
+impl<T: fmt::Display, M: fmt::Display> fmt::Display for Residue<T, M> {
+    /// Format the canonical value, or `a (mod m)` with the alternate flag.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "{} (mod {})", self.value, self.modulus)
+        } else {
+            fmt::Display::fmt(&self.value, f)
+        }
+    }
+}
+
+impl<T: Ord, M: Ord> PartialOrd for Residue<T, M> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord, M: Ord> Ord for Residue<T, M> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.modulus, &self.value).cmp(&(&other.modulus, &other.value))
+    }
+}
+
+impl<T, M> FromStr for Residue<T, M>
+where
+    T: Integer + TryFrom<M> + CheckedAdd + CheckedMul + FromStr,
+    M: Modulus<T> + FromStr,
+{
+    type Err = ParseResidueError;
+
+    /// Parse `a mod m` or `a (mod m)`, constraining the value, as written by the alternate `Display` format.
+    fn from_str(s: &str) -> Result<Self, ParseResidueError> {
+        let (value, modulus) = split_congruence(s)?;
+        let modulus: M = parse_integer(modulus.ok_or(ParseResidueError::MissingModulus)?)?;
+        if modulus == M::zero() {
+            return Err(ParseResidueError::ZeroModulus);
+        }
+        Ok(Self::new(parse_integer(value)?, modulus))
+    }
+}
+
+impl<T, M> Add for Residue<T, M>
+where
+    T: Integer + TryFrom<M> + CheckedAdd + CheckedMul,
+    M: Modulus<T>,
+{
+    type Output = Self;
+
+    /// Panics if the moduli differ.
+    fn add(self, rhs: Self) -> Self {
+        self.check(&rhs);
+        self.with_value(add_canonical(self.value, rhs.value, self.modulus.cast()))
+    }
+}
+
+impl<T, M> Sub for Residue<T, M>
+where
+    T: Integer + TryFrom<M> + CheckedAdd + CheckedMul,
+    M: Modulus<T>,
+{
+    type Output = Self;
+
+    /// Panics if the moduli differ.
+    fn sub(self, rhs: Self) -> Self {
+        self.check(&rhs);
+        self.with_value(sub_canonical(self.value, rhs.value, self.modulus.cast()))
+    }
+}
+
+impl<T, M> Mul for Residue<T, M>
+where
+    T: Integer + TryFrom<M> + CheckedAdd + CheckedMul,
+    M: Modulus<T>,
+{
+    type Output = Self;
+
+    /// Panics if the moduli differ.
+    fn mul(self, rhs: Self) -> Self {
+        self.check(&rhs);
+        self.with_value(mul_canonical(self.value, rhs.value, self.modulus.cast()))
+    }
+}
+
+impl<T, M> Div for Residue<T, M>
+where
+    T: Invert + TryFrom<M> + CheckedAdd + CheckedMul,
+    M: Modulus<T>,
+{
+    type Output = Self;
+
+    /// Panics if the moduli differ or the divisor is not a unit; see `checked_div`.
+    fn div(self, rhs: Self) -> Self {
+        self.checked_div(rhs)
+            .expect("the divisor must be invertible")
+    }
+}
+
+impl<T, M> Neg for Residue<T, M>
+where
+    T: Integer + TryFrom<M> + CheckedAdd + CheckedMul,
+    M: Modulus<T>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.with_value(sub_canonical(T::zero(), self.value, self.modulus.cast()))
+    }
+}
+
+impl<T, M> Inv for Residue<T, M>
+where
+    T: Invert + TryFrom<M> + CheckedAdd + CheckedMul,
+    M: Modulus<T>,
+{
+    type Output = Self;
+
+    /// Panics if the residue is not a unit; see `inverse`.
+    fn inv(self) -> Self {
+        self.inverse().expect("the residue must be invertible")
+    }
+}
+
+impl<T, M> Pow<u64> for Residue<T, M>
+where
+    T: Integer + TryFrom<M> + CheckedAdd + CheckedMul,
+    M: Modulus<T>,
+{
+    type Output = Self;
+
+    fn pow(self, exponent: u64) -> Self {
+        Residue::pow(self, exponent)
+    }
+}
+
+macro_rules! assign {
+    ($($trait:ident $method:ident $op:ident $bound:ident),*) => {$(
+        impl<T, M> $trait for Residue<T, M>
+        where
+            T: $bound + TryFrom<M> + CheckedAdd + CheckedMul,
+            M: Modulus<T>,
+        {
+            fn $method(&mut self, rhs: Self) {
+                *self = self.$op(rhs);
+            }
+        }
+    )*};
+}
+
+assign!(
+    AddAssign add_assign add Integer,
+    SubAssign sub_assign sub Integer,
+    MulAssign mul_assign mul Integer,
+    DivAssign div_assign div Invert
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PowMod;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_residue_new() {
+        let r = Residue::new(-3i32, 7u32);
+        assert_eq!(r.value(), 4);
+        assert_eq!(r.modulus(), 7);
+        assert_eq!(r, Residue::new(11, 7));
+        assert_eq!(Residue::new(5u64, 1u64).value(), 0);
+    }
+
+    #[test]
+    fn test_residue_to_symmetric() {
+        assert_eq!(Residue::new(6i32, 7u32).to_symmetric(), -1);
+        assert_eq!(Residue::new(-4i32, 8u32).to_symmetric(), 4);
+        assert_eq!(Residue::new(i64::MIN, u64::MAX >> 1).to_symmetric(), -1);
+    }
+
+    #[test]
+    fn test_residue_hash() {
+        let set: HashSet<_> = [Residue::new(-3i32, 7u32), Residue::new(4, 7)].into();
+        assert_eq!(set.len(), 1);
+        assert!(set.contains(&Residue::new(11, 7)));
+        let set: HashSet<_> = [Residue::new(4i32, 7u32), Residue::new(4, 8)].into();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_residue_ord() {
+        assert!(Residue::new(-1i32, 7u32) > Residue::new(5, 7));
+        assert!(Residue::new(7i32, 7u32) < Residue::new(1, 7));
+        assert_eq!(
+            Residue::new(-3i32, 7u32).cmp(&Residue::new(4, 7)),
+            Ordering::Equal
+        );
+        // Across moduli, the smaller modulus comes first regardless of the values.
+        assert!(Residue::new(6i32, 7u32) < Residue::new(0, 8));
+        let mut residues = vec![
+            Residue::new(1i32, 8u32),
+            Residue::new(5, 7),
+            Residue::new(0, 8),
+            Residue::new(2, 7),
+        ];
+        residues.sort();
+        assert_eq!(
+            residues,
+            [
+                Residue::new(2, 7),
+                Residue::new(5, 7),
+                Residue::new(0, 8),
+                Residue::new(1, 8)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_residue_from_parts() {
+        assert_eq!(Residue::from_parts(4i32, 7u32), Ok(Residue::new(4, 7)));
+        assert_eq!(Residue::from_parts(0i32, 1u32), Ok(Residue::new(0, 1)));
+        assert_eq!(
+            Residue::from_parts(7i32, 7u32),
+            Err(ResidueError::OutOfRange)
+        );
+        assert_eq!(
+            Residue::from_parts(-1i32, 7u32),
+            Err(ResidueError::OutOfRange)
+        );
+        assert_eq!(
+            Residue::from_parts(0i32, 0u32),
+            Err(ResidueError::ZeroModulus)
+        );
+        assert_eq!(
+            ResidueError::OutOfRange.to_string(),
+            "value is out of range for the modulus"
+        );
+    }
+
+    #[test]
+    fn test_residue_format() {
+        let r = Residue::new(-3i32, 7u32);
+        assert_eq!(r.to_string(), "4");
+        assert_eq!(format!("{r:#}"), "4 (mod 7)");
+        assert_eq!(format!("{r:>3}"), "  4");
+        assert_eq!(format!("{r:?}"), "Residue { value: 4, modulus: 7 }");
+        assert_eq!(format!("{:#}", Residue::new(12i64, 4u8)), "0 (mod 4)");
+        assert_eq!(format!("{:#}", Residue::new(5u64, 1u64)), "0 (mod 1)");
+        assert_eq!(format!("{:#}", Residue::new(9u64, 10u64)), "9 (mod 10)");
+    }
+
+    #[test]
+    fn test_residue_from_str() {
+        let expected = Ok(Residue::new(17i64, 23u32));
+        assert_eq!("17 mod 23".parse(), expected);
+        assert_eq!("17 (mod 23)".parse(), expected);
+        assert_eq!("  17   (  mod 23 )  ".parse(), expected);
+        assert_eq!("17mod23".parse(), expected);
+        assert_eq!("40 mod 23".parse(), expected);
+        assert_eq!("-5 mod 7".parse(), Ok(Residue::new(2i32, 7u8)));
+        assert_eq!("0 (mod 1)".parse(), Ok(Residue::new(0u64, 1u64)));
+        type R = Result<Residue<i32, u32>, ParseResidueError>;
+        assert_eq!(
+            "17".parse::<Residue<i32, u32>>(),
+            Err(ParseResidueError::MissingModulus)
+        );
+        assert_eq!(
+            "17 mod".parse() as R,
+            Err(ParseResidueError::MissingModulus)
+        );
+        assert_eq!(
+            "17 (mod )".parse() as R,
+            Err(ParseResidueError::MissingModulus)
+        );
+        assert_eq!("17 mod 0".parse() as R, Err(ParseResidueError::ZeroModulus));
+        assert_eq!(
+            "x mod 7".parse() as R,
+            Err(ParseResidueError::InvalidInteger)
+        );
+        assert_eq!(
+            "17 mod 7 x".parse() as R,
+            Err(ParseResidueError::InvalidInteger)
+        );
+        assert_eq!(
+            "17 (mod 7) x".parse() as R,
+            Err(ParseResidueError::InvalidInteger)
+        );
+        assert_eq!(
+            "17 (mod 7".parse() as R,
+            Err(ParseResidueError::InvalidInteger)
+        );
+        assert_eq!(
+            "17 (7)".parse() as R,
+            Err(ParseResidueError::InvalidInteger)
+        );
+        assert_eq!(
+            "17 mod -7".parse() as R,
+            Err(ParseResidueError::InvalidInteger)
+        );
+        assert_eq!("".parse() as R, Err(ParseResidueError::MissingModulus));
+        assert_eq!(
+            ParseResidueError::MissingModulus.to_string(),
+            "missing modulus"
+        );
+    }
+
+    #[test]
+    fn test_residue_parse_with_modulus() {
+        assert_eq!(
+            Residue::parse_with_modulus(" -5 ", 7u32),
+            Ok(Residue::new(2i64, 7))
+        );
+        assert_eq!(
+            Residue::parse_with_modulus("9 mod 7", 7u32),
+            Ok(Residue::new(2i64, 7))
+        );
+        assert_eq!(
+            Residue::<i64, u32>::parse_with_modulus("9 mod 8", 7),
+            Err(ParseResidueError::ModulusMismatch)
+        );
+        assert_eq!(
+            Residue::<i64, u32>::parse_with_modulus("9", 0),
+            Err(ParseResidueError::ZeroModulus)
+        );
+        assert_eq!(
+            Residue::<u32, u32>::parse_with_modulus("-5", 7),
+            Err(ParseResidueError::InvalidInteger)
+        );
+    }
+
+    #[test]
+    fn test_residue_round_trip() {
+        for (value, modulus) in [(-3i64, 7u32), (0, 1), (12, 4), (1_000_000, 999_983)] {
+            let r = Residue::new(value, modulus);
+            assert_eq!(format!("{r:#}").parse(), Ok(r));
+            assert_eq!(Residue::parse_with_modulus(&r.to_string(), modulus), Ok(r));
+        }
+    }
+
+    #[test]
+    fn test_format_congruence() {
+        assert_eq!(format_congruence(-3i32, 11, 7u32), "4 ≡ 4 (mod 7)");
+        assert_eq!(format_congruence(0i64, -1, 5u8), "0 ≡ 4 (mod 5)");
+        assert_eq!(format_congruence(3u16, 8, 1u16), "0 ≡ 0 (mod 1)");
+    }
+
+    #[test]
+    fn test_residue_arithmetic() {
+        let (a, b) = (Residue::new(5i64, 13u32), Residue::new(-4i64, 13u32));
+        assert_eq!((a + b).value(), 1);
+        assert_eq!((a - b).value(), 9);
+        assert_eq!((a * b).value(), 6);
+        assert_eq!((-a).value(), 8);
+        assert_eq!((-Residue::new(0i64, 13u32)).value(), 0);
+        assert_eq!((a / b) * b, a);
+        assert_eq!(a.inverse().map(|x| x.value()), Some(8));
+        assert_eq!(a.pow(100).value(), 5i64.pow_mod(100, 13u32));
+        assert_eq!(a.pow(0).value(), 1);
+        let mut c = a;
+        c += b;
+        c *= b;
+        c -= a;
+        c /= a;
+        assert_eq!(c, (a + b) * b / a - Residue::new(1, 13));
+    }
+
+    #[test]
+    fn test_residue_arithmetic_near_max() {
+        let m = u64::MAX - 58;
+        let a = Residue::new(m - 1, m);
+        assert_eq!((a + a).value(), m - 2);
+        assert_eq!((a - Residue::new(1, m)).value(), m - 2);
+        assert_eq!((Residue::new(1, m) - a).value(), 2);
+        assert_eq!((-a).value(), 1);
+        assert_eq!(Residue::new(u64::MAX, m).value(), 58);
+
+        let m = (1u64 << 61) - 1;
+        let (x, y) = (m - 12345, m - 67890);
+        let expected = (u128::from(x) * u128::from(y) % u128::from(m)) as u64;
+        assert_eq!((Residue::new(x, m) * Residue::new(y, m)).value(), expected);
+
+        let m = i64::MAX as u64;
+        let a = Residue::new(i64::MIN, m);
+        assert_eq!(a.value(), i64::MAX - 1);
+        assert_eq!((a + a).value(), i64::MAX - 2);
+        assert_eq!((a * a).value(), 1);
+
+        let m = u128::MAX - 158;
+        let a = Residue::new(m - 1, m);
+        assert_eq!((a + a).value(), m - 2);
+        assert_eq!((a * a).value(), 1);
+    }
+
+    #[test]
+    fn test_residue_pow_inv() {
+        let a = Residue::new(10i64, 1_000_000_007u64);
+        assert_eq!(
+            Pow::pow(a, 12345).value(),
+            10i64.pow_mod(12345, 1_000_000_007u64)
+        );
+        assert_eq!(a.inv() * a, Residue::new(1, 1_000_000_007));
+    }
+
+    #[test]
+    fn test_residue_not_invertible() {
+        let (a, b) = (Residue::new(3i32, 12u8), Residue::new(4i32, 12u8));
+        assert_eq!(b.inverse(), None);
+        assert_eq!(a.checked_div(b), None);
+        assert_eq!(
+            b.checked_div(Residue::new(5, 12)),
+            Some(Residue::new(8, 12))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the moduli must be equal")]
+    fn test_residue_mismatch() {
+        let _ = Residue::new(1i32, 7u32) + Residue::new(1, 11);
+    }
+
+    #[test]
+    #[should_panic(expected = "the divisor must be invertible")]
+    fn test_residue_div_by_zero() {
+        let _ = Residue::new(1i32, 7u32) / Residue::new(0, 7);
+    }
+}
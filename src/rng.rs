@@ -0,0 +1,182 @@
+use std::fmt::Debug;
+
+use crate::{binary_gcd, Invert};
+
+/// A source of uniformly distributed 64-bit values, for the functions that sample residues.
+/// Implement it for the generator of your choice to use that generator instead of `SplitMix64`.
+pub trait RandomSource {
+    /// Generate the next uniformly distributed value.
+    fn next_u64(&mut self) -> u64;
+}
+
+/// A small and fast pseudo-random generator, used where the crate needs random-looking values
+/// such as Miller–Rabin bases. It is not cryptographically secure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    /// Create a generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Generate the next pseudo-random value.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl RandomSource for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        SplitMix64::next_u64(self)
+    }
+}
+
+/// Sample a residue uniformly from `[0, m)` by rejection sampling.
+/// The values below `2^64 mod m` are rejected, so that every residue has exactly the same number of preimages;
+/// this takes fewer than two draws on average for any modulus. Panics if `m` is zero.
+pub fn random_residue<T, R>(rng: &mut R, m: T) -> T
+where
+    T: Into<u64> + TryFrom<u64>,
+    <T as TryFrom<u64>>::Error: Debug,
+    R: RandomSource + ?Sized,
+{
+    let m = m.into();
+    assert!(m != 0, "the modulus must be nonzero");
+    let threshold = m.wrapping_neg() % m;
+    loop {
+        let x = rng.next_u64();
+        if x >= threshold {
+            return T::try_from(x % m).expect("the residue is smaller than the modulus");
+        }
+    }
+}
+
+/// Sample a unit uniformly from the residues modulo `m` that are coprime to `m`, retrying until one is found.
+/// Panics if `m` is zero.
+pub fn random_unit<T, R>(rng: &mut R, m: T) -> T
+where
+    T: Into<u64> + TryFrom<u64>,
+    <T as TryFrom<u64>>::Error: Debug,
+    R: RandomSource + ?Sized,
+{
+    let m = m.into();
+    loop {
+        let x: u64 = random_residue(rng, m);
+        if binary_gcd(x, m) == 1 {
+            return T::try_from(x).expect("the residue is smaller than the modulus");
+        }
+    }
+}
+
+/// Sample a unit as in `random_unit` together with its inverse modulo `m`.
+/// Panics if `m` is zero.
+pub fn random_invertible_pair<T, R>(rng: &mut R, m: T) -> (T, T)
+where
+    T: Into<u64> + TryFrom<u64>,
+    <T as TryFrom<u64>>::Error: Debug,
+    R: RandomSource + ?Sized,
+{
+    let m = m.into();
+    let x: u64 = random_unit(rng, m);
+    let inverse = (x as i128).invert(m).expect("units are invertible") as u64;
+    let convert = |x| T::try_from(x).expect("the residue is smaller than the modulus");
+    (convert(x), convert(inverse))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_splitmix64() {
+        let mut rng = SplitMix64::new(1234567);
+        assert_eq!(rng.next_u64(), 6457827717110365317);
+        assert_eq!(rng.next_u64(), 3203168211198807973);
+    }
+
+    /// A source that replays fixed values.
+    struct Replay(std::vec::IntoIter<u64>);
+
+    impl RandomSource for Replay {
+        fn next_u64(&mut self) -> u64 {
+            self.0.next().expect("the replayed values ran out")
+        }
+    }
+
+    #[test]
+    fn test_random_residue_rejection() {
+        // `2^64 mod m = 2^63 - 1`, so the values below it would make the low residues twice as likely.
+        let m = (1u64 << 63) + 1;
+        let threshold = (1u64 << 63) - 1;
+        let mut rng = Replay(vec![0, threshold - 1, threshold, u64::MAX].into_iter());
+        assert_eq!(random_residue(&mut rng, m), threshold);
+        assert_eq!(random_residue(&mut rng, m), u64::MAX - m);
+        let mut rng = Replay(vec![u64::MAX].into_iter());
+        assert_eq!(random_residue(&mut rng, u64::MAX), 0);
+        assert_eq!(random_residue(&mut Replay(vec![12345].into_iter()), 1u8), 0);
+    }
+
+    #[test]
+    fn test_random_residue_uniform() {
+        let mut rng = SplitMix64::new(338);
+        let m = 7u32;
+        let samples = 70_000;
+        let mut counts = [0u32; 7];
+        for _ in 0..samples {
+            counts[random_residue(&mut rng, m) as usize] += 1;
+        }
+        // The chi-squared statistic has 6 degrees of freedom; 22.5 is its 0.1% critical value.
+        let expected = samples as f64 / m as f64;
+        let chi_squared: f64 = counts
+            .iter()
+            .map(|&c| (c as f64 - expected).powi(2) / expected)
+            .sum();
+        assert!(chi_squared < 22.5, "chi-squared = {chi_squared}");
+    }
+
+    #[test]
+    fn test_random_residue_boundary_bias() {
+        // Reducing raw values modulo `2^63 + 1` would put two thirds of the samples in the lower half.
+        let mut rng = SplitMix64::new(3380);
+        let m = (1u64 << 63) + 1;
+        let low = (0..10_000)
+            .filter(|_| random_residue(&mut rng, m) < m / 2)
+            .count();
+        assert!((4_700..5_300).contains(&low), "low = {low}");
+    }
+
+    #[test]
+    fn test_random_unit() {
+        let mut rng = SplitMix64::new(3381);
+        for m in [1u64, 2, 12, 1 << 40, 1_000_000_007] {
+            for _ in 0..100 {
+                let x = random_unit(&mut rng, m);
+                assert!(x < m && binary_gcd(x, m) == 1, "x = {x}, m = {m}");
+                let (y, inverse) = random_invertible_pair(&mut rng, m);
+                assert_eq!((y as u128 * inverse as u128) % m as u128, 1 % m as u128);
+            }
+        }
+        let mut counts = [0; 12];
+        for _ in 0..4000 {
+            counts[random_unit(&mut rng, 12u8) as usize] += 1;
+        }
+        assert!(counts
+            .iter()
+            .enumerate()
+            .all(|(x, &c)| (c > 0) == [1, 5, 7, 11].contains(&x)));
+    }
+
+    #[test]
+    #[should_panic(expected = "the modulus must be nonzero")]
+    fn test_random_residue_zero() {
+        random_residue(&mut SplitMix64::new(0), 0u32);
+    }
+}
@@ -0,0 +1,168 @@
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use crate::{discrete_log_ph_prime, primitive_root, solve_linear_congruence, Gcd, MulMod, PowMod};
+
+/// Find a solution of `x^k ≡ a (mod p)` for a prime `p`.
+/// A root exists if and only if `a ≡ 0` or `a^((p-1)/d) ≡ 1` with `d = gcd(k, p - 1)`.
+/// When `d = 1` the root is `a^(k⁻¹ mod p-1)`; otherwise it is found through the discrete logarithm
+/// of `a` to a primitive root, which is fast when `p - 1` is smooth.
+/// Returns `None` if there is no solution.
+pub fn nth_root_mod<T>(a: T, k: T, p: T) -> Option<T>
+where
+    T: Into<u64> + TryFrom<u64>,
+    <T as TryFrom<u64>>::Error: Debug,
+{
+    all_nth_roots(a, k, p).next()
+}
+
+/// Iterate over all solutions of `x^k ≡ a (mod p)` for a prime `p`.
+/// There are either none, exactly one for `a ≡ 0`, or `gcd(k, p - 1)` of them,
+/// which are a single root multiplied by the powers of a primitive `gcd(k, p - 1)`-th root of unity.
+/// They are not produced in increasing order.
+pub fn all_nth_roots<T>(a: T, k: T, p: T) -> NthRoots<T>
+where
+    T: Into<u64> + TryFrom<u64>,
+    <T as TryFrom<u64>>::Error: Debug,
+{
+    let p = p.into();
+    let (next, zeta, remaining) = nth_roots(a.into(), k.into(), p).unwrap_or((0, 0, 0));
+    NthRoots {
+        next,
+        zeta,
+        p,
+        remaining,
+        marker: PhantomData,
+    }
+}
+
+/// An iterator over the `k`-th roots modulo a prime.
+#[derive(Clone, Debug)]
+pub struct NthRoots<T> {
+    next: u64,
+    zeta: u64,
+    p: u64,
+    remaining: u64,
+    marker: PhantomData<T>,
+}
+
+impl<T> Iterator for NthRoots<T>
+where
+    T: TryFrom<u64>,
+    <T as TryFrom<u64>>::Error: Debug,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let current = self.next;
+        self.remaining -= 1;
+        self.next = (current as u128).mul_mod(self.zeta as u128, self.p) as u64;
+        Some(T::try_from(current).expect("the root is smaller than the modulus"))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.remaining as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+/// A root, a primitive root of unity to step through the others, and the number of roots.
+fn nth_roots(a: u64, k: u64, p: u64) -> Option<(u64, u64, u64)> {
+    if p < 2 {
+        return None;
+    }
+    let a = a % p;
+    if a == 0 {
+        return (k != 0).then_some((0, 0, 1));
+    }
+    let n = p - 1;
+    let d = k.gcd(n);
+    let pow = |x: u64, e: u64| (x as u128).pow_mod(e as u128, p) as u64;
+    if pow(a, n / d) != 1 {
+        return None;
+    }
+    if d == 1 {
+        let inverse = solve_linear_congruence(k as i128, 1, n)?.first();
+        return Some((pow(a, inverse as u64), 1, 1));
+    }
+    // With `a = g^l`, the roots are `g^y` for the solutions of `ky ≡ l (mod p - 1)`.
+    let g = primitive_root(p)?;
+    let l = discrete_log_ph_prime(g, a, p)?;
+    let y = solve_linear_congruence(k as i128, l as i128, n)?.first();
+    Some((pow(g, y as u64), pow(g, n / d), d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SqrtMod;
+    use pretty_assertions::assert_eq;
+
+    fn sorted_roots(a: u64, k: u64, p: u64) -> Vec<u64> {
+        let mut roots: Vec<u64> = all_nth_roots(a, k, p).collect();
+        roots.sort_unstable();
+        roots
+    }
+
+    fn brute_force(a: u64, k: u64, p: u64) -> Vec<u64> {
+        (0..p)
+            .filter(|&x| (x as u128).pow_mod(k as u128, p) as u64 == a % p)
+            .collect()
+    }
+
+    #[test]
+    fn test_cube_roots() {
+        // 11 ≡ 2 (mod 3): cubing is a bijection.
+        for a in 0..11 {
+            assert_eq!(sorted_roots(a, 3, 11), brute_force(a, 3, 11));
+            assert_eq!(all_nth_roots(a, 3u64, 11).count(), 1);
+        }
+        // 13 ≡ 1 (mod 3): cubes have three roots.
+        for a in 0..13 {
+            assert_eq!(sorted_roots(a, 3, 13), brute_force(a, 3, 13));
+        }
+        assert!([7, 8, 11].contains(&nth_root_mod(5u32, 3, 13).unwrap()));
+        assert_eq!(nth_root_mod(2u32, 3, 13), None);
+    }
+
+    #[test]
+    fn test_nth_roots_brute_force() {
+        for p in [2u64, 3, 5, 7, 17, 31, 61, 97, 101] {
+            for k in 1..2 * p {
+                for a in 0..p {
+                    assert_eq!(
+                        sorted_roots(a, k, p),
+                        brute_force(a, k, p),
+                        "x^{k} = {a} (mod {p})"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_square_roots_match_tonelli_shanks() {
+        let p = 998_244_353u32;
+        for a in [0u64, 1, 2, 3, 5, 10, 123_456_789] {
+            let expected = (a as i64).sqrt_mod(p);
+            let roots = sorted_roots(a, 2, p as u64);
+            assert_eq!(roots.first().map(|&x| x as i64), expected, "a = {a}");
+        }
+    }
+
+    #[test]
+    fn test_nth_root_large() {
+        let p = 998_244_353u64;
+        for k in [3u64, 7, 17, 119, 1 << 23] {
+            let x = 31_415_926u64;
+            let a = (x as u128).pow_mod(k as u128, p) as u64;
+            let root = nth_root_mod(a, k, p).unwrap();
+            assert_eq!((root as u128).pow_mod(k as u128, p) as u64, a);
+            assert_eq!(all_nth_roots(a, k, p).count() as u64, k.gcd(p - 1));
+        }
+        assert_eq!(nth_root_mod(3u64, 1 << 23, p), None);
+    }
+}
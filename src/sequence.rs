@@ -0,0 +1,372 @@
+use crate::{AddMod, Constrain, Integer, Invert, Modulus, MulMod, SubModConstrained};
+
+/// Compute the Fibonacci number `F(n)` modulo a modulus with the fast-doubling identities
+/// `F(2k) = F(k)(2F(k+1) - F(k))` and `F(2k+1) = F(k)² + F(k+1)²`, in `O(log n)` multiplications.
+pub fn fibonacci_mod<T, M>(n: u64, modulus: M) -> T
+where
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
+{
+    fibonacci_pair(n, modulus).0
+}
+
+/// Compute the Lucas number `L(n)` modulo a modulus as `2F(n+1) - F(n)`, with `L(0) = 2` and `L(1) = 1`.
+pub fn lucas_mod<T, M>(n: u64, modulus: M) -> T
+where
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
+{
+    let (f, g) = fibonacci_pair(n, modulus);
+    g.add_mod(g, modulus).sub_mod_constrained(f, modulus)
+}
+
+/// Compute the term `a_n` of the linear recurrence `a_n = c_1 a_{n-1} + ... + c_k a_{n-k}` modulo a modulus,
+/// given `coefficients = [c_1, ..., c_k]` and `initial = [a_0, ..., a_{k-1}]`.
+/// This is Kitamasa's method: `x^n` is reduced modulo the characteristic polynomial `x^k - c_1 x^{k-1} - ... - c_k`,
+/// and its coefficients weigh the initial terms, which takes `O(k² log n)` multiplications.
+/// Panics if the two slices have different lengths.
+pub fn linear_recurrence_kth<T, M>(coefficients: &[T], initial: &[T], n: u64, modulus: M) -> T
+where
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
+{
+    assert_eq!(
+        coefficients.len(),
+        initial.len(),
+        "one initial term is needed per coefficient"
+    );
+    let k = coefficients.len();
+    if k == 0 {
+        return T::zero();
+    }
+    if n < k as u64 {
+        return initial[n as usize].constrain(modulus);
+    }
+    let coefficients: Vec<T> = coefficients.iter().map(|c| c.constrain(modulus)).collect();
+    // Reduce a polynomial of degree below `2k - 1` modulo the characteristic polynomial, from the top.
+    let reduce = |mut product: Vec<T>| {
+        for d in (k..product.len()).rev() {
+            let top = product[d];
+            for (j, &c) in coefficients.iter().enumerate() {
+                product[d - j - 1] = product[d - j - 1].add_mod(top.mul_mod(c, modulus), modulus);
+            }
+        }
+        product.truncate(k);
+        product
+    };
+    let mut power = vec![T::zero(); k];
+    power[0] = T::one().constrain(modulus);
+    for i in (0..u64::BITS - n.leading_zeros()).rev() {
+        let mut square = vec![T::zero(); 2 * k - 1];
+        for (a, &x) in power.iter().enumerate() {
+            for (b, &y) in power.iter().enumerate() {
+                square[a + b] = square[a + b].add_mod(x.mul_mod(y, modulus), modulus);
+            }
+        }
+        power = reduce(square);
+        if (n >> i) & 1 == 1 {
+            power.insert(0, T::zero());
+            power = reduce(power);
+        }
+    }
+    power.iter().zip(initial).fold(T::zero(), |sum, (&r, &a)| {
+        sum.add_mod(r.mul_mod(a.constrain(modulus), modulus), modulus)
+    })
+}
+
+/// Find the shortest linear recurrence generating a sequence over the prime field `F_p` with the Berlekamp–Massey algorithm.
+/// The result `[c_1, ..., c_L]` satisfies `a_n = c_1 a_{n-1} + ... + c_L a_{n-L}` for every `n ≥ L` in the sequence,
+/// in the order taken by `linear_recurrence_kth`; the minimal polynomial is `x^L - c_1 x^{L-1} - ... - c_L`.
+/// A sequence of zeros, including the empty one, gives the empty recurrence.
+/// A recurrence of length `L` is determined uniquely by `2L` terms.
+pub fn berlekamp_massey<T, M>(sequence: &[T], p: M) -> Vec<T>
+where
+    T: Invert + TryFrom<M>,
+    M: Modulus<T>,
+{
+    let sequence: Vec<T> = sequence.iter().map(|a| a.constrain(p)).collect();
+    // The connection polynomials `1 + C_1 x + ...`, current and at the last length change.
+    let mut current = vec![T::one()];
+    let mut previous = vec![T::one()];
+    let (mut length, mut shift, mut previous_discrepancy) = (0, 1, T::one());
+    for n in 0..sequence.len() {
+        let discrepancy = (1..=length).fold(sequence[n], |d, i| {
+            d.add_mod(current[i].mul_mod(sequence[n - i], p), p)
+        });
+        if discrepancy == T::zero() {
+            shift += 1;
+            continue;
+        }
+        let factor = discrepancy.mul_mod(
+            previous_discrepancy
+                .invert(p)
+                .expect("nonzero in a prime field"),
+            p,
+        );
+        let snapshot = current.clone();
+        if current.len() < previous.len() + shift {
+            current.resize(previous.len() + shift, T::zero());
+        }
+        for (i, &b) in previous.iter().enumerate() {
+            current[i + shift] = current[i + shift].sub_mod_constrained(factor.mul_mod(b, p), p);
+        }
+        if 2 * length <= n {
+            length = n + 1 - length;
+            previous = snapshot;
+            previous_discrepancy = discrepancy;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+    }
+    current.resize(length + 1, T::zero());
+    current[1..]
+        .iter()
+        .map(|&c| T::zero().sub_mod_constrained(c, p))
+        .collect()
+}
+
+/// Compute `(F(n), F(n+1))` modulo a modulus, walking the bits of `n` from the top.
+fn fibonacci_pair<T, M>(n: u64, modulus: M) -> (T, T)
+where
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
+{
+    let (mut a, mut b) = (T::zero(), T::one().constrain(modulus));
+    for i in (0..u64::BITS - n.leading_zeros()).rev() {
+        // The subtraction `2F(k+1) - F(k)` is where a plain unsigned implementation would underflow.
+        let c = a.mul_mod(
+            b.add_mod(b, modulus).sub_mod_constrained(a, modulus),
+            modulus,
+        );
+        let d = a
+            .mul_mod(a, modulus)
+            .add_mod(b.mul_mod(b, modulus), modulus);
+        (a, b) = if (n >> i) & 1 == 1 {
+            (d, c.add_mod(d, modulus))
+        } else {
+            (c, d)
+        };
+    }
+    (a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_fibonacci_mod_small() {
+        let mut fibonacci = vec![0u64, 1];
+        for i in 2..90 {
+            fibonacci.push(fibonacci[i - 1] + fibonacci[i - 2]);
+        }
+        for (n, &f) in fibonacci.iter().enumerate() {
+            assert_eq!(fibonacci_mod::<u128, u64>(n as u64, u64::MAX), f as u128);
+            assert_eq!(
+                fibonacci_mod::<u32, u32>(n as u64, 1_000),
+                (f % 1_000) as u32
+            );
+            assert_eq!(fibonacci_mod::<i32, u32>(n as u64, 7), (f % 7) as i32);
+        }
+        assert_eq!(fibonacci_mod::<u64, u64>(10, 1_000), 55);
+        assert_eq!(fibonacci_mod::<u64, u64>(10, 1), 0);
+    }
+
+    #[test]
+    fn test_fibonacci_mod_large() {
+        assert_eq!(
+            fibonacci_mod::<u64, u64>(1_000_000_000_000, 1_000_000_007),
+            730_695_249
+        );
+        assert_eq!(
+            fibonacci_mod::<i64, u64>(1_000_000_000_000_000_000, 1_000_000_007),
+            209_783_453
+        );
+        assert_eq!(
+            fibonacci_mod::<u64, u64>(1_000_000_000_000_000_000, 998_244_353),
+            23_849_548
+        );
+    }
+
+    #[test]
+    fn test_fibonacci_doubling_identity() {
+        for modulus in [2u64, 10, 97, 1_000_000_007] {
+            for n in (0..1_000u64).chain([123_456_789, 1 << 40]) {
+                let f = fibonacci_mod::<u64, u64>(n, modulus);
+                let g = fibonacci_mod::<u64, u64>(n + 1, modulus);
+                let expected = f.mul_mod(
+                    g.add_mod(g, modulus).sub_mod_constrained(f, modulus),
+                    modulus,
+                );
+                assert_eq!(
+                    fibonacci_mod::<u64, u64>(2 * n, modulus),
+                    expected,
+                    "n = {n}, m = {modulus}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_lucas_mod() {
+        let expected = [2u64, 1, 3, 4, 7, 11, 18, 29, 47, 76, 123];
+        for (n, &l) in expected.iter().enumerate() {
+            assert_eq!(lucas_mod::<u64, u64>(n as u64, 1_000), l);
+        }
+        assert_eq!(
+            lucas_mod::<u64, u64>(1_000_000_000_000, 1_000_000_007),
+            687_187_671
+        );
+        assert_eq!(
+            lucas_mod::<i64, u64>(1_000_000_000_000_000_000, 998_244_353),
+            640_495_166
+        );
+        // L(n) = F(n-1) + F(n+1)
+        for n in 1..200 {
+            let f = fibonacci_mod::<u64, u64>(n - 1, 97)
+                .add_mod(fibonacci_mod::<u64, u64>(n + 1, 97), 97u64);
+            assert_eq!(lucas_mod::<u64, u64>(n, 97), f);
+        }
+    }
+
+    /// Compute `a_n` by raising the companion matrix to the `n`-th power.
+    fn matrix_power_term(coefficients: &[i64], initial: &[i64], n: u64, modulus: u64) -> i64 {
+        let k = coefficients.len();
+        let multiply = |x: &Vec<Vec<i64>>, y: &Vec<Vec<i64>>| {
+            let mut z = vec![vec![0i64; k]; k];
+            for i in 0..k {
+                for j in 0..k {
+                    for l in 0..k {
+                        z[i][j] = z[i][j].add_mod(x[i][l].mul_mod(y[l][j], modulus), modulus);
+                    }
+                }
+            }
+            z
+        };
+        let mut companion = vec![vec![0i64; k]; k];
+        companion[0] = coefficients.to_vec();
+        for i in 1..k {
+            companion[i][i - 1] = 1;
+        }
+        let mut result: Vec<Vec<i64>> = (0..k)
+            .map(|i| (0..k).map(|j| (i == j) as i64).collect())
+            .collect();
+        let mut n = n;
+        while n > 0 {
+            if n & 1 == 1 {
+                result = multiply(&result, &companion);
+            }
+            companion = multiply(&companion, &companion);
+            n >>= 1;
+        }
+        // The state vector is `[a_{k-1}, ..., a_0]`; its last entry after `n` steps is `a_n`.
+        (0..k).fold(0, |sum, j| {
+            sum.add_mod(
+                result[k - 1][j].mul_mod(initial[k - 1 - j], modulus),
+                modulus,
+            )
+        })
+    }
+
+    #[test]
+    fn test_linear_recurrence_fibonacci() {
+        for n in [0u64, 1, 2, 10, 1_000, 1_000_000_000_000] {
+            assert_eq!(
+                linear_recurrence_kth(&[1u64, 1], &[0, 1], n, 1_000_000_007u64),
+                fibonacci_mod(n, 1_000_000_007u64)
+            );
+        }
+    }
+
+    #[test]
+    fn test_linear_recurrence_matches_matrix_power() {
+        let cases: [(&[i64], &[i64]); 4] = [
+            (&[1, 1, 1], &[0, 0, 1]),
+            (&[2, -1], &[3, 5]),
+            (&[-1, 0, 4, -7], &[1, -2, 3, -4]),
+            (&[5], &[-3]),
+        ];
+        for (coefficients, initial) in cases {
+            for n in (0..50).chain([1_000_000, 1 << 40, u64::MAX]) {
+                for modulus in [2u64, 97, 1_000_000_007] {
+                    assert_eq!(
+                        linear_recurrence_kth(coefficients, initial, n, modulus),
+                        matrix_power_term(coefficients, initial, n, modulus),
+                        "{coefficients:?}, {initial:?}, n = {n}, m = {modulus}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_linear_recurrence_negative_coefficients() {
+        // a_n = 2a_{n-1} - a_{n-2} is the arithmetic progression 3 + 2n.
+        for n in 0..100u64 {
+            assert_eq!(
+                linear_recurrence_kth(&[2i64, -1], &[3, 5], n, 1_000u32),
+                ((3 + 2 * n) % 1_000) as i64
+            );
+        }
+        assert_eq!(linear_recurrence_kth::<i64, u32>(&[], &[], 5, 7), 0);
+    }
+
+    #[test]
+    fn test_berlekamp_massey_fibonacci() {
+        let sequence: Vec<i64> = (0..8).map(|n| fibonacci_mod(n, 1_000_000_007u64)).collect();
+        assert_eq!(berlekamp_massey(&sequence, 1_000_000_007u64), vec![1, 1]);
+    }
+
+    #[test]
+    fn test_berlekamp_massey_random() {
+        let p = 998_244_353u64;
+        let mut rng = crate::SplitMix64::new(316);
+        for _ in 0..20 {
+            let coefficients: Vec<i64> = (0..5).map(|_| (rng.next_u64() % p) as i64).collect();
+            let initial: Vec<i64> = (0..5).map(|_| (rng.next_u64() % p) as i64).collect();
+            let sequence: Vec<i64> = (0..10)
+                .map(|n| linear_recurrence_kth(&coefficients, &initial, n, p))
+                .collect();
+            let recovered = berlekamp_massey(&sequence, p);
+            assert_eq!(recovered, coefficients);
+            for n in 0..100 {
+                assert_eq!(
+                    linear_recurrence_kth(&recovered, &sequence[..5], n, p),
+                    linear_recurrence_kth(&coefficients, &initial, n, p)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_berlekamp_massey_reproduces_sequence() {
+        let p = 101u32;
+        let mut rng = crate::SplitMix64::new(3160);
+        for _ in 0..200 {
+            let sequence: Vec<i32> = (0..rng.next_u64() % 12)
+                .map(|_| (rng.next_u64() % 5) as i32)
+                .collect();
+            let recurrence = berlekamp_massey(&sequence, p);
+            assert!(
+                2 * recurrence.len() <= sequence.len() + 1 || recurrence.len() <= sequence.len()
+            );
+            for n in recurrence.len()..sequence.len() {
+                let predicted = (1..=recurrence.len()).fold(0, |sum, i| {
+                    sum.add_mod(recurrence[i - 1].mul_mod(sequence[n - i], p), p)
+                });
+                assert_eq!(predicted, sequence[n], "{sequence:?} with {recurrence:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_berlekamp_massey_zeros() {
+        assert_eq!(berlekamp_massey::<i64, u64>(&[], 7), vec![]);
+        assert_eq!(berlekamp_massey(&[0i64, 0, 0], 7u64), vec![]);
+        assert_eq!(berlekamp_massey(&[0i64, 0, 0, 1], 7u64).len(), 4);
+        assert_eq!(berlekamp_massey(&[0i64, 0, 1, 0, 0, 1], 7u64).len(), 3);
+        assert_eq!(berlekamp_massey(&[14i64, 7, 0], 7u64), vec![]);
+    }
+}
@@ -0,0 +1,226 @@
+use num_traits::{One, Zero};
+
+/// A linear sieve of the integers up to a limit, storing the smallest prime factor of each.
+/// It answers primality queries in `O(1)` and factorizes any integer up to the limit in `O(log n)`,
+/// which is the right tool when many integers need factoring, where `factorize` handles one integer at a time.
+/// The table is a single `Vec<u32>` of `limit + 1` entries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sieve {
+    smallest: Vec<u32>,
+}
+
+impl Sieve {
+    /// Sieve the integers in `[0, limit]` with the linear sieve, which marks every composite exactly once,
+    /// as its smallest prime factor times a cofactor.
+    pub fn new(limit: u32) -> Self {
+        let mut smallest = vec![0; limit as usize + 1];
+        let mut primes = Vec::new();
+        for i in 2..=limit as usize {
+            if smallest[i] == 0 {
+                smallest[i] = i as u32;
+                primes.push(i as u32);
+            }
+            for &p in &primes {
+                let multiple = i * p as usize;
+                if p > smallest[i] || multiple > limit as usize {
+                    break;
+                }
+                smallest[multiple] = p;
+            }
+        }
+        Self { smallest }
+    }
+
+    /// The largest integer covered by the sieve.
+    pub fn limit(&self) -> u32 {
+        (self.smallest.len() - 1) as u32
+    }
+
+    /// Check whether `n` is prime.
+    /// Panics if `n` exceeds the limit.
+    pub fn is_prime(&self, n: u32) -> bool {
+        self.smallest_prime_factor(n) == Some(n)
+    }
+
+    /// The smallest prime factor of `n`, or `None` for `0` and `1`.
+    /// Panics if `n` exceeds the limit.
+    pub fn smallest_prime_factor(&self, n: u32) -> Option<u32> {
+        match self.entry(n) {
+            0 => None,
+            p => Some(p),
+        }
+    }
+
+    /// Factorize `n` into `(prime, exponent)` pairs sorted by prime, dividing out smallest prime factors.
+    /// Both `0` and `1` have an empty factorization, as with `factorize`.
+    /// Panics if `n` exceeds the limit.
+    pub fn factorize(&self, mut n: u32) -> Vec<(u32, u32)> {
+        let mut factors: Vec<(u32, u32)> = Vec::new();
+        while let Some(p) = self.smallest_prime_factor(n) {
+            match factors.last_mut() {
+                Some((q, e)) if *q == p => *e += 1,
+                _ => factors.push((p, 1)),
+            }
+            n /= p;
+        }
+        factors
+    }
+
+    /// Iterate over the primes up to the limit in increasing order.
+    pub fn primes(&self) -> impl Iterator<Item = u32> + '_ {
+        self.smallest
+            .iter()
+            .enumerate()
+            .skip(2)
+            .filter(|&(n, &p)| p as usize == n)
+            .map(|(_, &p)| p)
+    }
+
+    /// Compute Euler's totient `φ(n)` for every `n` in `[0, limit]`, indexed by `n`, in `O(limit)`.
+    /// Each value follows from that of `n / p` for the smallest prime factor `p` of `n`:
+    /// it is multiplied by `p` if `p` still divides `n / p`, and by `p - 1` otherwise. As with `euler_phi`, `φ(0) = 0`.
+    pub fn totients(&self) -> Vec<u32> {
+        self.multiplicative(
+            |value: u32, p, repeated| {
+                if repeated {
+                    value * p
+                } else {
+                    value * (p - 1)
+                }
+            },
+        )
+    }
+
+    /// Compute the Möbius function `μ(n)` for every `n` in `[0, limit]`, indexed by `n`, in `O(limit)`.
+    /// As with `mobius`, `μ(0) = 0`.
+    pub fn mobius(&self) -> Vec<i8> {
+        self.multiplicative(|value: i8, _, repeated| if repeated { 0 } else { -value })
+    }
+
+    /// Evaluate a multiplicative function from `f(0) = 0` and `f(1) = 1`, deriving `f(n)` from `f(n / p)`
+    /// for the smallest prime factor `p` of `n` and whether `p` divides `n / p`.
+    fn multiplicative<V: Copy + Zero + One>(&self, step: impl Fn(V, u32, bool) -> V) -> Vec<V> {
+        let mut values = vec![V::zero(); self.smallest.len()];
+        if let Some(one) = values.get_mut(1) {
+            *one = V::one();
+        }
+        for n in 2..self.smallest.len() {
+            let p = self.smallest[n];
+            let m = n / p as usize;
+            values[n] = step(values[m], p, self.smallest[m] == p);
+        }
+        values
+    }
+
+    fn entry(&self, n: u32) -> u32 {
+        assert!(
+            n <= self.limit(),
+            "{n} exceeds the limit of the sieve, {}",
+            self.limit()
+        );
+        self.smallest[n as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{euler_phi, factorize, mobius, SplitMix64};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_primes() {
+        let sieve = Sieve::new(100);
+        assert_eq!(
+            sieve.primes().collect::<Vec<_>>(),
+            vec![
+                2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79,
+                83, 89, 97
+            ]
+        );
+        assert!(sieve.is_prime(97));
+        assert!(!sieve.is_prime(91));
+        assert!(!sieve.is_prime(1));
+        assert!(!sieve.is_prime(0));
+        assert_eq!(sieve.smallest_prime_factor(91), Some(7));
+        assert_eq!(Sieve::new(1_000_000).primes().count(), 78_498);
+    }
+
+    #[test]
+    fn test_factorize() {
+        let limit = 2_000_000;
+        let sieve = Sieve::new(limit);
+        let mut rng = SplitMix64::new(364);
+        for _ in 0..10_000 {
+            let n = (rng.next_u64() % (limit as u64 + 1)) as u32;
+            let expected: Vec<(u32, u32)> = factorize(n as u64)
+                .into_iter()
+                .map(|(p, k)| (p as u32, k))
+                .collect();
+            assert_eq!(sieve.factorize(n), expected, "n = {n}");
+        }
+        assert_eq!(sieve.factorize(limit), vec![(2, 7), (5, 6)]);
+        assert_eq!(sieve.factorize(1 << 20), vec![(2, 20)]);
+        assert_eq!(sieve.factorize(1_999_993), vec![(1_999_993, 1)]);
+    }
+
+    #[test]
+    fn test_totients() {
+        let sieve = Sieve::new(100_000);
+        let totients = sieve.totients();
+        assert_eq!(totients[..10], [0, 1, 1, 2, 2, 4, 2, 6, 4, 6]);
+        for (n, &phi) in totients.iter().enumerate() {
+            assert_eq!(phi as u64, euler_phi(n as u64), "n = {n}");
+        }
+    }
+
+    #[test]
+    fn test_mobius() {
+        let sieve = Sieve::new(100_000);
+        let mu = sieve.mobius();
+        assert_eq!(mu[..11], [0, 1, -1, -1, 0, -1, 1, -1, 0, 0, 1]);
+        for (n, &value) in mu.iter().enumerate() {
+            assert_eq!(value, mobius(n as u64), "n = {n}");
+        }
+        for n in [4usize, 12, 18, 49, 72, 1000, 99_225] {
+            assert_eq!(mu[n], 0, "n = {n}");
+        }
+        // The Mertens function, the partial sums of μ.
+        let mertens: Vec<i64> = mu
+            .iter()
+            .scan(0, |sum, &value| {
+                *sum += value as i64;
+                Some(*sum)
+            })
+            .collect();
+        assert_eq!(mertens[1..=10], [1, 0, -1, -1, -2, -1, -2, -2, -2, -1]);
+        assert_eq!(mertens[100], 1);
+        assert_eq!(mertens[1000], 2);
+        assert_eq!(mertens[10_000], -23);
+        assert_eq!(mertens[100_000], -48);
+    }
+
+    #[test]
+    fn test_small_limits() {
+        let sieve = Sieve::new(0);
+        assert_eq!(sieve.limit(), 0);
+        assert_eq!(sieve.totients(), vec![0]);
+        assert_eq!(sieve.mobius(), vec![0]);
+        assert_eq!(sieve.primes().next(), None);
+        assert_eq!(sieve.factorize(0), vec![]);
+        assert!(!sieve.is_prime(0));
+        let sieve = Sieve::new(1);
+        assert_eq!(sieve.primes().next(), None);
+        assert_eq!(sieve.totients(), vec![0, 1]);
+        assert_eq!(sieve.mobius(), vec![0, 1]);
+        assert_eq!(sieve.factorize(1), vec![]);
+        let sieve = Sieve::new(2);
+        assert_eq!(sieve.primes().collect::<Vec<_>>(), vec![2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "101 exceeds the limit of the sieve, 100")]
+    fn test_beyond_limit() {
+        Sieve::new(100).is_prime(101);
+    }
+}
@@ -0,0 +1,315 @@
+use num_traits::{CheckedAdd, Zero};
+
+use crate::{
+    parallel, AddMod, Constrain, Integer, Invert, Modulus, MulMod, SubMod, SubModConstrained, Widen,
+};
+
+/// Constrain every integer of a slice to a modulus in place.
+/// With the `parallel` feature, large slices are split into one chunk per thread.
+pub fn constrain_slice<T, M>(values: &mut [T], modulus: M)
+where
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
+{
+    let chunk = parallel::chunk_len(values.len(), 1);
+    parallel::map_chunks_mut(values, chunk, |_, values| {
+        for x in values.iter_mut() {
+            *x = x.constrain(modulus);
+        }
+    });
+}
+
+/// Add `rhs` to `values` element-wise modulo a modulus, in place.
+/// Panics if the slices differ in length.
+pub fn add_mod_slice<T, M>(values: &mut [T], rhs: &[T], modulus: M)
+where
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
+{
+    assert_eq!(values.len(), rhs.len(), "the slices differ in length");
+    for (x, &y) in values.iter_mut().zip(rhs) {
+        *x = x.add_mod(y, modulus);
+    }
+}
+
+/// Subtract `rhs` from `values` element-wise modulo a modulus, in place.
+/// Panics if the slices differ in length.
+pub fn sub_mod_slice<T, M>(values: &mut [T], rhs: &[T], modulus: M)
+where
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
+{
+    assert_eq!(values.len(), rhs.len(), "the slices differ in length");
+    for (x, &y) in values.iter_mut().zip(rhs) {
+        *x = x.sub_mod(y, modulus);
+    }
+}
+
+/// Multiply `values` by `rhs` element-wise modulo a modulus, in place.
+/// Panics if the slices differ in length.
+pub fn mul_mod_slice<T, M>(values: &mut [T], rhs: &[T], modulus: M)
+where
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
+{
+    assert_eq!(values.len(), rhs.len(), "the slices differ in length");
+    for (x, &y) in values.iter_mut().zip(rhs) {
+        *x = x.mul_mod(y, modulus);
+    }
+}
+
+/// Multiply every integer of a slice by a scalar modulo a modulus, in place.
+pub fn scale_mod_slice<T, M>(values: &mut [T], k: T, modulus: M)
+where
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
+{
+    let k = k.constrain(modulus);
+    for x in values.iter_mut() {
+        *x = x.mul_mod(k, modulus);
+    }
+}
+
+/// Compute the dot product `Σ a_i b_i` of two slices modulo a modulus; empty slices give zero.
+/// Products of constrained values are accumulated in the wider type, such as `u128` for `u64`,
+/// which is reduced only when the next product would overflow it, and once at the end.
+/// Panics if the slices differ in length.
+pub fn dot_mod<T, M>(a: &[T], b: &[T], modulus: M) -> T
+where
+    T: Widen + TryFrom<M>,
+    T::Wide: CheckedAdd + TryFrom<M>,
+    M: Modulus<T> + Modulus<T::Wide>,
+{
+    assert_eq!(a.len(), b.len(), "the slices differ in length");
+    let wide: T::Wide = modulus.cast();
+    let sum = a.iter().zip(b).fold(T::Wide::zero(), |sum, (&x, &y)| {
+        let product = x.widen().constrain(modulus) * y.widen().constrain(modulus);
+        // A reduced sum plus a product is below `m² + m`, which fits the wider type.
+        sum.checked_add(&product)
+            .unwrap_or_else(|| sum % wide + product)
+    });
+    T::narrow(sum % wide)
+}
+
+/// Compute the prefix sums of a slice modulo a modulus, starting with the empty sum:
+/// entry `i` is the sum of `values[..i]`, so there are `values.len() + 1` entries
+/// and the sum of `values[l..r]` is `range_sum(&prefix, l, r, modulus)`.
+pub fn prefix_sums_mod<T, M>(values: &[T], modulus: M) -> Vec<T>
+where
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
+{
+    let mut prefix = Vec::with_capacity(values.len() + 1);
+    prefix.push(T::zero());
+    for &x in values {
+        prefix.push(prefix[prefix.len() - 1].add_mod(x, modulus));
+    }
+    prefix
+}
+
+/// Compute the prefix products of a slice modulo a modulus, starting with the empty product:
+/// entry `i` is the product of `values[..i]`, so there are `values.len() + 1` entries
+/// and the product of `values[l..r]` is `range_product(&prefix, l, r, modulus)`.
+pub fn prefix_products_mod<T, M>(values: &[T], modulus: M) -> Vec<T>
+where
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
+{
+    let mut prefix = Vec::with_capacity(values.len() + 1);
+    prefix.push(T::one().constrain(modulus));
+    for &x in values {
+        prefix.push(prefix[prefix.len() - 1].mul_mod(x.constrain(modulus), modulus));
+    }
+    prefix
+}
+
+/// Get the sum of `values[l..r]` from the output of `prefix_sums_mod`.
+/// Panics if `l > r` or `r` is out of bounds.
+pub fn range_sum<T, M>(prefix: &[T], l: usize, r: usize, modulus: M) -> T
+where
+    T: Integer + TryFrom<M>,
+    M: Modulus<T>,
+{
+    assert!(l <= r, "the range is reversed");
+    prefix[r].sub_mod_constrained(prefix[l], modulus)
+}
+
+/// Get the product of `values[l..r]` from the output of `prefix_products_mod` by dividing out the prefix before `l`.
+/// Returns `None` if that prefix is not invertible, which modulo a prime happens when one of its values is zero.
+/// Panics if `l > r` or `r` is out of bounds.
+pub fn range_product<T, M>(prefix: &[T], l: usize, r: usize, modulus: M) -> Option<T>
+where
+    T: Invert + TryFrom<M>,
+    M: Modulus<T>,
+{
+    assert!(l <= r, "the range is reversed");
+    let inverse = prefix[l].invert(modulus)?;
+    Some(prefix[r].mul_mod(inverse, modulus))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SplitMix64;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_constrain_slice() {
+        let mut values = [-8i32, -1, 0, 6, 7, 15];
+        constrain_slice(&mut values, 7u32);
+        assert_eq!(values, [6, 6, 0, 6, 0, 1]);
+        let mut empty: [i32; 0] = [];
+        constrain_slice(&mut empty, 7u32);
+        let mut values: Vec<i64> = (-50_000..50_000).map(|x| x * 7919).collect();
+        let expected: Vec<i64> = values.iter().map(|x| x.rem_euclid(65_537)).collect();
+        constrain_slice(&mut values, 65_537u32);
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn test_slice_ops_signed() {
+        let mut values = [-3i64, 5, 12, -20];
+        add_mod_slice(&mut values, &[4, -6, 1, 0], 11u32);
+        assert_eq!(values, [1, 10, 2, 2]);
+        sub_mod_slice(&mut values, &[-1, 10, 5, 13], 11u32);
+        assert_eq!(values, [2, 0, 8, 0]);
+        mul_mod_slice(&mut values, &[-1, 3, -2, 4], 11u32);
+        assert_eq!(values, [9, 0, 6, 0]);
+        scale_mod_slice(&mut values, -3, 11u32);
+        assert_eq!(values, [6, 0, 4, 0]);
+        let mut empty: [i64; 0] = [];
+        add_mod_slice(&mut empty, &[], 11u32);
+        scale_mod_slice(&mut empty, 2, 11u32);
+    }
+
+    #[test]
+    fn test_slice_ops_per_element() {
+        let mut rng = SplitMix64::new(333);
+        let modulus = 1_000_000_007u64;
+        let a: Vec<i64> = (0..100)
+            .map(|_| rng.next_u64() as i64 % 2_000_000_000)
+            .collect();
+        let b: Vec<i64> = (0..100)
+            .map(|_| rng.next_u64() as i64 % 2_000_000_000)
+            .collect();
+        let check = |op: fn(&mut [i64], &[i64], u64), each: fn(i64, i64, u64) -> i64| {
+            let mut values = a.clone();
+            op(&mut values, &b, modulus);
+            let expected: Vec<i64> = a
+                .iter()
+                .zip(&b)
+                .map(|(&x, &y)| each(x, y, modulus))
+                .collect();
+            assert_eq!(values, expected);
+        };
+        check(add_mod_slice, |x, y, m| x.add_mod(y, m));
+        check(sub_mod_slice, |x, y, m| x.sub_mod(y, m));
+        check(mul_mod_slice, |x, y, m| {
+            x.constrain(m).mul_mod(y.constrain(m), m)
+        });
+        let mut values = a.clone();
+        scale_mod_slice(&mut values, b[0], modulus);
+        let expected: Vec<i64> = a
+            .iter()
+            .map(|&x| x.mul_mod(b[0].constrain(modulus), modulus))
+            .collect();
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "differ in length")]
+    fn test_slice_ops_mismatch() {
+        add_mod_slice(&mut [1u32, 2], &[3], 5u32);
+    }
+
+    #[test]
+    fn test_dot_mod() {
+        assert_eq!(dot_mod(&[1u32, 2, 3], &[4, 5, 6], 7u32), 4);
+        assert_eq!(dot_mod(&[-1i32, 2, -3], &[4, -5, 6], 7u32), 3);
+        assert_eq!(dot_mod::<u64, u64>(&[], &[], 7), 0);
+        assert_eq!(dot_mod(&[5u8], &[5], 1u8), 0);
+    }
+
+    #[test]
+    fn test_dot_mod_naive() {
+        let mut rng = SplitMix64::new(334);
+        for modulus in [2u64, 1_000_000_007, (1 << 62) + 135, u64::MAX - 58] {
+            for len in [1, 3, 100, 1_000] {
+                let a: Vec<u64> = (0..len).map(|_| rng.next_u64()).collect();
+                let b: Vec<u64> = (0..len).map(|_| rng.next_u64()).collect();
+                let naive = a.iter().zip(&b).fold(0u128, |acc, (&x, &y)| {
+                    let product = (x as u128).mul_mod(y as u128, modulus);
+                    (acc + product) % modulus as u128
+                });
+                assert_eq!(dot_mod(&a, &b, modulus) as u128, naive, "m = {modulus}");
+                if modulus <= i64::MAX as u64 {
+                    let m = modulus as i128;
+                    let a: Vec<i64> = a.iter().map(|&x| x as i64).collect();
+                    let b: Vec<i64> = b.iter().map(|&y| (y % modulus) as i64).collect();
+                    let expected = a.iter().zip(&b).fold(0i128, |acc, (&x, &y)| {
+                        (acc + (x as i128).rem_euclid(m) * y as i128) % m
+                    });
+                    assert_eq!(dot_mod(&a, &b, modulus) as i128, expected);
+                }
+            }
+        }
+        let small: Vec<u8> = (0..=255).collect();
+        let expected = small.iter().map(|&x| x as u64 * x as u64).sum::<u64>() % 251;
+        assert_eq!(dot_mod(&small, &small, 251u8) as u64, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "differ in length")]
+    fn test_dot_mod_mismatch() {
+        dot_mod(&[1u32, 2], &[3], 5u32);
+    }
+
+    #[test]
+    fn test_prefix_sums_mod() {
+        assert_eq!(prefix_sums_mod(&[3i32, -5, 9], 7u32), [0, 3, 5, 0]);
+        assert_eq!(prefix_sums_mod::<i32, u32>(&[], 7), [0]);
+        let mut rng = SplitMix64::new(335);
+        let modulus = 1_000_000_007u64;
+        let values: Vec<i64> = (0..60)
+            .map(|_| rng.next_u64() as i64 % 5_000_000_000)
+            .collect();
+        let prefix = prefix_sums_mod(&values, modulus);
+        for l in 0..=values.len() {
+            for r in l..=values.len() {
+                let expected = values[l..r].iter().sum::<i64>().rem_euclid(modulus as i64);
+                assert_eq!(range_sum(&prefix, l, r, modulus), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_prefix_products_mod() {
+        assert_eq!(prefix_products_mod(&[3i32, -5, 9], 7u32), [1, 3, 6, 5]);
+        assert_eq!(prefix_products_mod::<i32, u32>(&[], 1), [0]);
+        let mut rng = SplitMix64::new(1_000_000_007);
+        let modulus = 1_000_000_007u64;
+        let values: Vec<i64> = (0..60)
+            .map(|_| 1 + (rng.next_u64() % 1_000_000_006) as i64)
+            .map(|x| if x % 3 == 0 { -x } else { x })
+            .collect();
+        let prefix = prefix_products_mod(&values, modulus);
+        for l in 0..=values.len() {
+            for r in l..=values.len() {
+                let expected = values[l..r]
+                    .iter()
+                    .fold(1i64, |acc, &x| acc.mul_mod(x.constrain(modulus), modulus));
+                assert_eq!(range_product(&prefix, l, r, modulus), Some(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_range_product_zero() {
+        let prefix = prefix_products_mod(&[2i64, 7, 3, 4], 7u64);
+        assert_eq!(range_product(&prefix, 0, 2, 7u64), Some(0));
+        assert_eq!(range_product(&prefix, 2, 4, 7u64), None);
+        assert_eq!(range_product(&prefix, 1, 1, 7u64), Some(1));
+        let prefix = prefix_products_mod(&[2i64, 3, 5], 6u64);
+        assert_eq!(range_product(&prefix, 1, 3, 6u64), None);
+    }
+}
@@ -0,0 +1,577 @@
+use num_traits::{checked_pow, CheckedMul, FromPrimitive};
+
+use crate::integer::power;
+use crate::{
+    crt, hensel_lift, AddMod, Constrain, Integer, Invert, Modulus, MulMod, PowMod, SubMod,
+};
+
+/// A trait to compute square roots modulo a prime.
+pub trait SqrtMod<M>: Sized {
+    /// Compute the Legendre symbol `(self | p)` for a prime `p`.
+    /// Returns `0` if `self ≡ 0`, `1` if `self` is a quadratic residue, and `-1` otherwise.
+    fn legendre(self, p: M) -> i8;
+
+    /// Compute a square root of an integer modulo a prime `p`, that is, `r` such that `r * r ≡ self (mod p)`.
+    /// Of the two roots `r` and `p - r` the smaller one is returned.
+    /// If the integer is not a quadratic residue, return `None`.
+    /// Tonelli–Shanks is used unless `p - 1` is divisible by a large power of two, in which case Cipolla's algorithm is faster.
+    fn sqrt_mod(self, p: M) -> Option<Self>;
+
+    /// Compute a square root of an integer modulo a prime `p` with Cipolla's algorithm.
+    /// The result is the same as that of `sqrt_mod`.
+    fn sqrt_mod_cipolla(self, p: M) -> Option<Self>;
+}
+
+impl<T, M> SqrtMod<M> for T
+where
+    T: Integer + PartialOrd + TryFrom<M> + FromPrimitive,
+    M: Modulus<T>,
+{
+    fn legendre(self, p: M) -> i8 {
+        legendre(self, p)
+    }
+    fn sqrt_mod(self, p: M) -> Option<T> {
+        sqrt_mod(self, p, auto)
+    }
+    fn sqrt_mod_cipolla(self, p: M) -> Option<T> {
+        sqrt_mod(self, p, cipolla)
+    }
+}
+
+/// A trait to check whether an integer is a quadratic residue, that is, congruent to a square.
+/// Zero counts as a quadratic residue.
+pub trait QuadraticResidue<M>: Copy {
+    /// Check if an integer is a quadratic residue modulo a prime `p` using Euler's criterion.
+    fn is_quadratic_residue(self, p: M) -> bool;
+
+    /// Check if an integer is a quadratic residue modulo `n = p_1^k_1 · … · p_r^k_r`,
+    /// given the factorization of `n` as `(p_i, k_i)` pairs with distinct primes.
+    fn is_quadratic_residue_factored(self, factorization: &[(M, u32)]) -> bool;
+}
+
+impl<T, M> QuadraticResidue<M> for T
+where
+    T: Integer + PartialOrd + TryFrom<M> + FromPrimitive,
+    M: Modulus<T>,
+{
+    fn is_quadratic_residue(self, p: M) -> bool {
+        legendre(self, p) >= 0
+    }
+    fn is_quadratic_residue_factored(self, factorization: &[(M, u32)]) -> bool {
+        factorization
+            .iter()
+            .all(|&(p, k)| is_quadratic_residue_prime_power(self, p, k))
+    }
+}
+
+fn is_quadratic_residue_prime_power<T, M>(a: T, p: M, k: u32) -> bool
+where
+    T: Integer + PartialOrd + TryFrom<M> + FromPrimitive,
+    M: Modulus<T>,
+{
+    let q: T = p.cast();
+    let a = a.constrain(power(p, k));
+    if a == T::zero() {
+        return true;
+    }
+    // Write a = p^e * b with b coprime to p; a is a residue if and only if e is even
+    // and b is a residue modulo p^(k - e).
+    let (mut b, mut e) = (a, 0);
+    while b % q == T::zero() {
+        b = b / q;
+        e += 1;
+    }
+    if e % 2 == 1 {
+        return false;
+    }
+    let two = T::one() + T::one();
+    let four = two + two;
+    match (q == two, k - e) {
+        (false, _) => legendre(b, p) == 1,
+        (true, 1) => true,
+        (true, 2) => b % four == T::one(),
+        (true, _) => b % (four + four) == T::one(),
+    }
+}
+
+/// Compute all square roots of `a` modulo the prime power `p^k` in increasing order.
+/// Roots modulo `p` are lifted to `p^k` with Hensel's lemma, with the special rules for powers of two
+/// (odd squares are `1 (mod 8)` and have four roots modulo `2^k` for `k ≥ 3`).
+/// When `p` divides `a` the common power of `p` is factored out first, which may yield more than two roots.
+/// The modulus `p` must be prime and `p^k` must fit in both `T` and `M`.
+pub fn sqrt_mod_prime_power<T, M>(a: T, p: M, k: u32) -> Vec<T>
+where
+    T: Invert + PartialOrd + TryFrom<M> + FromPrimitive,
+    M: Modulus<T>,
+{
+    let pk = power(p, k);
+    let n: T = pk.cast();
+    let q: T = p.cast();
+    let a = a.constrain(pk);
+    if a == T::zero() {
+        // The roots of zero are the multiples of p^ceil(k / 2).
+        let step: T = power(p, k.div_ceil(2)).cast();
+        return successors(T::zero(), step, n);
+    }
+
+    // Write a = p^e * b with b coprime to p.
+    let (mut b, mut e) = (a, 0);
+    while b % q == T::zero() {
+        b = b / q;
+        e += 1;
+    }
+    if e % 2 == 1 {
+        return Vec::new();
+    }
+    // x = p^j * y with y^2 ≡ b (mod p^(k - e)), and y is determined modulo p^(k - j).
+    let j = e / 2;
+    let scale: T = power(p, j).cast();
+    let step: T = power(p, k - e).cast();
+    let mut roots: Vec<T> = unit_roots(b, p, k - e)
+        .into_iter()
+        .flat_map(|y| successors(y, step, step * scale))
+        .map(|y| y * scale)
+        .collect();
+    roots.sort_by(|x, y| x.partial_cmp(y).expect("roots are comparable"));
+    roots
+}
+
+/// Compute all square roots of `a` modulo `n = p_1^k_1 · … · p_r^k_r` in increasing order,
+/// given the factorization of `n` as `(p_i, k_i)` pairs with distinct primes.
+/// The roots modulo each prime power are combined with the Chinese Remainder Theorem,
+/// so there can be more than two of them.
+/// Returns `None` if `n` does not fit in both `T` and `M`, or if `crt` overflows while combining the roots.
+pub fn sqrt_mod_composite<T, M>(a: T, factorization: &[(M, u32)]) -> Option<Vec<T>>
+where
+    T: Invert + PartialOrd + TryFrom<M> + FromPrimitive + CheckedMul,
+    M: Modulus<T> + TryFrom<T> + CheckedMul,
+{
+    let mut roots = vec![T::zero()];
+    let mut modulus = M::one();
+    for &(p, k) in factorization {
+        let pk = checked_pow(p, k as usize)?;
+        modulus = modulus.checked_mul(&pk)?;
+        modulus.try_cast().ok()?;
+        let local = sqrt_mod_prime_power(a, p, k);
+        let previous = modulus / pk;
+        roots = roots
+            .iter()
+            .flat_map(|&x| local.iter().map(move |&y| (x, y)))
+            .map(|(x, y)| crt(x, previous, y, pk).map(|(root, _)| root))
+            .collect::<Option<_>>()?;
+    }
+    roots.sort_by(|x, y| x.partial_cmp(y).expect("roots are comparable"));
+    Some(roots)
+}
+
+/// Compute the square roots of `b` modulo `p^k`, where `b` is coprime to `p`.
+fn unit_roots<T, M>(b: T, p: M, k: u32) -> Vec<T>
+where
+    T: Invert + PartialOrd + TryFrom<M> + FromPrimitive,
+    M: Modulus<T>,
+{
+    let pk = power(p, k);
+    let n: T = pk.cast();
+    let b = b.constrain(pk);
+    let one = T::one();
+    let two = one + one;
+    let four = two + two;
+    let eight = four + four;
+    if p.cast() != two {
+        let Some(r) = b.sqrt_mod(p) else {
+            return Vec::new();
+        };
+        let r = hensel_lift(|x| x.mul_mod(x, pk).sub_mod(b, pk), |x| x + x, r, p, k)
+            .expect("root is simple");
+        return if r == n - r { vec![r] } else { vec![r, n - r] };
+    }
+    match k {
+        0 | 1 => vec![b],
+        2 if b == one => vec![one, two + one],
+        2 => Vec::new(),
+        _ if b % eight != one => Vec::new(),
+        _ => {
+            // Lift r^2 ≡ b from modulo 2^i to 2^(i + 1); the correction term is 2^(i - 1).
+            let mut r = one;
+            let mut modulus = eight;
+            let mut correction = four;
+            for _ in 3..k {
+                modulus = modulus * two;
+                if (r * r - b) % modulus != T::zero() {
+                    r = r + correction;
+                }
+                correction = correction * two;
+            }
+            // correction is now 2^(k - 1).
+            let mut roots = vec![r, n - r, (r + correction) % n, (n - r + correction) % n];
+            roots.sort_by(|x, y| x.partial_cmp(y).expect("roots are comparable"));
+            roots
+        }
+    }
+}
+
+/// Collect `start, start + step, …` below `end`.
+fn successors<T: Integer + PartialOrd>(start: T, step: T, end: T) -> Vec<T> {
+    std::iter::successors(Some(start), |&x| Some(x + step))
+        .take_while(|&x| x < end)
+        .collect()
+}
+
+/// Handle the cases shared by all algorithms and return the smaller root.
+/// The `search` function is only called for nonzero quadratic residues modulo odd primes.
+fn sqrt_mod<T, M>(a: T, p: M, search: fn(T, M) -> T) -> Option<T>
+where
+    T: Integer + PartialOrd + TryFrom<M> + FromPrimitive,
+    M: Modulus<T>,
+{
+    let n: T = p.cast();
+    let a = a.constrain(p);
+    if a == T::zero() || n == T::one() + T::one() {
+        return Some(a);
+    }
+    if legendre(a, p) != 1 {
+        return None;
+    }
+    let root = search(a, p);
+    Some(if n - root < root { n - root } else { root })
+}
+
+/// Choose between Tonelli–Shanks and Cipolla's algorithm based on the 2-adic valuation `s` of `p - 1`.
+/// Tonelli–Shanks takes up to `O(s^2)` multiplications while Cipolla's algorithm always takes `O(log p)`.
+fn auto<T, M>(a: T, p: M) -> T
+where
+    T: Integer + TryFrom<M> + FromPrimitive,
+    M: Modulus<T>,
+{
+    let n: T = p.cast();
+    let two = T::one() + T::one();
+    let four = two + two;
+    if n % four == two + T::one() {
+        return a.pow_mod((n - two - T::one()) / four + T::one(), p);
+    }
+    let (mut q, mut s) = (n - T::one(), 0u32);
+    while q % two == T::zero() {
+        q = q / two;
+        s += 1;
+    }
+    let (mut rest, mut bits) = (n, 0u32);
+    while rest != T::zero() {
+        rest = rest / two;
+        bits += 1;
+    }
+    if s * s > 4 * bits {
+        cipolla(a, p)
+    } else {
+        tonelli_shanks(a, p)
+    }
+}
+
+fn legendre<T, M>(a: T, p: M) -> i8
+where
+    T: Integer + TryFrom<M> + FromPrimitive,
+    M: Modulus<T>,
+{
+    let a = a.constrain(p);
+    if a == T::zero() {
+        return 0;
+    }
+    let two = T::one() + T::one();
+    if a.pow_mod((p.cast() - T::one()) / two, p) == T::one() {
+        1
+    } else {
+        -1
+    }
+}
+
+/// Find a square root of the quadratic residue `a` with the Tonelli–Shanks algorithm.
+fn tonelli_shanks<T, M>(a: T, p: M) -> T
+where
+    T: Integer + TryFrom<M> + FromPrimitive,
+    M: Modulus<T>,
+{
+    let n: T = p.cast();
+    let two = T::one() + T::one();
+
+    // Write p - 1 = q * 2^s with q odd.
+    let mut q = n - T::one();
+    let mut s = 0;
+    while q % two == T::zero() {
+        q = q / two;
+        s += 1;
+    }
+    let mut z = two;
+    while legendre(z, p) != -1 {
+        z = z + T::one();
+    }
+
+    let mut c = z.pow_mod(q, p);
+    let mut r = a.pow_mod((q + T::one()) / two, p);
+    let mut t = a.pow_mod(q, p);
+    while t != T::one() {
+        // Find the least i such that t^(2^i) = 1.
+        let mut i = 0;
+        let mut square = t;
+        while square != T::one() {
+            square = square.mul_mod(square, p);
+            i += 1;
+        }
+        let mut b = c;
+        for _ in 0..s - i - 1 {
+            b = b.mul_mod(b, p);
+        }
+        r = r.mul_mod(b, p);
+        c = b.mul_mod(b, p);
+        t = t.mul_mod(c, p);
+        s = i;
+    }
+    r
+}
+
+/// Find a square root of the quadratic residue `a` with Cipolla's algorithm, that is,
+/// as `(t + x)^((p + 1) / 2)` in `F_p[x] / (x^2 - w)` where `w = t^2 - a` is a non-residue.
+fn cipolla<T, M>(a: T, p: M) -> T
+where
+    T: Integer + TryFrom<M> + FromPrimitive,
+    M: Modulus<T>,
+{
+    let n: T = p.cast();
+    let two = T::one() + T::one();
+    let mut t = T::one();
+    let mut w = t.sub_mod(a, p);
+    while legendre(w, p) != -1 {
+        t = t + T::one();
+        w = t.mul_mod(t, p).sub_mod(a, p);
+    }
+
+    let multiply = |(a0, a1): (T, T), (b0, b1): (T, T)| {
+        let c0 = a0
+            .mul_mod(b0, p)
+            .add_mod(a1.mul_mod(b1, p).mul_mod(w, p), p);
+        let c1 = a0.mul_mod(b1, p).add_mod(a1.mul_mod(b0, p), p);
+        (c0, c1)
+    };
+    let mut result = (T::one(), T::zero());
+    let mut base = (t, T::one());
+    let mut exponent = (n - T::one()) / two + T::one();
+    while exponent != T::zero() {
+        if exponent % two == T::one() {
+            result = multiply(result, base);
+        }
+        base = multiply(base, base);
+        exponent = exponent / two;
+    }
+    result.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_legendre() {
+        let residues = [1, 3, 4, 5, 9];
+        for a in -22i32..22 {
+            let expected = match a.rem_euclid(11) {
+                0 => 0,
+                r if residues.contains(&r) => 1,
+                _ => -1,
+            };
+            assert_eq!(a.legendre(11u8), expected, "a = {a}");
+        }
+    }
+
+    #[test]
+    fn test_is_quadratic_residue() {
+        let residues: Vec<i32> = (0..11).filter(|a| a.is_quadratic_residue(11u8)).collect();
+        assert_eq!(residues, vec![0, 1, 3, 4, 5, 9]);
+        assert!((-2).is_quadratic_residue(11u8));
+        assert!(!(-1).is_quadratic_residue(11u8));
+        for p in [2u32, 3, 5, 7, 13, 17, 101] {
+            let n = p as i64;
+            for a in -n..n {
+                let expected = (0..n).any(|x| (x * x - a).rem_euclid(n) == 0);
+                assert_eq!(a.is_quadratic_residue(p), expected, "a = {a}, p = {p}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_quadratic_residue_factored() {
+        for factorization in [
+            vec![(3u32, 3)],
+            vec![(2, 5)],
+            vec![(5, 2)],
+            vec![(2, 2), (3, 2), (5, 1)],
+            vec![(7, 1), (2, 1)],
+        ] {
+            let n: i64 = factorization
+                .iter()
+                .map(|&(p, k)| p.pow(k) as i64)
+                .product();
+            for a in -n..n {
+                let expected = (0..n).any(|x| (x * x - a).rem_euclid(n) == 0);
+                assert_eq!(
+                    a.is_quadratic_residue_factored(&factorization),
+                    expected,
+                    "a = {a}, n = {n}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_sqrt_mod_small_primes() {
+        for p in [2u32, 3, 5, 7, 11, 13, 17, 41, 97, 193, 257, 7681] {
+            let n = p as i64;
+            let mut roots = 0;
+            for a in 0..n {
+                match a.sqrt_mod(p) {
+                    Some(r) => {
+                        assert_eq!((r * r) % n, a, "p = {p}, a = {a}");
+                        assert!(r <= n - r);
+                        roots += 1;
+                    }
+                    None => assert_eq!(a.legendre(p), -1),
+                }
+            }
+            assert_eq!(roots, if p == 2 { 2 } else { (n + 1) / 2 });
+            for x in 0..n {
+                let r = (x * x % n).sqrt_mod(p).unwrap();
+                assert!(r == x || r == n - x);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sqrt_mod_negative() {
+        assert_eq!((-1).sqrt_mod(13u8), Some(5));
+        assert_eq!((-1).sqrt_mod(11u8), None);
+        assert_eq!((-2).sqrt_mod(11u8), Some(3));
+    }
+
+    #[test]
+    fn test_cipolla_agrees_with_tonelli_shanks() {
+        for p in [5u32, 13, 17, 97, 7681, 12289, 65537] {
+            for a in 1..p.min(2000) as i64 {
+                if a.legendre(p) == 1 {
+                    let r = a.sqrt_mod_cipolla(p).unwrap();
+                    let s = tonelli_shanks(a, p);
+                    assert!(r == s || r == p as i64 - s, "p = {p}, a = {a}");
+                    assert_eq!(Some(r), a.sqrt_mod(p));
+                } else {
+                    assert_eq!(a.sqrt_mod_cipolla(p), None);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sqrt_mod_high_two_adic_valuation() {
+        // 3 * 2^30 + 1 and 5 * 2^55 + 1 are the worst cases for Tonelli–Shanks.
+        for p in [3_221_225_473u64, 180_143_985_094_819_841] {
+            let n = p as i128;
+            for x in [2i128, 3, 1_000_003, n / 7, n - 5] {
+                let square = x * x % n;
+                let r = square.sqrt_mod(p).unwrap();
+                assert!(r == x || r == n - x);
+                assert_eq!(square.sqrt_mod_cipolla(p), Some(r));
+                let s = tonelli_shanks(square, p);
+                assert!(s == r || s == n - r);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sqrt_mod_prime_power() {
+        assert_eq!(sqrt_mod_prime_power(2, 7u32, 3), vec![108, 235]);
+        assert_eq!(sqrt_mod_prime_power(-1, 5u32, 2), vec![7, 18]);
+        assert_eq!(sqrt_mod_prime_power(3, 5u32, 2), Vec::<i64>::new());
+        assert_eq!(sqrt_mod_prime_power(0, 3u32, 3), vec![0, 9, 18]);
+        assert_eq!(sqrt_mod_prime_power(9, 3u32, 3), vec![3, 6, 12, 15, 21, 24]);
+        assert_eq!(sqrt_mod_prime_power(3, 3u32, 3), Vec::<i64>::new());
+        assert_eq!(sqrt_mod_prime_power(5, 3u32, 0), vec![0]);
+    }
+
+    #[test]
+    fn test_sqrt_mod_powers_of_two() {
+        assert_eq!(sqrt_mod_prime_power(1, 2u32, 1), vec![1]);
+        assert_eq!(sqrt_mod_prime_power(1, 2u32, 2), vec![1, 3]);
+        assert_eq!(sqrt_mod_prime_power(3, 2u32, 2), Vec::<i32>::new());
+        assert_eq!(sqrt_mod_prime_power(1, 2u32, 3), vec![1, 3, 5, 7]);
+        assert_eq!(sqrt_mod_prime_power(5, 2u32, 3), Vec::<i32>::new());
+        assert_eq!(sqrt_mod_prime_power(17, 2u32, 5), vec![7, 9, 23, 25]);
+        assert_eq!(sqrt_mod_prime_power(4, 2u32, 4), vec![2, 6, 10, 14]);
+    }
+
+    #[test]
+    fn test_sqrt_mod_prime_power_exhaustive() {
+        for (p, k) in [
+            (2u32, 1),
+            (2, 2),
+            (2, 3),
+            (2, 4),
+            (2, 6),
+            (3, 1),
+            (3, 4),
+            (5, 3),
+            (7, 2),
+            (11, 2),
+        ] {
+            let n = p.pow(k) as i64;
+            for a in 0..n {
+                let expected: Vec<i64> = (0..n).filter(|x| x * x % n == a).collect();
+                assert_eq!(
+                    sqrt_mod_prime_power(a, p, k),
+                    expected,
+                    "a = {a}, p = {p}, k = {k}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_sqrt_mod_composite() {
+        let factorization = [(2u32, 3), (3, 1)];
+        assert_eq!(
+            sqrt_mod_composite(1, &factorization),
+            Some(vec![1, 5, 7, 11, 13, 17, 19, 23])
+        );
+        assert_eq!(
+            sqrt_mod_composite(4, &[(3u32, 1), (5, 1)]),
+            Some(vec![2, 7, 8, 13])
+        );
+        assert_eq!(
+            sqrt_mod_composite(2, &[(3u32, 1), (5, 1)]),
+            Some(Vec::<i32>::new())
+        );
+        assert_eq!(sqrt_mod_composite::<i32, u32>(7, &[]), Some(vec![0]));
+        let factorization = [(2u32, 2), (3, 2), (5, 1)];
+        for a in 0..180i64 {
+            let expected: Vec<i64> = (0..180).filter(|x| x * x % 180 == a).collect();
+            assert_eq!(sqrt_mod_composite(a, &factorization), Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_sqrt_mod_composite_overflow() {
+        let factorization = [(65537u64, 1), (65539, 1), (65543, 1), (65551, 1)];
+        assert_eq!(sqrt_mod_composite(4i64, &factorization), None);
+        assert_eq!(sqrt_mod_composite(4i64, &[(3u64, 40)]), None);
+        assert_eq!(sqrt_mod_composite(4i8, &[(3u8, 5)]), None);
+        assert_eq!(sqrt_mod_composite(4i8, &[(3u8, 2)]), Some(vec![2, 7]));
+    }
+
+    #[test]
+    fn test_sqrt_mod_large_primes() {
+        for p in [(1u64 << 61) - 1, 2_305_843_009_213_693_921] {
+            let n = p as i128;
+            for x in [2i128, 3, 12_345_678_901, n / 3, n - 2] {
+                let square = x * x % n;
+                let r = square.sqrt_mod(p).unwrap();
+                assert!(r == x || r == n - x);
+                assert!(r <= n - r);
+            }
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use num_traits::Signed;
+
+use crate::Integer;
+
+/// Compute the Jacobi symbol `(a | n)` for an odd positive `n`.
+/// It extends the Legendre symbol to odd composite `n` and is computed with quadratic reciprocity
+/// instead of exponentiation. Negative `a` is handled with the `(-1 | n)` rule.
+/// Returns `None` if `n` is even or not positive.
+pub fn jacobi<T>(a: T, n: T) -> Option<i8>
+where
+    T: Integer + Signed + PartialOrd,
+{
+    let two = T::one() + T::one();
+    if n <= T::zero() || n % two == T::zero() {
+        return None;
+    }
+    // Reducing first keeps the negation below from overflowing.
+    let a = a % n;
+    if a < T::zero() {
+        Some(minus_one_symbol(n) * jacobi_odd(-a, n))
+    } else {
+        Some(jacobi_odd(a, n))
+    }
+}
+
+/// Compute the Kronecker symbol `(a | n)`, which extends the Jacobi symbol to all integers `n`.
+/// The conventions are `(a | 0) = 1` if `a = ±1` and `0` otherwise, `(a | -1) = -1` if `a < 0` and `1` otherwise,
+/// and `(a | 2) = 0` for even `a`, `1` if `a ≡ ±1 (mod 8)`, and `-1` if `a ≡ ±3 (mod 8)`.
+pub fn kronecker<T>(a: T, n: T) -> i8
+where
+    T: Integer + Signed + PartialOrd,
+{
+    if n == T::zero() {
+        return if a == T::one() || a == -T::one() {
+            1
+        } else {
+            0
+        };
+    }
+    let two = T::one() + T::one();
+    let eight = two * two * two;
+    let mut result = 1;
+    let mut n = n;
+    // Factoring out the powers of two before the sign keeps the negation from overflowing.
+    while n % two == T::zero() {
+        if a % two == T::zero() {
+            return 0;
+        }
+        result *= two_symbol(a % eight + eight);
+        n = n / two;
+    }
+    if n < T::zero() {
+        n = -n;
+        if a < T::zero() {
+            result = -result;
+        }
+    }
+    result * jacobi(a, n).expect("n is odd and positive")
+}
+
+/// Compute `(-1 | n)` for an odd positive `n`, which is `1` if `n ≡ 1 (mod 4)` and `-1` otherwise.
+fn minus_one_symbol<T: Integer>(n: T) -> i8 {
+    let four = (T::one() + T::one()) * (T::one() + T::one());
+    if n % four == T::one() {
+        1
+    } else {
+        -1
+    }
+}
+
+/// Compute `(2 | n)` for an odd positive `n`, which is `1` if `n ≡ ±1 (mod 8)` and `-1` otherwise.
+fn two_symbol<T: Integer>(n: T) -> i8 {
+    let two = T::one() + T::one();
+    let eight = two * two * two;
+    let r = n % eight;
+    if r == T::one() || r == eight - T::one() {
+        1
+    } else {
+        -1
+    }
+}
+
+/// The Jacobi symbol loop for a non-negative `a` and an odd positive `n`.
+fn jacobi_odd<T: Integer>(mut a: T, mut n: T) -> i8 {
+    let two = T::one() + T::one();
+    let three = two + T::one();
+    let four = two + two;
+    let mut result = 1;
+    a = a % n;
+    while a != T::zero() {
+        while a % two == T::zero() {
+            a = a / two;
+            result *= two_symbol(n);
+        }
+        std::mem::swap(&mut a, &mut n);
+        if a % four == three && n % four == three {
+            result = -result;
+        }
+        a = a % n;
+    }
+    if n == T::one() {
+        result
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SqrtMod;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_jacobi_matches_legendre() {
+        for p in [3u32, 5, 7, 11, 13, 97, 101, 1009] {
+            let n = p as i64;
+            for a in -n..2 * n {
+                assert_eq!(jacobi(a, n), Some(a.legendre(p)), "a = {a}, p = {p}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_jacobi_composite() {
+        assert_eq!(jacobi(1001, 9907), Some(-1));
+        assert_eq!(jacobi(19, 45), Some(1));
+        assert_eq!(jacobi(8, 21), Some(-1));
+        assert_eq!(jacobi(5, 21), Some(1));
+        assert_eq!(jacobi(3, 9), Some(0));
+        assert_eq!(jacobi(0, 1), Some(1));
+        assert_eq!(jacobi(i64::MIN, 7), Some(-1));
+    }
+
+    #[test]
+    fn test_jacobi_identities() {
+        for n in (1..200i32).step_by(2) {
+            let two = if n % 8 == 1 || n % 8 == 7 { 1 } else { -1 };
+            let minus_one = if n % 4 == 1 { 1 } else { -1 };
+            assert_eq!(jacobi(2, n), Some(two));
+            assert_eq!(jacobi(-1, n), Some(minus_one));
+        }
+    }
+
+    #[test]
+    fn test_kronecker_matches_jacobi() {
+        for n in (1..100i32).step_by(2) {
+            for a in -50..50 {
+                assert_eq!(Some(kronecker(a, n)), jacobi(a, n));
+            }
+        }
+    }
+
+    #[test]
+    fn test_kronecker_zero() {
+        for a in -10..10 {
+            let expected = if a == 1 || a == -1 { 1 } else { 0 };
+            assert_eq!(kronecker(a, 0), expected);
+        }
+        assert_eq!(kronecker(i64::MIN, 0), 0);
+        assert_eq!(kronecker(i64::MAX, 0), 0);
+        assert_eq!(kronecker(i8::MIN, 0), 0);
+    }
+
+    #[test]
+    fn test_kronecker_two() {
+        for a in -40i32..40 {
+            let expected = match a.rem_euclid(8) {
+                1 | 7 => 1,
+                3 | 5 => -1,
+                _ => 0,
+            };
+            assert_eq!(kronecker(a, 2), expected, "a = {a}");
+        }
+        assert_eq!(kronecker(5, 12), -1);
+        assert_eq!(kronecker(3, 8), -1);
+        assert_eq!(kronecker(7, 16), 1);
+    }
+
+    #[test]
+    fn test_kronecker_negative() {
+        for a in -10..10 {
+            let expected = if a < 0 { -1 } else { 1 };
+            assert_eq!(kronecker(a, -1), expected);
+        }
+        assert_eq!(kronecker(-5, -3), -1);
+        assert_eq!(kronecker(5, -3), -1);
+        assert_eq!(kronecker(-1, -3), 1);
+        assert_eq!(kronecker(3, i64::MIN), -1);
+        assert_eq!(kronecker(2, i64::MIN), 0);
+        assert_eq!(kronecker(-1, i64::MIN), -1);
+        assert_eq!(kronecker(3, i64::MIN + 1), kronecker(3, -(i64::MIN + 1)));
+    }
+
+    #[test]
+    fn test_jacobi_invalid() {
+        assert_eq!(jacobi(3, 8), None);
+        assert_eq!(jacobi(3, 0), None);
+        assert_eq!(jacobi(3, -7), None);
+    }
+}
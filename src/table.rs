@@ -0,0 +1,92 @@
+use num_traits::{FromPrimitive, ToPrimitive};
+
+use crate::{Integer, Modulus, MulMod};
+
+/// A table of the inverses of all integers in `1..=n` modulo a prime.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InverseTable<T> {
+    // Index 0 is a placeholder so that the inverse of `i` lives at index `i`.
+    inverses: Vec<T>,
+}
+
+#[cfg(feature = "zeroize")]
+impl<T> Drop for InverseTable<T> {
+    fn drop(&mut self) {
+        crate::wipe::wipe_vec(&mut self.inverses);
+    }
+}
+
+impl<T: Integer> InverseTable<T> {
+    /// Build the table in `O(n)` using the recurrence `inv[i] = -(p / i) * inv[p % i]`.
+    /// Returns `None` if `n >= p` or if any entry fails validation, which happens when `p` is not prime.
+    pub fn new<M>(n: usize, p: M) -> Option<Self>
+    where
+        T: TryFrom<M> + FromPrimitive + ToPrimitive,
+        M: Modulus<T>,
+    {
+        let modulus: T = p.cast();
+        if modulus.to_usize().is_some_and(|p| n >= p) {
+            return None;
+        }
+        let mut inverses = Vec::with_capacity(n + 1);
+        inverses.push(T::zero());
+        for i in 1..=n {
+            let value = T::from_usize(i)?;
+            let inverse = if i == 1 {
+                T::one()
+            } else {
+                let index = (modulus % value).to_usize()?;
+                (modulus - modulus / value).mul_mod(inverses[index], p)
+            };
+            if value.mul_mod(inverse, p) != T::one() {
+                return None;
+            }
+            inverses.push(inverse);
+        }
+        Some(Self { inverses })
+    }
+
+    /// Get the inverse of `i`.
+    /// Panics if `i` is zero or greater than the size of the table.
+    pub fn inv(&self, i: usize) -> T {
+        assert!(i != 0, "zero has no inverse");
+        self.inverses[i]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Invert;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_inverse_table() {
+        let table = InverseTable::<i64>::new(96, 97u32).unwrap();
+        for i in 1..97 {
+            assert_eq!(Some(table.inv(i)), (i as i64).invert(97u32));
+        }
+    }
+
+    #[test]
+    fn test_inverse_table_unsigned() {
+        let table = InverseTable::<u64>::new(10, 1_000_000_007u64).unwrap();
+        assert_eq!(table.inv(1), 1);
+        assert_eq!(table.inv(2), 500_000_004);
+        assert_eq!(table.inv(10), 700_000_005);
+    }
+
+    #[test]
+    fn test_inverse_table_rejects() {
+        assert_eq!(InverseTable::<u32>::new(11, 11u32), None);
+        assert_eq!(InverseTable::<u32>::new(20, 11u32), None);
+        assert_eq!(InverseTable::<u32>::new(10, 15u32), None);
+        assert!(InverseTable::<u32>::new(2, 15u32).is_some());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_inverse_table_out_of_range() {
+        InverseTable::<u32>::new(5, 11u32).unwrap().inv(6);
+    }
+}
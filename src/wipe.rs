@@ -0,0 +1,92 @@
+//! Wiping secret-derived values from memory with the `zeroize` feature.
+//! Every write is volatile and followed by a compiler fence, so the compiler cannot drop it as a dead store.
+
+use std::sync::atomic::{compiler_fence, Ordering};
+
+use num_traits::Zero;
+
+use crate::{Integer, ModInt, Montgomery, Residue};
+
+/// A trait to overwrite a value that may hold secret data with zero.
+/// The wrapper types are `Copy`, so they cannot wipe themselves on drop: copies may exist anywhere.
+/// Wipe each copy explicitly once it is no longer needed. The precomputed tables wipe themselves on drop.
+pub trait Wipe {
+    /// Overwrite the secret parts of the value with zero.
+    fn wipe(&mut self);
+}
+
+impl<T: Integer, M> Wipe for Residue<T, M> {
+    /// Set the value to zero, which keeps the residue valid; the modulus is treated as public.
+    fn wipe(&mut self) {
+        wipe_value(self.value_mut());
+    }
+}
+
+impl<const M: u64> Wipe for ModInt<M> {
+    /// Set the value to zero, which keeps the residue valid.
+    fn wipe(&mut self) {
+        wipe_value(self.value_mut());
+    }
+}
+
+impl<T: Zero + Copy> Wipe for Montgomery<T> {
+    /// Set every constant to zero. The context must not be used afterwards.
+    fn wipe(&mut self) {
+        for x in self.constants_mut() {
+            wipe_value(x);
+        }
+    }
+}
+
+/// Overwrite a value with zero.
+pub(crate) fn wipe_value<T: Zero>(x: &mut T) {
+    // SAFETY: `x` is a valid, aligned and exclusive reference, and zero is a valid `T`.
+    unsafe { std::ptr::write_volatile(x, T::zero()) };
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// Empty a vector and overwrite its whole allocation, including any spare capacity, with zero bytes.
+pub(crate) fn wipe_vec<T>(values: &mut Vec<T>) {
+    values.clear();
+    let bytes = values.capacity() * std::mem::size_of::<T>();
+    let ptr = values.as_mut_ptr().cast::<u8>();
+    for i in 0..bytes {
+        // SAFETY: the allocation holds `bytes` bytes, and after `clear` they are uninitialized memory
+        // that may hold any bit pattern.
+        unsafe { ptr.add(i).write_volatile(0) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_wipe_wrappers() {
+        let mut r = Residue::new(-3i64, 7u32);
+        r.wipe();
+        assert_eq!(r, Residue::new(0, 7));
+        let mut a = ModInt::<998_244_353>::new(12345);
+        a.wipe();
+        assert_eq!(a.value(), 0);
+        let mut context = Montgomery::<u64>::new(1_000_000_007).unwrap();
+        context.wipe();
+        assert!(context.constants_mut().iter().all(|x| **x == 0));
+    }
+
+    #[test]
+    fn test_wipe_vec() {
+        let mut values: Vec<u64> = Vec::with_capacity(16);
+        values.extend([u64::MAX; 16]);
+        values.truncate(10);
+        let (ptr, capacity) = (values.as_ptr().cast::<u8>(), values.capacity());
+        wipe_vec(&mut values);
+        assert!(values.is_empty());
+        assert_eq!(values.capacity(), capacity);
+        // SAFETY: the allocation is still owned by `values` and every byte of it was just written.
+        let bytes = unsafe { std::slice::from_raw_parts(ptr, capacity * 8) };
+        assert!(bytes.iter().all(|&b| b == 0));
+    }
+}
@@ -0,0 +1,48 @@
+use modicum::*;
+use pretty_assertions::assert_eq;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, ModicumInteger)]
+#[modicum(signed)]
+struct Coeff(i64);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, ModicumInteger)]
+#[modicum(unsigned)]
+pub struct Order(pub u64);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, ModicumInteger)]
+struct Count(u32);
+
+#[test]
+fn test_derive_signed() {
+    let modulus = 13u32;
+    assert_eq!(Coeff(-3).constrain(modulus), Coeff(10));
+    assert_eq!(Coeff(9).add_mod(Coeff(7), modulus), Coeff(3));
+    assert_eq!(Coeff(2).sub_mod(Coeff(5), modulus), Coeff(10));
+    assert_eq!(Coeff(5).mul_mod(Coeff(-4), modulus), Coeff(6));
+    assert_eq!(Coeff(3).div_mod(Coeff(4), modulus), Some(Coeff(4)));
+    assert_eq!(Coeff(3).div_mod(Coeff(13), modulus), None);
+    assert_eq!(Coeff(2).pow_mod(Coeff(10), modulus), Coeff(10));
+    assert_eq!(Coeff(4).invert(modulus), Some(Coeff(10)));
+    assert_eq!(Coeff(12).egcd(Coeff(18)).0, Coeff(6));
+    assert_eq!(Coeff(-12).gcd(Coeff(18)), Coeff(6));
+    assert_eq!(Coeff(i64::MAX).lcm(Coeff(2)), None);
+    assert!(Coeff(-1).eq_mod(Coeff(12), modulus));
+}
+
+#[test]
+fn test_derive_unsigned() {
+    let modulus = Order(1_000_000_007);
+    assert_eq!(Order(1_000_000_008).constrain(modulus), Order(1));
+    assert_eq!(Order(3).pow_mod(Order(1_000_000_006), modulus), Order(1));
+    assert_eq!(7u64.mul_mod(8, modulus), 56);
+}
+
+#[test]
+fn test_derive_unspecified() {
+    assert_eq!(Count(9).add_mod(Count(9), 10u8), Count(8));
+    assert_eq!(Count(3).pow_mod(Count(4), 7u32), Count(4));
+    assert_eq!(
+        <Count as num_traits::Num>::from_str_radix("ff", 16),
+        Ok(Count(255))
+    );
+}
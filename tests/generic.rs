@@ -0,0 +1,30 @@
+use modicum::*;
+use pretty_assertions::assert_eq;
+
+// Generic helpers need only the trait they call, with no bounds on conversion errors.
+
+fn sum_of_squares<T: AddMod<u64, Output = T> + MulMod<u64, Output = T> + Copy>(
+    a: T,
+    b: T,
+    m: u64,
+) -> T {
+    a.mul_mod(a, m).add_mod(b.mul_mod(b, m), m)
+}
+
+fn all_congruent<T: EqMod<M> + Copy, M: Copy>(values: &[T], m: M) -> bool {
+    values.windows(2).all(|w| w[0].eq_mod(w[1], m))
+}
+
+fn checked_square<T: CheckedMod<u32> + Copy>(a: T, m: u32) -> Result<T, ModError> {
+    a.checked_mul_mod(a, m)
+}
+
+#[test]
+fn test_generic_helpers() {
+    assert_eq!(sum_of_squares(3i64, 4, 7), 4);
+    assert_eq!(sum_of_squares(3u128, 4, 20), 5);
+    assert!(all_congruent(&[-1i32, 6, 13], 7u8));
+    assert!(!all_congruent(&[1u64, 2], 7u64));
+    assert_eq!(checked_square(5i64, 7), Ok(4));
+    assert_eq!(checked_square(5u8, 300), Err(ModError::ModulusTooLarge));
+}